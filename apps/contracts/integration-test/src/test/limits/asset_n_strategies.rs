@@ -120,7 +120,7 @@ fn hodl() {
 
     // Rebalance
     setup.env.cost_estimate().budget().reset_unlimited();
-    vault_contract.rebalance(&manager, &invest_instructions);
+    vault_contract.rebalance(&manager, &invest_instructions, &None);
     check_limits(&setup.env, "Invest");
 
     setup.env.jump(DAY_IN_LEDGERS * 7);
@@ -229,7 +229,7 @@ fn hodl_panic() {
 
     // Rebalance
     setup.env.cost_estimate().budget().reset_unlimited();
-    vault_contract.rebalance(&manager, &invest_instructions);
+    vault_contract.rebalance(&manager, &invest_instructions, &None);
     check_limits(&setup.env, "Invest");
 
     setup.env.jump(DAY_IN_LEDGERS * 7);
@@ -338,7 +338,7 @@ fn fixed() {
 
     // Rebalance
     setup.env.cost_estimate().budget().reset_unlimited();
-    vault_contract.rebalance(&manager, &invest_instructions);
+    vault_contract.rebalance(&manager, &invest_instructions, &None);
     check_limits(&setup.env, "Invest");
 
     setup.env.jump(DAY_IN_LEDGERS * 7);
@@ -461,7 +461,7 @@ fn fixed_panic() {
 
     // Rebalance
     setup.env.cost_estimate().budget().reset_unlimited();
-    vault_contract.rebalance(&manager, &invest_instructions);
+    vault_contract.rebalance(&manager, &invest_instructions, &None);
     check_limits(&setup.env, "Invest");
 
     setup.env.jump(DAY_IN_LEDGERS * 7);
@@ -645,7 +645,7 @@ fn blend() {
     }
 
     setup.env.cost_estimate().budget().reset_unlimited();
-    vault_contract.rebalance(&manager, &invest_instructions);
+    vault_contract.rebalance(&manager, &invest_instructions, &None);
     let invest_usage= check_limits_return_info(&setup.env, "Invest");
 
     /* ----------------------------------------------------------- Deposit and invest ---------------------------------------------------- */
@@ -668,7 +668,7 @@ fn blend() {
         ));
     }
     setup.env.cost_estimate().budget().reset_unlimited();
-    vault_contract.rebalance(&manager, &unwind_instructions);
+    vault_contract.rebalance(&manager, &unwind_instructions, &None);
     let unwind_usage = check_limits_return_info(&setup.env, "Unwind");
 
 
@@ -680,7 +680,7 @@ fn blend() {
             starting_balance / num_strategies as i128,
         ));
     }
-    vault_contract.rebalance(&manager, &invest_instructions);
+    vault_contract.rebalance(&manager, &invest_instructions, &None);
     setup.env.cost_estimate().budget().reset_unlimited();
 
 
@@ -768,7 +768,7 @@ fn blend() {
     println!("report = {:?}", report);
 
     setup.env.cost_estimate().budget().reset_unlimited();    
-    let lock_fees = vault_contract.lock_fees(&None);
+    let lock_fees = vault_contract.lock_fees();
     let lock_fees_usage= check_limits_return_info(&setup.env, "Lock Fees");
     println!("locked_fees = {:?}", lock_fees);
 
@@ -790,7 +790,7 @@ fn blend() {
             starting_balance / num_strategies as i128,
         ));
     }
-    vault_contract.rebalance(&manager, &unwind_instructions);
+    vault_contract.rebalance(&manager, &unwind_instructions, &None);
 
     let total_managed_funds = vault_contract.fetch_total_managed_funds();
     print_total_managed_funds(&total_managed_funds);
@@ -987,7 +987,7 @@ fn blend_panic() {
     }
 
     setup.env.cost_estimate().budget().reset_unlimited();
-    vault_contract.rebalance(&manager, &invest_instructions);
+    vault_contract.rebalance(&manager, &invest_instructions, &None);
     let invest_usage= check_limits_return_info(&setup.env, "Invest");
 
     /* ----------------------------------------------------------- Deposit and invest ---------------------------------------------------- */
@@ -1010,7 +1010,7 @@ fn blend_panic() {
         ));
     }
     setup.env.cost_estimate().budget().reset_unlimited();
-    vault_contract.rebalance(&manager, &invest_instructions);
+    vault_contract.rebalance(&manager, &invest_instructions, &None);
     let unwind_usage = check_limits_return_info(&setup.env, "Unwind");
 
 
@@ -1022,7 +1022,7 @@ fn blend_panic() {
             starting_balance / num_strategies as i128,
         ));
     }
-    vault_contract.rebalance(&manager, &invest_instructions);
+    vault_contract.rebalance(&manager, &invest_instructions, &None);
     setup.env.cost_estimate().budget().reset_unlimited();
 
 
@@ -1110,7 +1110,7 @@ fn blend_panic() {
     let report = vault_contract.report();
     println!("report = {:?}", report);
 
-    let lock_fees = vault_contract.lock_fees(&None);
+    let lock_fees = vault_contract.lock_fees();
     println!("locked_fees = {:?}", lock_fees);
 
     /* ---------------------------------------------------------- Distribute Fees ------------------------------------------------------- */
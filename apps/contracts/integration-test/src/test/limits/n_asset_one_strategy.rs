@@ -114,7 +114,7 @@ fn n_assets_one_strategy_hodl() {
 
     // Rebalance first batch
     setup.env.cost_estimate().budget().reset_unlimited();
-    vault_contract.rebalance(&manager, &invest_instructions);
+    vault_contract.rebalance(&manager, &invest_instructions, &None);
     let invest_batch1_usage = check_limits_return_info(&setup.env, "Invest Batch 1");
 
     let mut invest_instructions = svec![&setup.env];
@@ -128,7 +128,7 @@ fn n_assets_one_strategy_hodl() {
 
     // Rebalance second batch
     setup.env.cost_estimate().budget().reset_unlimited();
-    vault_contract.rebalance(&manager, &invest_instructions);
+    vault_contract.rebalance(&manager, &invest_instructions, &None);
     let invest_batch2_usage = check_limits_return_info(&setup.env, "Invest Batch 2");
 
     for i in 0..num_tokens {
@@ -308,7 +308,7 @@ fn n_assets_one_strategy_fixed() {
 
     // Rebalance first batch
     setup.env.cost_estimate().budget().reset_unlimited();
-    vault_contract.rebalance(&manager, &invest_instructions);
+    vault_contract.rebalance(&manager, &invest_instructions, &None);
     let invest_usage = check_limits_return_info(&setup.env, "Invest");
 
     for i in 0..num_tokens {
@@ -549,7 +549,7 @@ fn n_assets_one_strategy_blend() {
 
     // Rebalance first batch
     setup.env.cost_estimate().budget().reset_unlimited();
-    vault_contract.rebalance(&manager, &invest_instructions);
+    vault_contract.rebalance(&manager, &invest_instructions, &None);
     let invest_usage = check_limits_return_info(&setup.env, "Invest");
 
     // Fetch total managed funds to get idle amounts
@@ -566,7 +566,7 @@ fn n_assets_one_strategy_blend() {
 
     // // Rebalance second batch
     // setup.env.cost_estimate().budget().reset_unlimited();
-    // vault_contract.rebalance(&manager, &invest_instructions);
+    // vault_contract.rebalance(&manager, &invest_instructions, &None);
     // check_limits(&setup.env, "Invest Batch 2");
 
     let mut amounts_desired = svec![&setup.env];
@@ -601,7 +601,7 @@ fn n_assets_one_strategy_blend() {
     ));
     
     setup.env.cost_estimate().budget().reset_unlimited();
-    vault_contract.rebalance(&manager, &unwind_instructions);
+    vault_contract.rebalance(&manager, &unwind_instructions, &None);
     let unwind_usage = check_limits_return_info(&setup.env, "Unwind");
 
     // Get total managed funds
@@ -659,15 +659,14 @@ fn n_assets_one_strategy_blend() {
     // Get idle amount of first asset
     let swap_amount = total_managed_funds.get(0).unwrap().idle_amount / 2;
     let swap_instruction = Instruction::SwapExactIn(
-        usdc.address.clone(),
-        xlm.address.clone(),
+        svec![&setup.env, usdc.address.clone(), xlm.address.clone()],
         swap_amount,
         0i128,
         setup.env.ledger().timestamp() + 3600u64,
     );
 
     setup.env.cost_estimate().budget().reset_unlimited();
-    vault_contract.rebalance(&manager, &svec![&setup.env, swap_instruction]);
+    vault_contract.rebalance(&manager, &svec![&setup.env, swap_instruction], &None);
     let swap_usage = check_limits_return_info(&setup.env, "Swap");
 
     // Simulate a user withdrawal touching all strategies
@@ -93,7 +93,7 @@ fn asset_one_strategy_hodl_rebalance() {
 
     // Rebalance
     setup.env.cost_estimate().budget().reset_unlimited();
-    vault_contract.rebalance(&manager, &invest_instructions);
+    vault_contract.rebalance(&manager, &invest_instructions, &None);
     check_limits(&setup.env, "Invest");
 
     // Checking unwind limit
@@ -110,7 +110,7 @@ fn asset_one_strategy_hodl_rebalance() {
 
     // Rebalance
     setup.env.cost_estimate().budget().reset_unlimited();
-    vault_contract.rebalance(&manager, &unwind_instructions);
+    vault_contract.rebalance(&manager, &unwind_instructions, &None);
     check_limits(&setup.env, "Unwind");
 }
 
@@ -204,7 +204,7 @@ fn asset_one_strategy_hodl_rebalance_panic_invest() {
 
     // Rebalance
     setup.env.cost_estimate().budget().reset_unlimited();
-    vault_contract.rebalance(&manager, &invest_instructions);
+    vault_contract.rebalance(&manager, &invest_instructions, &None);
     check_limits(&setup.env, "Invest");
 }
 
@@ -297,7 +297,7 @@ fn asset_one_strategy_hodl_rebalance_panic_unwind() {
     }
 
     // Rebalance
-    vault_contract.rebalance(&manager, &invest_instructions);
+    vault_contract.rebalance(&manager, &invest_instructions, &None);
 
     // Checking unwind limit
     let balance_on_strategy = strategy_contract.balance(&vault_contract.address);
@@ -313,7 +313,7 @@ fn asset_one_strategy_hodl_rebalance_panic_unwind() {
 
     // Rebalance
     setup.env.cost_estimate().budget().reset_unlimited();
-    vault_contract.rebalance(&manager, &unwind_instructions);
+    vault_contract.rebalance(&manager, &unwind_instructions, &None);
     check_limits(&setup.env, "Unwind");
 }
 
@@ -405,7 +405,7 @@ fn asset_one_strategy_fixed_rebalance() {
 
     // Rebalance
     setup.env.cost_estimate().budget().reset_unlimited();
-    vault_contract.rebalance(&manager, &invest_instructions);
+    vault_contract.rebalance(&manager, &invest_instructions, &None);
     check_limits(&setup.env, "Invest");
 
     setup.env.jump(DAY_IN_LEDGERS * 7);
@@ -414,7 +414,7 @@ fn asset_one_strategy_fixed_rebalance() {
     strategy_contract.harvest(&manager);
 
     vault_contract.report();
-    vault_contract.lock_fees(&None);
+    vault_contract.lock_fees();
     vault_contract.distribute_fees(&manager);
 
     let balance_on_strategy = strategy_contract.balance(&vault_contract.address);
@@ -431,7 +431,7 @@ fn asset_one_strategy_fixed_rebalance() {
 
     // Rebalance
     setup.env.cost_estimate().budget().reset_unlimited();
-    vault_contract.rebalance(&manager, &unwind_instructions);
+    vault_contract.rebalance(&manager, &unwind_instructions, &None);
     check_limits(&setup.env, "Unwind");
 }
 
@@ -524,7 +524,7 @@ fn asset_one_strategy_fixed_rebalance_panic_invest() {
 
     // Rebalance
     setup.env.cost_estimate().budget().reset_unlimited();
-    vault_contract.rebalance(&manager, &invest_instructions);
+    vault_contract.rebalance(&manager, &invest_instructions, &None);
     check_limits(&setup.env, "Invest");
 }
 
@@ -617,7 +617,7 @@ fn asset_one_strategy_fixed_rebalance_panic_unwind() {
 
     // Rebalance
     setup.env.cost_estimate().budget().reset_unlimited();
-    vault_contract.rebalance(&manager, &invest_instructions);
+    vault_contract.rebalance(&manager, &invest_instructions, &None);
     check_limits(&setup.env, "Invest");
 
     setup.env.jump(DAY_IN_LEDGERS * 7);
@@ -626,7 +626,7 @@ fn asset_one_strategy_fixed_rebalance_panic_unwind() {
     strategy_contract.harvest(&manager);
 
     vault_contract.report();
-    vault_contract.lock_fees(&None);
+    vault_contract.lock_fees();
     vault_contract.distribute_fees(&manager);
 
     let balance_on_strategy = strategy_contract.balance(&vault_contract.address);
@@ -643,7 +643,7 @@ fn asset_one_strategy_fixed_rebalance_panic_unwind() {
 
     // Rebalance
     setup.env.cost_estimate().budget().reset_unlimited();
-    vault_contract.rebalance(&manager, &unwind_instructions);
+    vault_contract.rebalance(&manager, &unwind_instructions, &None);
     check_limits(&setup.env, "Unwind");
 }
 
@@ -771,7 +771,7 @@ fn asset_one_strategy_blend_rebalance() {
     }
 
     setup.env.cost_estimate().budget().reset_unlimited();
-    vault_contract.rebalance(&manager, &invest_instructions);
+    vault_contract.rebalance(&manager, &invest_instructions, &None);
     check_limits(&setup.env, "Invest");
 
     let balance_on_strategy = strategy_contract.balance(&vault_contract.address);
@@ -786,7 +786,7 @@ fn asset_one_strategy_blend_rebalance() {
     }
 
     setup.env.cost_estimate().budget().reset_unlimited();
-    vault_contract.rebalance(&manager, &unwinds_instructions);
+    vault_contract.rebalance(&manager, &unwinds_instructions, &None);
     check_limits(&setup.env, "Unwind");
 }
 
@@ -915,7 +915,7 @@ fn asset_one_strategy_blend_rebalance_panic_invest() {
     }
 
     setup.env.cost_estimate().budget().reset_unlimited();
-    vault_contract.rebalance(&manager, &invest_instructions);
+    vault_contract.rebalance(&manager, &invest_instructions, &None);
     check_limits(&setup.env, "Invest");
 }
 
@@ -1044,7 +1044,7 @@ fn asset_one_strategy_blend_rebalance_panic_unwind() {
         ));
     }
 
-    vault_contract.rebalance(&manager, &invest_instructions);
+    vault_contract.rebalance(&manager, &invest_instructions, &None);
 
     let balance_on_strategy = strategy_contract.balance(&vault_contract.address);
     let num_unwinds = 5;
@@ -1058,7 +1058,7 @@ fn asset_one_strategy_blend_rebalance_panic_unwind() {
     }
 
     setup.env.cost_estimate().budget().reset_unlimited();
-    vault_contract.rebalance(&manager, &unwinds_instructions);
+    vault_contract.rebalance(&manager, &unwinds_instructions, &None);
     check_limits(&setup.env, "Unwind");
 }
 
@@ -1168,8 +1168,7 @@ fn two_assets_swap_limits_rebalance() {
     let mut exact_in_instructions = svec![&setup.env];
     for _ in 0..num_exact_in {
         exact_in_instructions.push_back(Instruction::SwapExactIn(
-            usdc.address.clone(),
-            xlm.address.clone(),
+            svec![&setup.env, usdc.address.clone(), xlm.address.clone()],
             usdc_balance_on_vault / num_exact_in as i128,
             0,
             setup.env.ledger().timestamp() + 3600u64,
@@ -1177,7 +1176,7 @@ fn two_assets_swap_limits_rebalance() {
     }
 
     setup.env.cost_estimate().budget().reset_unlimited();
-    vault_contract.rebalance(&manager, &exact_in_instructions);
+    vault_contract.rebalance(&manager, &exact_in_instructions, &None);
     check_limits(&setup.env, "SwapExactIn");
 
     let num_exact_out = 5;
@@ -1185,8 +1184,7 @@ fn two_assets_swap_limits_rebalance() {
     let mut exact_out_instructions = svec![&setup.env];
     for _ in 0..num_exact_out {
         exact_out_instructions.push_back(Instruction::SwapExactIn(
-            xlm.address.clone(),
-            usdc.address.clone(),
+            svec![&setup.env, xlm.address.clone(), usdc.address.clone()],
             usdc_balance_on_vault / num_exact_out as i128,
             0,
             setup.env.ledger().timestamp() + 3600u64,
@@ -1194,7 +1192,7 @@ fn two_assets_swap_limits_rebalance() {
     }
 
     setup.env.cost_estimate().budget().reset_unlimited();
-    vault_contract.rebalance(&manager, &exact_out_instructions);
+    vault_contract.rebalance(&manager, &exact_out_instructions, &None);
     check_limits(&setup.env, "SwapExactOut");
 }
 
@@ -1305,8 +1303,7 @@ fn two_assets_swap_limits_rebalance_panic_exact_in() {
     let mut exact_in_instructions = svec![&setup.env];
     for _ in 0..num_exact_in {
         exact_in_instructions.push_back(Instruction::SwapExactIn(
-            usdc.address.clone(),
-            xlm.address.clone(),
+            svec![&setup.env, usdc.address.clone(), xlm.address.clone()],
             usdc_balance_on_vault / num_exact_in as i128,
             0,
             setup.env.ledger().timestamp() + 3600u64,
@@ -1314,7 +1311,7 @@ fn two_assets_swap_limits_rebalance_panic_exact_in() {
     }
 
     setup.env.cost_estimate().budget().reset_unlimited();
-    vault_contract.rebalance(&manager, &exact_in_instructions);
+    vault_contract.rebalance(&manager, &exact_in_instructions, &None);
     check_limits(&setup.env, "SwapExactIn");
 }
 
@@ -1425,23 +1422,21 @@ fn two_assets_swap_limits_rebalance_panic_exact_out() {
     let mut exact_in_instructions = svec![&setup.env];
     for _ in 0..num_exact_in {
         exact_in_instructions.push_back(Instruction::SwapExactIn(
-            usdc.address.clone(),
-            xlm.address.clone(),
+            svec![&setup.env, usdc.address.clone(), xlm.address.clone()],
             usdc_balance_on_vault / num_exact_in as i128,
             0,
             setup.env.ledger().timestamp() + 3600u64,
         ));
     }
 
-    vault_contract.rebalance(&manager, &exact_in_instructions);
+    vault_contract.rebalance(&manager, &exact_in_instructions, &None);
 
     let num_exact_out = 6;
 
     let mut exact_out_instructions = svec![&setup.env];
     for _ in 0..num_exact_out {
         exact_out_instructions.push_back(Instruction::SwapExactIn(
-            xlm.address.clone(),
-            usdc.address.clone(),
+            svec![&setup.env, xlm.address.clone(), usdc.address.clone()],
             usdc_balance_on_vault / num_exact_out as i128,
             0,
             setup.env.ledger().timestamp() + 3600u64,
@@ -1449,6 +1444,6 @@ fn two_assets_swap_limits_rebalance_panic_exact_out() {
     }
 
     setup.env.cost_estimate().budget().reset_unlimited();
-    vault_contract.rebalance(&manager, &exact_out_instructions);
+    vault_contract.rebalance(&manager, &exact_out_instructions, &None);
     check_limits(&setup.env, "SwapExactOut");
 }
\ No newline at end of file
@@ -164,11 +164,11 @@ fn success() {
         invoke: &MockAuthInvoke {
             contract: &vault_contract.address.clone(),
             fn_name: "rebalance",
-            args: (enviroment.manager.clone(), invest_instructions.clone()).into_val(&setup.env),
+            args: (enviroment.manager.clone(), invest_instructions.clone(), None::<u32>).into_val(&setup.env),
             sub_invokes: &[],
         },
     }])
-    .rebalance(&enviroment.manager, &invest_instructions);
+    .rebalance(&enviroment.manager, &invest_instructions, &None);
 
     println!("--report after investing 0 0 2 --");
     let report = vault_contract.report();
@@ -272,7 +272,7 @@ fn success() {
     let report = vault_contract.report();
     println!("report = {:?}", report);
 
-    let lock_fees = vault_contract.lock_fees(&None);
+    let lock_fees = vault_contract.lock_fees();
     println!("locked_fees = {:?}", lock_fees);
 
 
@@ -303,7 +303,7 @@ fn success() {
     println!("Vault Balance on Strategy: {}", enviroment.strategy_contract.balance(&vault_contract.address));
     println!("report NEW = {:?}", report);
 
-    // let lock_fees = vault_contract.lock_fees(&None);
+    // let lock_fees = vault_contract.lock_fees();
     // println!("locked_fees = {:?}", lock_fees);
 
     // -> verify over withdraw fails
@@ -64,7 +64,7 @@ fn rounding_attack() {
 
         print_shares_value(&e, "before deposit");
 
-        e.vault_contract.lock_fees(&None::<u32>);
+        e.vault_contract.lock_fees();
         print_shares_value(&e, "after lock_fees");
 
         // Deposit to vault
@@ -206,11 +206,11 @@ fn invest(e: &crate::setup::VaultOneBlendStrategy<'_>, amount: i128, strategy_ad
             invoke: &MockAuthInvoke {
                 contract: &e.vault_contract.address.clone(),
                 fn_name: "rebalance",
-                args: (e.manager.clone(), invest_instructions.clone()).into_val(&e.setup.env),
+                args: (e.manager.clone(), invest_instructions.clone(), None::<u32>).into_val(&e.setup.env),
                 sub_invokes: &[],
             },
         }])
-        .rebalance(&e.manager, &invest_instructions);
+        .rebalance(&e.manager, &invest_instructions, &None);
     
     // Report after investing
     let report = e.vault_contract.report();
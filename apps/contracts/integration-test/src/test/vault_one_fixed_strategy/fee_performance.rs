@@ -2,7 +2,7 @@ use crate::{
     setup::create_vault_one_asset_fixed_strategy,
     test::{EnvTestUtils, IntegrationTest, ONE_YEAR_IN_SECONDS},
     vault::{
-        defindex_vault_contract::Instruction,
+        defindex_vault_contract::{Instruction, ProposalAction},
         },
 };
 use soroban_sdk::{
@@ -20,6 +20,15 @@ fn fee_performance() {
     let users = IntegrationTest::generate_random_users(&setup.env, 1);
     let user = &users[0];
 
+    // The fee performance below is computed with a 20% vault fee
+    let vault_fee_bps = 2000u32;
+    let proposal_id = enviroment
+        .vault_contract
+        .mock_all_auths()
+        .propose(&ProposalAction::SetVaultFee(vault_fee_bps));
+    setup.env.jump_time(enviroment.vault_contract.get_manager_timelock());
+    enviroment.vault_contract.mock_all_auths().execute_proposal(&proposal_id);
+
     enviroment
         .token_admin_client
         .mock_auths(&[MockAuth {
@@ -84,11 +93,11 @@ fn fee_performance() {
             invoke: &MockAuthInvoke {
                 contract: &enviroment.vault_contract.address.clone(),
                 fn_name: "rebalance",
-                args: (enviroment.manager.clone(), invest_instructions.clone()).into_val(&setup.env),
+                args: (enviroment.manager.clone(), invest_instructions.clone(), None::<u32>).into_val(&setup.env),
                 sub_invokes: &[],
             },
         }])
-        .rebalance(&enviroment.manager, &invest_instructions);
+        .rebalance(&enviroment.manager, &invest_instructions, &None);
 
     let vault_balance_in_strategy = enviroment
         .strategy_contract
@@ -116,7 +125,6 @@ fn fee_performance() {
     assert_eq!(vault_balance_in_strategy, expected_balance);
 
     // Lock fees
-    let lock_fees_bps = 2000u32;
     let lock_fees_result = enviroment
         .vault_contract
         .mock_auths(&[MockAuth {
@@ -124,11 +132,11 @@ fn fee_performance() {
             invoke: &MockAuthInvoke {
                 contract: &enviroment.vault_contract.address.clone(),
                 fn_name: "lock_fees",
-                args: svec![&setup.env, lock_fees_bps].into_val(&setup.env),
+                args: ().into_val(&setup.env),
                 sub_invokes: &[],
             },
         }])
-        .lock_fees(&Some(lock_fees_bps));
+        .lock_fees();
 
     // lock fees should be 20% of the (vault balance before harvest - vault balance in strategy)
     // 20% of (100_000_000 - 100_000_000 * 11 / 10) = 2_0_000_000
@@ -182,7 +190,6 @@ fn fee_performance() {
     println!("total_funds_after_release: {:?}", total_funds_after_release);
 
     // Lock fees
-    let lock_fees_bps = 2000u32;
     let _lock_fees_result = enviroment
         .vault_contract
         .mock_auths(&[MockAuth {
@@ -190,11 +197,11 @@ fn fee_performance() {
             invoke: &MockAuthInvoke {
                 contract: &enviroment.vault_contract.address.clone(),
                 fn_name: "lock_fees",
-                args: svec![&setup.env, lock_fees_bps].into_val(&setup.env),
+                args: ().into_val(&setup.env),
                 sub_invokes: &[],
             },
         }])
-        .lock_fees(&Some(lock_fees_bps));
+        .lock_fees();
 
     println!("_lock_fees_result 2: {:?}", _lock_fees_result);
 
@@ -225,7 +232,7 @@ fn fee_performance() {
     // Get report from storage before rebalance
     // let report_after_distribute = enviroment.vault_contract.mock_all_auths().report();
     // println!("report_after_distribute: {:?}", report_after_distribute);
-    let lock_fees_result_after = enviroment.vault_contract.mock_all_auths().lock_fees(&None);
+    let lock_fees_result_after = enviroment.vault_contract.mock_all_auths().lock_fees();
     println!("lock_fees_result_after: {:?}", lock_fees_result_after);
 
     let total_funds_after_distribute = enviroment
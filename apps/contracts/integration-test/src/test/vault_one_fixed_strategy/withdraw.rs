@@ -187,11 +187,11 @@ fn fixed_apr_invest_withdraw_success() {
             invoke: &MockAuthInvoke {
                 contract: &enviroment.vault_contract.address.clone(),
                 fn_name: "rebalance",
-                args: (enviroment.manager.clone(), invest_instructions.clone()).into_val(&setup.env),
+                args: (enviroment.manager.clone(), invest_instructions.clone(), None::<u32>).into_val(&setup.env),
                 sub_invokes: &[],
             },
         }])
-        .rebalance(&enviroment.manager, &invest_instructions);
+        .rebalance(&enviroment.manager, &invest_instructions, &None);
 
     setup
         .env
@@ -1,7 +1,7 @@
 use crate::{
     setup::create_vault_one_asset_hodl_strategy,
     test::{EnvTestUtils, IntegrationTest, ONE_YEAR_IN_SECONDS},
-    vault::{defindex_vault_contract::Instruction, VaultContractError},
+    vault::{defindex_vault_contract::{Instruction, ProposalAction}, VaultContractError},
 };
 use soroban_sdk::{
     testutils::{MockAuth, MockAuthInvoke},
@@ -19,6 +19,15 @@ fn fee_performance() {
     let users = IntegrationTest::generate_random_users(&setup.env, 1);
     let user = &users[0];
 
+    // The fee performance below is computed with a 20% vault fee
+    let vault_fee_bps = 2000u32;
+    let proposal_id = enviroment
+        .vault_contract
+        .mock_all_auths()
+        .propose(&ProposalAction::SetVaultFee(vault_fee_bps));
+    setup.env.jump_time(enviroment.vault_contract.get_manager_timelock());
+    enviroment.vault_contract.mock_all_auths().execute_proposal(&proposal_id);
+
     enviroment
         .token_admin_client
         .mock_auths(&[MockAuth {
@@ -127,11 +136,11 @@ fn fee_performance() {
             invoke: &MockAuthInvoke {
                 contract: &enviroment.vault_contract.address.clone(),
                 fn_name: "rebalance",
-                args: (enviroment.manager.clone(), invest_instructions.clone()).into_val(&setup.env),
+                args: (enviroment.manager.clone(), invest_instructions.clone(), None::<u32>).into_val(&setup.env),
                 sub_invokes: &[],
             },
         }])
-        .rebalance(&enviroment.manager, &invest_instructions);
+        .rebalance(&enviroment.manager, &invest_instructions, &None);
 
     let report_result_after_1_invest = enviroment.vault_contract.report();
     println!(
@@ -147,11 +156,11 @@ fn fee_performance() {
             invoke: &MockAuthInvoke {
                 contract: &enviroment.vault_contract.address.clone(),
                 fn_name: "rebalance",
-                args: (enviroment.manager.clone(), invest_instructions.clone()).into_val(&setup.env),
+                args: (enviroment.manager.clone(), invest_instructions.clone(), None::<u32>).into_val(&setup.env),
                 sub_invokes: &[],
             },
         }])
-        .rebalance(&enviroment.manager, &invest_instructions);
+        .rebalance(&enviroment.manager, &invest_instructions, &None);
 
 
     let report_result_after_2_invests = enviroment.vault_contract.report();
@@ -182,11 +191,11 @@ fn fee_performance() {
             invoke: &MockAuthInvoke {
                 contract: &enviroment.vault_contract.address.clone(),
                 fn_name: "lock_fees",
-                args: svec![&setup.env, 2000u32].into_val(&setup.env),
+                args: ().into_val(&setup.env),
                 sub_invokes: &[],
             },
         }])
-        .lock_fees(&Some(2000u32));
+        .lock_fees();
     let total_funds_after_lock = enviroment
         .vault_contract
         .fetch_total_managed_funds()
@@ -90,11 +90,11 @@ fn invest_success() {
             invoke: &MockAuthInvoke {
                 contract: &enviroment.vault_contract.address.clone(),
                 fn_name: "rebalance",
-                args: (enviroment.manager.clone(), invest_instructions.clone()).into_val(&setup.env),
+                args: (enviroment.manager.clone(), invest_instructions.clone(), None::<u32>).into_val(&setup.env),
                 sub_invokes: &[],
             },
         }])
-        .rebalance(&enviroment.manager, &invest_instructions);
+        .rebalance(&enviroment.manager, &invest_instructions, &None);
 
     // enviroment
     //     .vault_contract
@@ -212,11 +212,11 @@ fn invest_insufficient_balance() {
             invoke: &MockAuthInvoke {
                 contract: &enviroment.vault_contract.address.clone(),
                 fn_name: "rebalance",
-                args: (enviroment.manager.clone(), invest_instructions.clone()).into_val(&setup.env),
+                args: (enviroment.manager.clone(), invest_instructions.clone(), None::<u32>).into_val(&setup.env),
                 sub_invokes: &[],
             },
         }])
-        .rebalance(&enviroment.manager, &invest_instructions);
+        .rebalance(&enviroment.manager, &invest_instructions, &None);
 }
 
 #[test]
@@ -348,11 +348,11 @@ fn invest_multiple_users() {
             invoke: &MockAuthInvoke {
                 contract: &enviroment.vault_contract.address.clone(),
                 fn_name: "rebalance",
-                args: (enviroment.manager.clone(), invest_instructions.clone()).into_val(&setup.env),
+                args: (enviroment.manager.clone(), invest_instructions.clone(), None::<u32>).into_val(&setup.env),
                 sub_invokes: &[],
             },
         }])
-        .rebalance(&enviroment.manager, &invest_instructions);
+        .rebalance(&enviroment.manager, &invest_instructions, &None);
 
 
 
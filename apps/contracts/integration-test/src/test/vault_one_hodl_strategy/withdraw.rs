@@ -375,11 +375,11 @@ fn test_withdraw_after_invest() {
             invoke: &MockAuthInvoke {
                 contract: &enviroment.vault_contract.address.clone(),
                 fn_name: "rebalance",
-                args: (enviroment.manager.clone(), invest_instructions.clone()).into_val(&setup.env),
+                args: (enviroment.manager.clone(), invest_instructions.clone(), None::<u32>).into_val(&setup.env),
                 sub_invokes: &[],
             },
         }])
-        .rebalance(&enviroment.manager, &invest_instructions);
+        .rebalance(&enviroment.manager, &invest_instructions, &None);
 
     setup
         .env
@@ -722,11 +722,11 @@ fn test_withdraw_after_invest_multiple_users() {
             invoke: &MockAuthInvoke {
                 contract: &enviroment.vault_contract.address.clone(),
                 fn_name: "rebalance",
-                args: (enviroment.manager.clone(), invest_instructions.clone()).into_val(&setup.env),
+                args: (enviroment.manager.clone(), invest_instructions.clone(), None::<u32>).into_val(&setup.env),
                 sub_invokes: &[],
             },
         }])
-        .rebalance(&enviroment.manager, &invest_instructions);
+        .rebalance(&enviroment.manager, &invest_instructions, &None);
 
     setup
         .env
@@ -1,24 +1,24 @@
 import { Keypair } from "@stellar/stellar-sdk";
 import { AddressBook } from "../../utils/address_book.js";
 import { airdropAccount } from "../../utils/contract.js";
-import { manager, setEmergencyManager, setFeeReceiver, setRebalanceManager, setVaultManager } from "../../utils/vault.js";
+import { manager, proposeEmergencyManager, proposeFeeReceiver, setRebalanceManager, queueVaultManager } from "../../utils/vault.js";
 import { green, purple, red } from "../common.js";
 import { CreateVaultParams } from "../types.js";
 import { deployDefindexVault } from "./utils.js";
 
 /* 
 // Access control tests:
-  - [x] try setManager from unauthorized
-  - [x] setManager
+  - [x] try queueManager from unauthorized
+  - [x] queueManager
 
   - [x] try setRebalanceManager from unauthorized
   - [x] setRebalanceManager
 
-  - [x] try setFeeReceiver from unauthorized
-  - [x] setFeeReceiver
+  - [x] try proposeFeeReceiver from unauthorized
+  - [x] proposeFeeReceiver
 
-  - [x] try setEmergencyManager from unauthorized
-  - [x] setEmergencyManager
+  - [x] try proposeEmergencyManager from unauthorized
+  - [x] proposeEmergencyManager
 */
 export async function testAccessControl(addressBook: AddressBook, params: CreateVaultParams[], user: Keypair) {
   //Deploy vault
@@ -83,7 +83,7 @@ export async function testAccessControl(addressBook: AddressBook, params: Create
       console.log(purple, "---------------------------------------");
       const random_user = Keypair.random();
       await airdropAccount(random_user);
-      await setFeeReceiver(vault_address, random_user, user.publicKey());
+      await proposeFeeReceiver(vault_address, random_user, user.publicKey());
 
     } catch (error: any) {
       console.error(red, error);
@@ -93,7 +93,7 @@ export async function testAccessControl(addressBook: AddressBook, params: Create
     }
   })(); 
 
-  // setFeeReceiver success
+  // proposeFeeReceiver success
   const {
     instructions: set_fee_receiver_instructions,
     readBytes: set_fee_receiver_read_bytes,
@@ -101,11 +101,11 @@ export async function testAccessControl(addressBook: AddressBook, params: Create
   } = await (async () => {
     try {
       console.log(purple, "---------------------------------------");
-      console.log(purple, "setFeeReceiver");
+      console.log(purple, "proposeFeeReceiver");
       console.log(purple, "---------------------------------------");
-      const {instructions, readBytes, writeBytes} = await setFeeReceiver(vault_address, manager, user.publicKey());
+      const {instructions, readBytes, writeBytes} = await proposeFeeReceiver(vault_address, manager, user.publicKey());
       console.log(green, "---------------------------------");
-      console.log(green, "| Fee receiver set sucessfully  |");
+      console.log(green, "| Fee receiver proposed        |");
       console.log(green, "---------------------------------");
       return {instructions, readBytes, writeBytes};
     } catch (error: any) {
@@ -123,7 +123,7 @@ export async function testAccessControl(addressBook: AddressBook, params: Create
     console.log(purple, "-------------------------------------------");
     const random_user = Keypair.random();
     await airdropAccount(random_user);
-    await setEmergencyManager(vault_address, random_user, user.publicKey());
+    await proposeEmergencyManager(vault_address, random_user, user.publicKey());
   } catch (error: any) {
     console.error(red, error);
     console.log(red, "--------------------------------------------------------------");
@@ -132,7 +132,7 @@ export async function testAccessControl(addressBook: AddressBook, params: Create
   }
   })();
 
-  // setEmergencyManager success
+  // proposeEmergencyManager success
   const {
     instructions: set_emergency_manager_instructions,
     readBytes: set_emergency_manager_read_bytes,
@@ -140,18 +140,14 @@ export async function testAccessControl(addressBook: AddressBook, params: Create
   } = await (async () => {
     try {
       console.log(purple, "---------------------------------------");
-      console.log(purple, "setEmergencyManager");
+      console.log(purple, "proposeEmergencyManager");
       console.log(purple, "---------------------------------------");
       const random_user = Keypair.random();
       await airdropAccount(random_user);
-      const {result, instructions, readBytes, writeBytes} = await setEmergencyManager(vault_address, manager, random_user.publicKey());
-      if( result === null){
-        console.log(green, "--------------------------------------");
-        console.log(green, "| Emergency manager set sucessfully  |");
-        console.log(green, "--------------------------------------");
-      } else if (result === false) {
-        throw Error("Set emergency manager failed");
-      }
+      const {result, instructions, readBytes, writeBytes} = await proposeEmergencyManager(vault_address, manager, random_user.publicKey());
+      console.log(green, "--------------------------------------");
+      console.log(green, "| Emergency manager proposed         |");
+      console.log(green, "--------------------------------------");
       return {result, instructions, readBytes, writeBytes};
     } catch (error: any) {
       console.error(red, error);
@@ -167,33 +163,33 @@ export async function testAccessControl(addressBook: AddressBook, params: Create
         console.log(purple, "---------------------------------------");
         const random_user = Keypair.random();
         await airdropAccount(random_user);
-        await setVaultManager(vault_address, random_user, random_user);
+        await queueVaultManager(vault_address, random_user, random_user);
 
       } catch (error: any) {
         console.error(red, error);
         console.log(red, "------------------------------------------------------");
-        console.log(red, "| Set manager from unauthorized failed as expected |");
+        console.log(red, "| Queue manager from unauthorized failed as expected |");
         console.log(red, "------------------------------------------------------");
       }
     })();
   
-    //setManager succesfully
+    //queueManager succesfully
     const {
-      instructions: set_manager_instructions, 
-      readBytes: set_manager_read_bytes, 
-      writeBytes: set_manager_write_bytes
+      instructions: queue_manager_instructions, 
+      readBytes: queue_manager_read_bytes, 
+      writeBytes: queue_manager_write_bytes
     } = await (async () => {
       try {
         console.log(purple, "---------------------------------------");
-        console.log(purple, "setManager");
+        console.log(purple, "queueManager");
         console.log(purple, "---------------------------------------");
-        const {result, instructions, readBytes, writeBytes}  = await setVaultManager(vault_address, new_manager, manager);
+        const {result, instructions, readBytes, writeBytes}  = await queueVaultManager(vault_address, new_manager, manager);
         if( result === null){
           console.log(green, "--------------------------------------");
-          console.log(green, "| Manager set sucessfully  |");
+          console.log(green, "| Manager queued sucessfully |");
           console.log(green, "--------------------------------------");
         } else if (result === false) {
-          throw Error("Set manager failed");
+          throw Error("Queue manager failed");
         }
         return {result, instructions, readBytes, writeBytes};
       } catch (error: any) {
@@ -204,8 +200,8 @@ export async function testAccessControl(addressBook: AddressBook, params: Create
   
 
   const tests_status = {
-    set_manager: {
-      status: set_manager_instructions + set_manager_read_bytes + set_manager_write_bytes,
+    queue_manager: {
+      status: queue_manager_instructions + queue_manager_read_bytes + queue_manager_write_bytes,
     },
     set_rebalance_manager: {
       status: set_rebalance_manager_instructions + set_rebalance_manager_read_bytes + set_rebalance_manager_write_bytes,
@@ -219,11 +215,11 @@ export async function testAccessControl(addressBook: AddressBook, params: Create
   }
 
   const budgetData = {
-    set_manager: {
-      status: tests_status.set_manager.status > 0 ? `success`: `failed`,
-      instructions: set_manager_instructions,
-      readBytes: set_manager_read_bytes,
-      writeBytes: set_manager_write_bytes,
+    queue_manager: {
+      status: tests_status.queue_manager.status > 0 ? `success`: `failed`,
+      instructions: queue_manager_instructions,
+      readBytes: queue_manager_read_bytes,
+      writeBytes: queue_manager_write_bytes,
     },
     set_rebalance_manager: {
       status: tests_status.set_rebalance_manager.status > 0 ? `success`: `failed`,
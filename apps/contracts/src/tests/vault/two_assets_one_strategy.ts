@@ -412,8 +412,7 @@ export async function testVaultTwoAssetsOneStrategy(addressBook: AddressBook, pa
               type: "SwapExactIn",
               amount_in: BigInt(1_0_000),
               amount_out_min: BigInt(1_0_000),
-              token_in: xlmAddress.toString(),
-              token_out: USDC_ADDRESS.toString(),
+              path: [xlmAddress.toString(), USDC_ADDRESS.toString()],
               deadline: BigInt(getCurrentTimePlusOneHour()),
             }
           ];
@@ -448,8 +447,7 @@ export async function testVaultTwoAssetsOneStrategy(addressBook: AddressBook, pa
             type: "SwapExactIn",
             amount_in: BigInt(swapEIn_amount),
             amount_out_min: BigInt(0),
-            token_in: params[0].address.toString(),
-            token_out: params[1].address.toString(),
+            path: [params[0].address.toString(), params[1].address.toString()],
             deadline: BigInt(getCurrentTimePlusOneHour()),
           },
 /*           {
@@ -526,8 +524,7 @@ export async function testVaultTwoAssetsOneStrategy(addressBook: AddressBook, pa
               type: "SwapExactIn",
               amount_in: BigInt(1_0_000),
               amount_out_min: BigInt(0),
-              token_in: params[0].address.toString(),
-              token_out: USDC_ADDRESS.toString(),
+              path: [params[0].address.toString(), USDC_ADDRESS.toString()],
               deadline: BigInt(getCurrentTimePlusOneHour()),
             }
           ];
@@ -560,8 +557,7 @@ export async function testVaultTwoAssetsOneStrategy(addressBook: AddressBook, pa
             type: "SwapExactOut",
             amount_out: BigInt(5_000_000),
             amount_in_max: BigInt(10_0_000_000),
-            token_in: params[1].address.toString(),
-            token_out: params[0].address.toString(),
+            path: [params[1].address.toString(), params[0].address.toString()],
             deadline: BigInt(getCurrentTimePlusOneHour()),
           },
  /*          {
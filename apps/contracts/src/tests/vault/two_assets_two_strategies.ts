@@ -315,8 +315,7 @@ export async function testVaultTwoAssetsTwoStrategies(addressBook: AddressBook,
               type: "SwapExactIn",
               amount_in: BigInt(500_000),
               amount_out_min: BigInt(0),
-              token_in: USDC_ADDRESS.toString(),
-              token_out: xlmAddress.toString(),
+              path: [USDC_ADDRESS.toString(), xlmAddress.toString()],
               deadline: BigInt(getCurrentTimePlusOneHour()),
             },       
           ];       
@@ -350,8 +349,7 @@ export async function testVaultTwoAssetsTwoStrategies(addressBook: AddressBook,
             type: "SwapExactIn",
             amount_in: BigInt(1_000),
             amount_out_min: BigInt(0),
-            token_in: BLEND_USDC_ADDRESS.toString(),
-            token_out: xlmAddress.toString(),
+            path: [BLEND_USDC_ADDRESS.toString(), xlmAddress.toString()],
             deadline: BigInt(getCurrentTimePlusOneHour()),
           },       
 /*           {
@@ -1,7 +1,7 @@
 import { Keypair } from "@stellar/stellar-sdk";
 import { AddressBook } from "../../utils/address_book.js";
 import { airdropAccount, installContract } from "../../utils/contract.js";
-import { admin, manager, upgradeVaultWasm } from "../../utils/vault.js";
+import { admin, manager, proposeVaultUpgrade } from "../../utils/vault.js";
 import { green, purple, red, yellow } from "../common.js";
 import { CreateVaultParams } from "../types.js";
 import { deployDefindexVault } from "./utils.js";
@@ -39,7 +39,7 @@ export async function testUpgradeContract(addressBook: AddressBook, params: Crea
       const random_user = Keypair.random();
       const wasm_hash = new Uint8Array(Buffer.from(addressBook.getWasmHash("defindex_vault"), "hex"));
       await airdropAccount(random_user);
-      await upgradeVaultWasm(vault_address, random_user, wasm_hash);
+      await proposeVaultUpgrade(vault_address, random_user, wasm_hash);
   } catch (error: any) {
     console.error(error);
     console.log(green, "------------------------------------------------");
@@ -59,7 +59,7 @@ export async function testUpgradeContract(addressBook: AddressBook, params: Crea
       console.log(purple, "Upgrade");
       console.log(purple, "---------------------------------------");
       const wasm_hash = new Uint8Array(Buffer.from(addressBook.getWasmHash("defindex_vault"), "hex"));
-      const {instructions, readBytes, writeBytes} = await upgradeVaultWasm(vault_address, manager, wasm_hash);
+      const {instructions, readBytes, writeBytes} = await proposeVaultUpgrade(vault_address, manager, wasm_hash);
       console.log(green, "------------------------");
       console.log(green, "| Upgrade sucessfully  |");
       console.log(green, "------------------------");
@@ -40,16 +40,14 @@ export type Instruction =
   | { type: "Invest"; strategy: string; amount: i128 }
   | {
       type: "SwapExactIn";
-      token_in: string;
-      token_out: string;
+      path: string[];
       amount_in: i128;
       amount_out_min: i128;
       deadline: u64;
     }
   | {
       type: "SwapExactOut";
-      token_in: string;
-      token_out: string;
+      path: string[];
       amount_out: i128;
       amount_in_max: i128;
       deadline: u64;
@@ -74,8 +72,9 @@ export function mapInstructionsToParams(
           // Handle SwapExactIn action
           return xdr.ScVal.scvVec([
             xdr.ScVal.scvSymbol("SwapExactIn"),
-            new Address(instruction.token_in).toScVal(),
-            new Address(instruction.token_out).toScVal(),
+            xdr.ScVal.scvVec(
+              instruction.path.map((token) => new Address(token).toScVal())
+            ),
             nativeToScVal(instruction.amount_in, { type: "i128" }),
             nativeToScVal(instruction.amount_out_min, { type: "i128" }),
             nativeToScVal(instruction.deadline, { type: "u64" }),
@@ -85,8 +84,9 @@ export function mapInstructionsToParams(
           // Handle SwapExactOut action
           return xdr.ScVal.scvVec([
             xdr.ScVal.scvSymbol("SwapExactOut"),
-            new Address(instruction.token_in).toScVal(),
-            new Address(instruction.token_out).toScVal(),
+            xdr.ScVal.scvVec(
+              instruction.path.map((token) => new Address(token).toScVal())
+            ),
             nativeToScVal(instruction.amount_out, { type: "i128" }),
             nativeToScVal(instruction.amount_in_max, { type: "i128" }),
             nativeToScVal(instruction.deadline, { type: "u64" }),
@@ -607,14 +607,17 @@ export async function investVault(
   }
 }
 
-export async function rebalanceVault(deployedVault: string, instructions: Instruction[], manager: Keypair) {
+export async function rebalanceVault(deployedVault: string, instructions: Instruction[], manager: Keypair, maxLossBps?: number) {
   const params = mapInstructionsToParams(instructions);
+  const maxLoss = maxLossBps !== undefined
+    ? nativeToScVal(maxLossBps, { type: "u32" })
+    : xdr.ScVal.scvVoid();
 
   try {
     const rebalanceResult = await invokeCustomContract(
       deployedVault,
       "rebalance",
-      [new Address(manager.publicKey()).toScVal(), params],
+      [new Address(manager.publicKey()).toScVal(), params, maxLoss],
       manager
     );
     if(rebalanceResult.status != 'ERROR') {
@@ -923,7 +926,7 @@ export async function fetchCurrentIdleFunds(
   }
 }
 
-export async function setVaultManager(
+export async function queueVaultManager(
   deployedVault: string,
   newManager: Keypair,
   manager: Keypair
@@ -931,16 +934,16 @@ export async function setVaultManager(
   try {
     const result = await invokeCustomContract(
       deployedVault,
-      "set_manager",
+      "queue_manager",
       [new Address(newManager.publicKey()).toScVal()],
       manager
     );
     const parsed_result = scValToNative(result.returnValue);
     const { instructions, readBytes, writeBytes } = getTransactionBudget(result);
-    console.log("Set manager successful:", scValToNative(result.returnValue));
+    console.log("Queue manager successful:", scValToNative(result.returnValue));
     return { result: parsed_result, instructions, readBytes, writeBytes };
   } catch (error) {
-    console.error("Set manager failed:", error);
+    console.error("Queue manager failed:", error);
     throw error;
   }
 }
@@ -963,60 +966,71 @@ export async function setRebalanceManager(deployedVault:Address, manager:Keypair
   }
 }
 
-export async function setFeeReceiver(deployedVault:Address, manager:Keypair, new_fee_receiver:string){
+export async function proposeFeeReceiver(deployedVault:Address, manager:Keypair, new_fee_receiver:string){
   try {
     const result = await invokeCustomContract(
       deployedVault.toString(),
-      "set_fee_receiver",
+      "propose",
       [
-        new Address(manager.publicKey()).toScVal(),
-        new Address(new_fee_receiver).toScVal()
+        xdr.ScVal.scvVec([
+          xdr.ScVal.scvSymbol("SetFeeReceiver"),
+          new Address(new_fee_receiver).toScVal()
+        ])
       ],
       manager
     );
     const parsed_result = scValToNative(result.returnValue);
     const { instructions, readBytes, writeBytes } = getTransactionBudget(result);
-    console.log("Set fee receiver successful:", scValToNative(result.returnValue));
+    console.log("Propose fee receiver successful:", scValToNative(result.returnValue));
     return { result: parsed_result, instructions, readBytes, writeBytes };
   } catch (error) {
-    console.error("Set fee receiver failed:", error);
+    console.error("Propose fee receiver failed:", error);
     throw error;
   }
 }
 
-//fn set_emergency_manager(emergency_manager: address)
-export async function setEmergencyManager(deployedVault:Address, manager:Keypair, new_emergency_manager:string){
+export async function proposeEmergencyManager(deployedVault:Address, manager:Keypair, new_emergency_manager:string){
   try {
     const result = await invokeCustomContract(
       deployedVault.toString(),
-      "set_emergency_manager",
-      [new Address(new_emergency_manager).toScVal()],
+      "propose",
+      [
+        xdr.ScVal.scvVec([
+          xdr.ScVal.scvSymbol("SetEmergencyManager"),
+          new Address(new_emergency_manager).toScVal()
+        ])
+      ],
       manager
     );
     const parsed_result = scValToNative(result.returnValue);
     const { instructions, readBytes, writeBytes } = getTransactionBudget(result);
-    console.log("Set emergency manager successful:", scValToNative(result.returnValue));
+    console.log("Propose emergency manager successful:", scValToNative(result.returnValue));
     return { result: parsed_result, instructions, readBytes, writeBytes };
   } catch (error) {
-    console.error("Set emergency manager failed:", error);
+    console.error("Propose emergency manager failed:", error);
     throw error;
   }
 }
 
-export async function upgradeVaultWasm(deployedVault:Address, manager:Keypair, new_wasm_hash:Uint8Array){
+export async function proposeVaultUpgrade(deployedVault:Address, manager:Keypair, new_wasm_hash:Uint8Array){
   try {
     const result = await invokeCustomContract(
       deployedVault.toString(),
-      "upgrade",
-      [nativeToScVal(new_wasm_hash)],
+      "propose",
+      [
+        xdr.ScVal.scvVec([
+          xdr.ScVal.scvSymbol("Upgrade"),
+          nativeToScVal(new_wasm_hash)
+        ])
+      ],
       manager
     );
     const parsed_result = scValToNative(result.returnValue);
     const { instructions, readBytes, writeBytes } = getTransactionBudget(result);
-    console.log("Upgrade successful:", scValToNative(result.returnValue));
+    console.log("Propose upgrade successful:", scValToNative(result.returnValue));
     return { result: parsed_result, instructions, readBytes, writeBytes };
   } catch (error) {
-    console.error("Upgrade failed:", error);
+    console.error("Propose upgrade failed:", error);
     throw error;
   }
 }
@@ -8,6 +8,7 @@ pub enum RolesDataKey {
     VaultFeeReceiver, // Role: 1 Fee Receiver
     Manager,          // Role: 2 Manager
     RebalanceManager, // Role: 3 Rebalance Manager
+    AllowlistManager, // Role: 4 Allowlist Manager (optional)
 }
 
 #[derive(Clone)]
@@ -71,11 +72,7 @@ impl AccessControlTrait for AccessControl {
 
 // Role-specific setters and getters
 impl AccessControl {
-    pub fn set_fee_receiver(&self, caller: &Address, vault_fee_receiver: &Address) {
-        self.require_any_role(
-            &[RolesDataKey::Manager, RolesDataKey::VaultFeeReceiver],
-            caller,
-        );
+    pub fn set_fee_receiver(&self, vault_fee_receiver: &Address) {
         self.set_role(&RolesDataKey::VaultFeeReceiver, vault_fee_receiver);
     }
 
@@ -84,7 +81,7 @@ impl AccessControl {
     }
 
     pub fn set_manager(&self, new_manager: &Address) {
-        self.require_role( &RolesDataKey::Manager);
+        new_manager.require_auth();
         self.set_role(&RolesDataKey::Manager, &new_manager);
     }
 
@@ -93,7 +90,6 @@ impl AccessControl {
     }
 
     pub fn set_emergency_manager(&self, emergency_manager: &Address) {
-        self.require_role(&RolesDataKey::Manager);
         self.set_role(&RolesDataKey::EmergencyManager, emergency_manager);
     }
 
@@ -109,4 +105,13 @@ impl AccessControl {
     pub fn get_rebalance_manager(&self) -> Result<Address, ContractError> {
         self.check_role(&RolesDataKey::RebalanceManager)
     }
+
+    pub fn set_allowlist_manager(&self, allowlist_manager: &Address) {
+        self.require_role(&RolesDataKey::Manager);
+        self.set_role(&RolesDataKey::AllowlistManager, allowlist_manager);
+    }
+
+    pub fn get_allowlist_manager(&self) -> Result<Address, ContractError> {
+        self.check_role(&RolesDataKey::AllowlistManager)
+    }
 }
@@ -1,2 +1,17 @@
 /// Scaling factor for basis points (BPS) calculations, where 10,000 BPS represents 100%.
-pub(crate) const SCALAR_BPS: i128 = 10_000;
\ No newline at end of file
+pub(crate) const SCALAR_BPS: i128 = 10_000;
+/// Soroswap pairs charge a 0.3% fee on the input amount, i.e. only 997/1000 of the input is swapped.
+pub(crate) const SOROSWAP_FEE_NUMERATOR: i128 = 997;
+pub(crate) const SOROSWAP_FEE_DENOMINATOR: i128 = 1000;
+/// Default rebalance tolerance band, in basis points of an asset's total managed funds.
+pub(crate) const DEFAULT_REBALANCE_TOLERANCE_BPS: u32 = 100;
+/// Default delay, in seconds, between queueing a new Manager and the handover (7 days).
+pub(crate) const DEFAULT_MANAGER_TIMELOCK: u64 = 604_800;
+/// Minimum delay, in seconds, the Emergency Manager can set as manager timelock (1 day).
+pub(crate) const MIN_MANAGER_TIMELOCK: u64 = 86_400;
+/// Maximum annual management fee, in basis points of the total managed funds (10%).
+pub(crate) const MAX_MANAGEMENT_FEE_BPS: u32 = 1_000;
+/// Length of the management fee year, in seconds (365 days).
+pub(crate) const SECONDS_PER_YEAR: u64 = 31_536_000;
+/// Maximum entry and exit fee, in basis points of the deposited or withdrawn shares (5%).
+pub(crate) const MAX_ENTRY_EXIT_FEE_BPS: u32 = 500;
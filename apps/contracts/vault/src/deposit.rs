@@ -1,13 +1,20 @@
-use soroban_sdk::{panic_with_error, token::TokenClient, Address, Env, Vec};
+use soroban_sdk::{panic_with_error, token::{Interface as _, TokenClient}, Address, Env, Vec};
 
 use crate::{
     models::CurrentAssetInvestmentAllocation,
+    router::{get_pair_reserves, swap_in_current_ledger},
+    report::{calculate_entry_fee_shares, pay_fee_shares},
+    storage::{get_deposit_cap, get_user_share_cap},
     token::{internal_mint, VaultToken},
     utils::{calculate_deposit_amounts_and_shares_to_mint, validate_amount},
-    ContractError, MINIMUM_LIQUIDITY,
+    constants::{SOROSWAP_FEE_DENOMINATOR, SOROSWAP_FEE_NUMERATOR},
+    ContractError, VaultError, MINIMUM_LIQUIDITY,
 };
 
 /// Common logic for processing deposits.
+///
+/// Returns the deposited amounts, the shares minted to `from` net of the entry fee and the total supply
+/// before the deposit.
 pub fn process_deposit(
     e: &Env,
     total_managed_funds: &Vec<CurrentAssetInvestmentAllocation>,
@@ -15,6 +22,40 @@ pub fn process_deposit(
     amounts_min: &Vec<i128>,
     from: &Address,
 ) -> Result<(Vec<i128>, i128, i128), ContractError> {
+    let total_supply = VaultToken::total_supply(e.clone());
+    let (amounts, shares_to_mint) = calculate_deposit_shares(
+        e,
+        total_managed_funds,
+        amounts_desired,
+        amounts_min,
+        total_supply,
+    )?;
+
+    // Transfer assets
+    for (i, amount) in amounts.iter().enumerate() {
+        if amount > 0 {
+            let asset = total_managed_funds.get(i as u32).ok_or(ContractError::ArithmeticError)?; 
+            let asset_client = TokenClient::new(&e, &asset.asset);
+            asset_client.transfer(&from, &e.current_contract_address(), &amount);
+        }
+    }
+
+    // Mint shares
+    let entry_fee_shares = mint_shares(e, &total_supply, shares_to_mint, from.clone())?;
+    let shares_to_mint = shares_to_mint.checked_sub(entry_fee_shares).ok_or(ContractError::Underflow)?;
+
+    Ok((amounts, shares_to_mint, total_supply))
+}
+
+/// Validates the deposit inputs and calculates the amounts to be deposited and the shares to mint,
+/// without transferring any tokens.
+pub fn calculate_deposit_shares(
+    e: &Env,
+    total_managed_funds: &Vec<CurrentAssetInvestmentAllocation>,
+    amounts_desired: &Vec<i128>,
+    amounts_min: &Vec<i128>,
+    total_supply: i128,
+) -> Result<(Vec<i128>, i128), ContractError> {
     let assets_length = total_managed_funds.len();
 
     // Validate inputs
@@ -26,7 +67,6 @@ pub fn process_deposit(
         validate_amount(amount)?;
     }
 
-    let total_supply = VaultToken::total_supply(e.clone());
     let (amounts, shares_to_mint) = if assets_length == 1 {
         calculate_single_asset_shares(e, amounts_desired, &total_managed_funds, total_supply)?
     } else {
@@ -38,28 +78,148 @@ pub fn process_deposit(
                 &total_managed_funds,
                 amounts_desired,
                 amounts_min,
+                total_supply,
             )?
         }
     };
 
-    // Transfer assets
     for (i, amount) in amounts.iter().enumerate() {
         if amount < amounts_min.get(i as u32).unwrap() {
             panic_with_error!(&e, ContractError::NoOptimalAmounts);
         }
-        if amount > 0 {
-            let asset = total_managed_funds.get(i as u32).ok_or(ContractError::ArithmeticError)?; 
-            let asset_client = TokenClient::new(&e, &asset.asset);
-            asset_client.transfer(&from, &e.current_contract_address(), &amount);
+    }
+
+    Ok((amounts, shares_to_mint))
+}
+
+/// Processes a deposit of a single asset into the vault.
+///
+/// The full `amount` is transferred from the depositor to the vault, and part of it is swapped through the
+/// Soroswap router into every other vault asset so that the resulting amounts match the vault's current ratio.
+/// Shares are minted for the matched amounts, and any leftover produced by swap rounding is returned to the depositor.
+///
+/// # Arguments
+/// * `e` - The current environment reference.
+/// * `total_managed_funds` - The vault's managed funds, fetched before the deposit.
+/// * `asset` - The address of the asset being deposited.
+/// * `amount` - The amount of `asset` to deposit.
+/// * `from` - The address of the depositor.
+///
+/// # Returns
+/// * `Result<(Vec<i128>, i128, i128), ContractError>` - The amounts deposited for each asset, the shares minted
+///   and the total supply before the deposit.
+pub fn process_single_asset_deposit(
+    e: &Env,
+    total_managed_funds: &Vec<CurrentAssetInvestmentAllocation>,
+    asset: &Address,
+    amount: i128,
+    from: &Address,
+) -> Result<(Vec<i128>, i128, i128), ContractError> {
+    validate_amount(amount)?;
+
+    let asset_index = total_managed_funds
+        .iter()
+        .position(|allocation| &allocation.asset == asset)
+        .ok_or(ContractError::UnsupportedAsset)? as u32;
+
+    // An empty multi-asset vault has no ratio to match yet, its first deposit must provide every asset
+    if total_managed_funds.len() > 1 && VaultToken::total_supply(e.clone()) == 0 {
+        return Err(ContractError::InsufficientManagedFunds);
+    }
+
+    TokenClient::new(e, asset).transfer(from, &e.current_contract_address(), &amount);
+
+    let (values, total_value) = calculate_single_asset_values(e, total_managed_funds, asset_index)?;
+
+    // Swap part of the deposited asset into each of the other assets
+    let mut amounts_desired: Vec<i128> = Vec::new(e);
+    let mut amounts_min: Vec<i128> = Vec::new(e);
+    let mut remaining_amount = amount;
+    for (i, value) in values.iter().enumerate() {
+        // The deposited amount is split proportionally to the value of every asset
+        let swap_amount = if total_value == 0 {
+            0
+        } else {
+            amount
+                .checked_mul(value)
+                .ok_or(ContractError::ArithmeticError)?
+                .checked_div(total_value)
+                .ok_or(ContractError::ArithmeticError)?
+        };
+        let amount_out = if i as u32 != asset_index && swap_amount > 0 {
+            remaining_amount = remaining_amount.checked_sub(swap_amount).ok_or(ContractError::Underflow)?;
+            swap_in_current_ledger(e, asset, &total_managed_funds.get(i as u32).unwrap().asset, &swap_amount)?
+        } else {
+            0
+        };
+        amounts_desired.push_back(amount_out);
+        amounts_min.push_back(0);
+    }
+    amounts_desired.set(asset_index, remaining_amount);
+
+    let total_supply = VaultToken::total_supply(e.clone());
+    let (amounts, shares_to_mint) = calculate_deposit_shares(
+        e,
+        total_managed_funds,
+        &amounts_desired,
+        &amounts_min,
+        total_supply,
+    )?;
+
+    // Return whatever could not be matched to the vault ratio
+    for (i, amount) in amounts.iter().enumerate() {
+        let leftover = amounts_desired
+            .get(i as u32)
+            .unwrap()
+            .checked_sub(amount)
+            .ok_or(ContractError::Underflow)?;
+        if leftover > 0 {
+            let asset_client = TokenClient::new(e, &total_managed_funds.get(i as u32).unwrap().asset);
+            asset_client.transfer(&e.current_contract_address(), from, &leftover);
         }
     }
 
-    // Mint shares
-    mint_shares(e, &total_supply, shares_to_mint, from.clone())?;
+    let entry_fee_shares = mint_shares(e, &total_supply, shares_to_mint, from.clone())?;
+    let shares_to_mint = shares_to_mint.checked_sub(entry_fee_shares).ok_or(ContractError::Underflow)?;
 
     Ok((amounts, shares_to_mint, total_supply))
 }
 
+/// Values every asset's total managed funds in units of the deposited asset, using the Soroswap pair reserves
+/// (grossed up by the 0.3% pair fee).
+///
+/// Returns the value of each asset and their total.
+fn calculate_single_asset_values(
+    e: &Env,
+    total_managed_funds: &Vec<CurrentAssetInvestmentAllocation>,
+    asset_index: u32,
+) -> Result<(Vec<i128>, i128), ContractError> {
+    let asset = total_managed_funds.get(asset_index).unwrap().asset;
+
+    let mut values: Vec<i128> = Vec::new(e);
+    let mut total_value: i128 = 0;
+    for (i, allocation) in total_managed_funds.iter().enumerate() {
+        let value = if i as u32 == asset_index || allocation.total_amount == 0 {
+            allocation.total_amount
+        } else {
+            // The pair fee is charged on the input, so the swapped side is worth slightly more deposited asset
+            let (reserve_in, reserve_out) = get_pair_reserves(e, &asset, &allocation.asset)?;
+            allocation
+                .total_amount
+                .checked_mul(reserve_in)
+                .ok_or(ContractError::ArithmeticError)?
+                .checked_mul(SOROSWAP_FEE_DENOMINATOR)
+                .ok_or(ContractError::ArithmeticError)?
+                .checked_div(reserve_out.checked_mul(SOROSWAP_FEE_NUMERATOR).ok_or(ContractError::ArithmeticError)?)
+                .ok_or(ContractError::ArithmeticError)?
+        };
+        total_value = total_value.checked_add(value).ok_or(ContractError::Overflow)?;
+        values.push_back(value);
+    }
+
+    Ok((values, total_value))
+}
+
 /// Calculate shares for single-asset deposits.
 fn calculate_single_asset_shares(
     e: &Env,
@@ -70,7 +230,7 @@ fn calculate_single_asset_shares(
     let shares = if total_supply == 0 {
         amounts_desired.get(0).unwrap()
     } else {
-        VaultToken::total_supply(e.clone())
+        total_supply
             .checked_mul(amounts_desired.get(0).unwrap())
             .unwrap_or_else(|| panic_with_error!(&e, ContractError::ArithmeticError))
             .checked_div(
@@ -84,30 +244,65 @@ fn calculate_single_asset_shares(
     Ok((amounts_desired.clone(), shares))
 }
 
+/// Checks the deposit limits set by the Manager once the deposited amounts and minted shares are known.
+///
+/// `total_managed_funds` must be fetched before the deposit. Fails with `VaultError::DepositCapExceeded` if the
+/// total managed amount of any asset would exceed its cap, or if the share balance of `from` would exceed the
+/// per-address share cap.
+pub fn check_deposit_limits(
+    e: &Env,
+    total_managed_funds: &Vec<CurrentAssetInvestmentAllocation>,
+    amounts: &Vec<i128>,
+    from: &Address,
+) -> Result<(), ContractError> {
+    for (i, asset_allocation) in total_managed_funds.iter().enumerate() {
+        if let Some(cap) = get_deposit_cap(e, &asset_allocation.asset) {
+            let amount = amounts.get(i as u32).unwrap_or(0);
+            let new_total_amount = asset_allocation
+                .total_amount
+                .checked_add(amount)
+                .ok_or(ContractError::Overflow)?;
+            if amount > 0 && new_total_amount > cap {
+                panic_with_error!(e, VaultError::DepositCapExceeded);
+            }
+        }
+    }
+
+    if let Some(cap) = get_user_share_cap(e) {
+        if VaultToken::balance(e.clone(), from.clone()) > cap {
+            panic_with_error!(e, VaultError::DepositCapExceeded);
+        }
+    }
+    Ok(())
+}
+
 /// Mint vault shares.
+///
+/// `shares_to_mint` go to `from`, except for `MINIMUM_LIQUIDITY` kept in the vault on the first deposit, and the
+/// entry fee is charged on the shares minted to `from`. Returns the entry fee, in shares.
 fn mint_shares(
     e: &Env,
     total_supply: &i128,
     shares_to_mint: i128,
     from: Address,
-) -> Result<(), ContractError> {
+) -> Result<i128, ContractError> {
     if shares_to_mint <= 0 {
         panic_with_error!(&e, ContractError::InsufficientAmount);
     }
-    if *total_supply == 0 {
+    let depositor_shares = if *total_supply == 0 {
         if shares_to_mint <= MINIMUM_LIQUIDITY {
             panic_with_error!(&e, ContractError::InsufficientAmount);
         }
         internal_mint(e.clone(), e.current_contract_address(), MINIMUM_LIQUIDITY);
-        internal_mint(
-            e.clone(),
-            from.clone(),
-            shares_to_mint.checked_sub(MINIMUM_LIQUIDITY).unwrap(),
-        );
+        shares_to_mint.checked_sub(MINIMUM_LIQUIDITY).unwrap()
     } else {
-        internal_mint(e.clone(), from, shares_to_mint);
-    }
-    Ok(())
+        shares_to_mint
+    };
+    internal_mint(e.clone(), from.clone(), depositor_shares);
+
+    let entry_fee_shares = calculate_entry_fee_shares(e, depositor_shares)?;
+    pay_fee_shares(e, &from, entry_fee_shares)?;
+    Ok(entry_fee_shares)
 }
 
 #[cfg(test)]
@@ -117,6 +312,6 @@ pub fn test_mint_shares(
     shares_to_mint: i128,
     from: Address,
 ) -> Result<(), ContractError> {
-    let result = mint_shares(e, total_supply, shares_to_mint, from)?;
-    Ok(result)
+    mint_shares(e, total_supply, shares_to_mint, from)?;
+    Ok(())
 }
\ No newline at end of file
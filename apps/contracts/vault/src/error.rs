@@ -1,5 +1,4 @@
 use soroban_sdk::{self, contracterror};
-use soroswap_library::SoroswapLibraryError;
 
 #[contracterror]
 #[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
@@ -47,6 +46,7 @@ pub enum ContractError {
     ManagerNotInQueue = 132,
     SetManagerBeforeTime = 133,
     QueueEmpty = 134,
+    NotAllowlisted = 135,
 
     // Strategy Errors (14x)
     StrategyNotFound = 140,
@@ -64,20 +64,39 @@ pub enum ContractError {
     ExcessiveInputAmount = 161,
     InvalidFeeBps = 162,
 
-    // Add mappings for SoroswapLibraryError
+    // Pair errors
     LibrarySortIdenticalTokens = 190,
 
     // Swap errors
     SoroswapRouterError = 200,
     SwapExactInError = 201,
     SwapExactOutError = 202,
+
+    // Rebalance Errors (23x)
+    MaxLossExceeded = 230,
 }
 
-impl From<SoroswapLibraryError> for ContractError {
-    fn from(err: SoroswapLibraryError) -> Self {
-        match err {
-            SoroswapLibraryError::SortIdenticalTokens => ContractError::LibrarySortIdenticalTokens,
-            _ => panic!("Unhandled SoroswapLibraryError variant: {:?}", err),
-        }
-    }
+// Vault errors that no longer fit in `ContractError`, which is limited to 50 cases.
+// Their codes do not overlap with `ContractError`. They are raised with `panic_with_error!`, so the vault
+// functions keep returning `ContractError`.
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum VaultError {
+    // Withdrawal Queue Errors (17x)
+    WithdrawRequestNotFound = 170,
+    WithdrawRequestNotFirst = 171,
+
+    // Governance Errors (18x)
+    ProposalNotFound = 180,
+    ProposalNotReady = 181,
+    FeeModeNotSupported = 182,
+
+    // Vault Pause Errors (21x)
+    DepositsPaused = 210,
+    WithdrawalsPaused = 211,
+    RebalancesPaused = 212,
+
+    // Deposit Limit Errors (22x)
+    DepositCapExceeded = 220,
 }
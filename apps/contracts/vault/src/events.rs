@@ -1,10 +1,13 @@
 //! Definition of the Events used in the DeFindex Vault contract
+//!
+//! Event structs are not part of the contract interface, so they are kept out of the contract spec
+//! (`export = false`) to save WASM size.
 use soroban_sdk::{contracttype, symbol_short, Address, Env, Symbol, Val, Vec};
 
-use crate::{models::{AssetInvestmentAllocation, CurrentAssetInvestmentAllocation}, report::Report};
+use crate::{models::{AssetInvestmentAllocation, AssetTargetAllocation, CurrentAssetInvestmentAllocation, FeeDistributionMode, FeeReceiver, PauseFlags, ProposalAction}, report::Report};
 
 // DEPOSIT EVENT
-#[contracttype]
+#[contracttype(export = false)]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct VaultDepositEvent {
     pub depositor: Address,
@@ -36,7 +39,7 @@ pub(crate) fn emit_deposit_event(
 }
 
 // WITHDRAW EVENT
-#[contracttype]
+#[contracttype(export = false)]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct VaultWithdrawEvent {
     pub withdrawer: Address,
@@ -67,8 +70,53 @@ pub(crate) fn emit_withdraw_event(
         .publish(("DeFindexVault", symbol_short!("withdraw")), event);
 }
 
+// WITHDRAW REQUEST EVENTS
+#[contracttype(export = false)]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WithdrawRequestEvent {
+    pub request_id: u64,
+    pub owner: Address,
+    pub shares: i128,
+}
+
+/// Publishes a `WithdrawRequestEvent` when a withdrawal request is queued.
+pub(crate) fn emit_withdraw_requested_event(e: &Env, request_id: u64, owner: Address, shares: i128) {
+    let event = WithdrawRequestEvent {
+        request_id,
+        owner,
+        shares,
+    };
+
+    e.events()
+        .publish(("DeFindexVault", symbol_short!("wrequest")), event);
+}
+
+/// Publishes a `WithdrawRequestEvent` when a withdrawal request is cancelled by its owner.
+pub(crate) fn emit_withdraw_request_cancelled_event(e: &Env, request_id: u64, owner: Address, shares: i128) {
+    let event = WithdrawRequestEvent {
+        request_id,
+        owner,
+        shares,
+    };
+
+    e.events()
+        .publish(("DeFindexVault", symbol_short!("wcancel")), event);
+}
+
+/// Publishes a `WithdrawRequestEvent` when a withdrawal request is parked out of the queue.
+pub(crate) fn emit_withdraw_request_parked_event(e: &Env, request_id: u64, owner: Address, shares: i128) {
+    let event = WithdrawRequestEvent {
+        request_id,
+        owner,
+        shares,
+    };
+
+    e.events()
+        .publish(("DeFindexVault", symbol_short!("wpark")), event);
+}
+
 // EMERGENCY WITHDRAW EVENT
-#[contracttype]
+#[contracttype(export = false)]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct EmergencyWithdrawEvent {
     pub caller: Address,
@@ -94,7 +142,7 @@ pub(crate) fn emit_rescue_event(
 }
 
 // STRATEGY PAUSED EVENT
-#[contracttype]
+#[contracttype(export = false)]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct StrategyPausedEvent {
     pub strategy_address: Address,
@@ -113,7 +161,7 @@ pub(crate) fn emit_strategy_paused_event(e: &Env, strategy_address: Address, cal
 }
 
 // STRATEGY UNPAUSED EVENT
-#[contracttype]
+#[contracttype(export = false)]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct StrategyUnpausedEvent {
     pub strategy_address: Address,
@@ -132,7 +180,7 @@ pub(crate) fn emit_strategy_unpaused_event(e: &Env, strategy_address: Address, c
 }
 
 // FEE RECEIVER CHANGED EVENT
-#[contracttype]
+#[contracttype(export = false)]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct FeeReceiverChangedEvent {
     pub new_fee_receiver: Address,
@@ -150,8 +198,27 @@ pub(crate) fn emit_fee_receiver_changed_event(e: &Env, new_fee_receiver: Address
         .publish(("DeFindexVault", symbol_short!("nreceiver")), event);
 }
 
+// FEE RECEIVERS CHANGED EVENT
+#[contracttype(export = false)]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FeeReceiversChangedEvent {
+    pub fee_receivers: Vec<FeeReceiver>,
+    pub caller: Address,
+}
+
+/// Publishes a `FeeReceiversChangedEvent` to the event stream.
+pub(crate) fn emit_fee_receivers_changed_event(e: &Env, fee_receivers: Vec<FeeReceiver>, caller: Address) {
+    let event = FeeReceiversChangedEvent {
+        fee_receivers,
+        caller,
+    };
+
+    e.events()
+        .publish(("DeFindexVault", symbol_short!("nreceivrs")), event);
+}
+
 // MANAGER CHANGED EVENT
-#[contracttype]
+#[contracttype(export = false)]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct ManagerChangedEvent {
     pub new_manager: Address,
@@ -165,8 +232,129 @@ pub(crate) fn emit_manager_changed_event(e: &Env, new_manager: Address) {
         .publish(("DeFindexVault", symbol_short!("nmanager")), event);
 }
 
+// MANAGER QUEUED EVENT
+#[contracttype(export = false)]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ManagerQueuedEvent {
+    pub new_manager: Address,
+    pub available_at: u64,
+}
+
+/// Publishes a `ManagerQueuedEvent` to the event stream.
+pub(crate) fn emit_manager_queued_event(e: &Env, new_manager: Address, available_at: u64) {
+    let event = ManagerQueuedEvent {
+        new_manager,
+        available_at,
+    };
+
+    e.events()
+        .publish(("DeFindexVault", symbol_short!("qmanager")), event);
+}
+
+// MANAGER QUEUE CANCELLED EVENT
+#[contracttype(export = false)]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ManagerQueueCancelledEvent {
+    pub new_manager: Address,
+}
+
+/// Publishes a `ManagerQueueCancelledEvent` to the event stream.
+pub(crate) fn emit_manager_queue_cancelled_event(e: &Env, new_manager: Address) {
+    let event = ManagerQueueCancelledEvent { new_manager };
+
+    e.events()
+        .publish(("DeFindexVault", symbol_short!("cmanager")), event);
+}
+
+// MANAGER TIMELOCK CHANGED EVENT
+#[contracttype(export = false)]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ManagerTimelockChangedEvent {
+    pub delay: u64,
+    pub effective_at: u64,
+}
+
+/// Publishes a `ManagerTimelockChangedEvent` to the event stream.
+pub(crate) fn emit_manager_timelock_changed_event(e: &Env, delay: u64, effective_at: u64) {
+    let event = ManagerTimelockChangedEvent { delay, effective_at };
+
+    e.events()
+        .publish(("DeFindexVault", symbol_short!("mtimelock")), event);
+}
+
+// PAUSE FLAGS CHANGED EVENT
+#[contracttype(export = false)]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PauseFlagsChangedEvent {
+    pub flags: PauseFlags,
+}
+
+/// Publishes a `PauseFlagsChangedEvent` to the event stream.
+pub(crate) fn emit_pause_flags_changed_event(e: &Env, flags: PauseFlags) {
+    let event = PauseFlagsChangedEvent { flags };
+
+    e.events()
+        .publish(("DeFindexVault", symbol_short!("npause")), event);
+}
+
+// PROPOSAL CREATED EVENT
+#[contracttype(export = false)]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProposalCreatedEvent {
+    pub proposal_id: u64,
+    pub action: ProposalAction,
+    pub executable_at: u64,
+}
+
+/// Publishes a `ProposalCreatedEvent` to the event stream.
+pub(crate) fn emit_proposal_created_event(
+    e: &Env,
+    proposal_id: u64,
+    action: ProposalAction,
+    executable_at: u64,
+) {
+    let event = ProposalCreatedEvent {
+        proposal_id,
+        action,
+        executable_at,
+    };
+
+    e.events()
+        .publish(("DeFindexVault", symbol_short!("proposal")), event);
+}
+
+// PROPOSAL EXECUTED EVENT
+#[contracttype(export = false)]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProposalExecutedEvent {
+    pub proposal_id: u64,
+}
+
+/// Publishes a `ProposalExecutedEvent` to the event stream.
+pub(crate) fn emit_proposal_executed_event(e: &Env, proposal_id: u64) {
+    let event = ProposalExecutedEvent { proposal_id };
+
+    e.events()
+        .publish(("DeFindexVault", symbol_short!("pexecuted")), event);
+}
+
+// PROPOSAL VETOED EVENT
+#[contracttype(export = false)]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProposalVetoedEvent {
+    pub proposal_id: u64,
+}
+
+/// Publishes a `ProposalVetoedEvent` to the event stream.
+pub(crate) fn emit_proposal_vetoed_event(e: &Env, proposal_id: u64) {
+    let event = ProposalVetoedEvent { proposal_id };
+
+    e.events()
+        .publish(("DeFindexVault", symbol_short!("pvetoed")), event);
+}
+
 // EMERGENCY MANAGER CHANGED EVENT
-#[contracttype]
+#[contracttype(export = false)]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct EmergencyManagerChangedEvent {
     pub new_emergency_manager: Address,
@@ -183,7 +371,7 @@ pub(crate) fn emit_emergency_manager_changed_event(e: &Env, new_emergency_manage
 }
 
 // REBALANCE MANAGER CHANGED EVENT
-#[contracttype]
+#[contracttype(export = false)]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct RebalanceManagerChangedEvent {
     pub new_rebalance_manager: Address,
@@ -199,8 +387,202 @@ pub(crate) fn emit_rebalance_manager_changed_event(e: &Env, new_rebalance_manage
         .publish(("DeFindexVault", symbol_short!("rbmanager")), event);
 }
 
+// ALLOWLIST MANAGER CHANGED EVENT
+#[contracttype(export = false)]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AllowlistManagerChangedEvent {
+    pub new_allowlist_manager: Address,
+}
+
+/// Publishes an `AllowlistManagerChangedEvent` to the event stream.
+pub(crate) fn emit_allowlist_manager_changed_event(e: &Env, new_allowlist_manager: Address) {
+    let event = AllowlistManagerChangedEvent {
+        new_allowlist_manager,
+    };
+
+    e.events()
+        .publish(("DeFindexVault", symbol_short!("almanager")), event);
+}
+
+// ALLOWLIST ENABLED EVENT
+#[contracttype(export = false)]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AllowlistEnabledEvent {
+    pub enabled: bool,
+}
+
+/// Publishes an `AllowlistEnabledEvent` to the event stream.
+pub(crate) fn emit_allowlist_enabled_event(e: &Env, enabled: bool) {
+    let event = AllowlistEnabledEvent { enabled };
+
+    e.events()
+        .publish(("DeFindexVault", symbol_short!("allowmode")), event);
+}
+
+// ALLOWLIST CHANGED EVENT
+#[contracttype(export = false)]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AllowlistChangedEvent {
+    pub address: Address,
+    pub allowed: bool,
+}
+
+/// Publishes an `AllowlistChangedEvent` to the event stream.
+pub(crate) fn emit_allowlist_changed_event(e: &Env, address: Address, allowed: bool) {
+    let event = AllowlistChangedEvent { address, allowed };
+
+    e.events()
+        .publish(("DeFindexVault", symbol_short!("nallow")), event);
+}
+
+// TARGET ALLOCATION CHANGED EVENT
+#[contracttype(export = false)]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TargetAllocationChangedEvent {
+    pub target_allocation: AssetTargetAllocation,
+}
+
+/// Publishes a `TargetAllocationChangedEvent` to the event stream.
+pub(crate) fn emit_target_allocation_changed_event(e: &Env, target_allocation: AssetTargetAllocation) {
+    let event = TargetAllocationChangedEvent { target_allocation };
+
+    e.events()
+        .publish(("DeFindexVault", symbol_short!("ntarget")), event);
+}
+
+// REBALANCE TOLERANCE CHANGED EVENT
+#[contracttype(export = false)]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RebalanceToleranceChangedEvent {
+    pub tolerance_bps: u32,
+}
+
+/// Publishes a `RebalanceToleranceChangedEvent` to the event stream.
+pub(crate) fn emit_rebalance_tolerance_changed_event(e: &Env, tolerance_bps: u32) {
+    let event = RebalanceToleranceChangedEvent { tolerance_bps };
+
+    e.events()
+        .publish(("DeFindexVault", symbol_short!("tolerance")), event);
+}
+
+// IDLE RESERVE CHANGED EVENT
+#[contracttype(export = false)]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct IdleReserveChangedEvent {
+    pub asset: Address,
+    pub reserve_bps: u32,
+}
+
+/// Publishes an `IdleReserveChangedEvent` to the event stream.
+pub(crate) fn emit_idle_reserve_changed_event(e: &Env, asset: Address, reserve_bps: u32) {
+    let event = IdleReserveChangedEvent { asset, reserve_bps };
+
+    e.events()
+        .publish(("DeFindexVault", symbol_short!("nreserve")), event);
+}
+
+// STRATEGY ADDED EVENT
+#[contracttype(export = false)]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StrategyAddedEvent {
+    pub asset: Address,
+    pub strategy_address: Address,
+}
+
+/// Publishes a `StrategyAddedEvent` to the event stream.
+pub(crate) fn emit_strategy_added_event(e: &Env, asset: Address, strategy_address: Address) {
+    let event = StrategyAddedEvent {
+        asset,
+        strategy_address,
+    };
+
+    e.events()
+        .publish(("DeFindexVault", symbol_short!("nstrategy")), event);
+}
+
+// STRATEGY REMOVED EVENT
+#[contracttype(export = false)]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StrategyRemovedEvent {
+    pub asset: Address,
+    pub strategy_address: Address,
+}
+
+/// Publishes a `StrategyRemovedEvent` to the event stream.
+pub(crate) fn emit_strategy_removed_event(e: &Env, asset: Address, strategy_address: Address) {
+    let event = StrategyRemovedEvent {
+        asset,
+        strategy_address,
+    };
+
+    e.events()
+        .publish(("DeFindexVault", symbol_short!("rstrategy")), event);
+}
+
+// WITHDRAW ORDER CHANGED EVENT
+#[contracttype(export = false)]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WithdrawOrderChangedEvent {
+    pub asset: Address,
+    pub strategies: Vec<Address>,
+}
+
+/// Publishes a `WithdrawOrderChangedEvent` to the event stream.
+pub(crate) fn emit_withdraw_order_changed_event(e: &Env, asset: Address, strategies: Vec<Address>) {
+    let event = WithdrawOrderChangedEvent { asset, strategies };
+
+    e.events()
+        .publish(("DeFindexVault", symbol_short!("nworder")), event);
+}
+
+// DEPOSIT CAP CHANGED EVENT
+#[contracttype(export = false)]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DepositCapChangedEvent {
+    pub asset: Address,
+    pub cap: Option<i128>,
+}
+
+/// Publishes a `DepositCapChangedEvent` to the event stream.
+pub(crate) fn emit_deposit_cap_changed_event(e: &Env, asset: Address, cap: Option<i128>) {
+    let event = DepositCapChangedEvent { asset, cap };
+
+    e.events()
+        .publish(("DeFindexVault", symbol_short!("ndepcap")), event);
+}
+
+// USER SHARE CAP CHANGED EVENT
+#[contracttype(export = false)]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UserShareCapChangedEvent {
+    pub cap: Option<i128>,
+}
+
+/// Publishes a `UserShareCapChangedEvent` to the event stream.
+pub(crate) fn emit_user_share_cap_changed_event(e: &Env, cap: Option<i128>) {
+    let event = UserShareCapChangedEvent { cap };
+
+    e.events()
+        .publish(("DeFindexVault", symbol_short!("nusercap")), event);
+}
+
+// FEE DISTRIBUTION MODE CHANGED EVENT
+#[contracttype(export = false)]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FeeDistributionModeChangedEvent {
+    pub mode: FeeDistributionMode,
+}
+
+/// Publishes a `FeeDistributionModeChangedEvent` to the event stream.
+pub(crate) fn emit_fee_distribution_mode_changed_event(e: &Env, mode: FeeDistributionMode) {
+    let event = FeeDistributionModeChangedEvent { mode };
+
+    e.events()
+        .publish(("DeFindexVault", symbol_short!("nfeemode")), event);
+}
+
 // FEES DISTRIBUTED EVENT
-#[contracttype]
+#[contracttype(export = false)]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct FeesDistributedEvent {
     pub distributed_fees: Vec<(Address, i128)>,
@@ -214,8 +596,60 @@ pub(crate) fn emit_fees_distributed_event(e: &Env, distributed_fees: Vec<(Addres
         .publish(("DeFindexVault", symbol_short!("dfees")), event);
 }
 
+// MANAGEMENT FEE ACCRUED EVENT
+#[contracttype(export = false)]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ManagementFeeAccruedEvent {
+    pub vault_fee_shares: i128,
+    pub defindex_fee_shares: i128,
+    pub elapsed: u64,
+}
+
+/// Publishes a `ManagementFeeAccruedEvent` to the event stream.
+pub(crate) fn emit_management_fee_accrued_event(
+    e: &Env,
+    vault_fee_shares: i128,
+    defindex_fee_shares: i128,
+    elapsed: u64,
+) {
+    let event = ManagementFeeAccruedEvent {
+        vault_fee_shares,
+        defindex_fee_shares,
+        elapsed,
+    };
+
+    e.events()
+        .publish(("DeFindexVault", symbol_short!("mfees")), event);
+}
+
+// ENTRY OR EXIT FEE CHARGED EVENT
+#[contracttype(export = false)]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TransactionFeeEvent {
+    pub payer: Address,
+    pub vault_fee_shares: i128,
+    pub defindex_fee_shares: i128,
+}
+
+/// Publishes a `TransactionFeeEvent` to the event stream.
+pub(crate) fn emit_transaction_fee_event(
+    e: &Env,
+    payer: Address,
+    vault_fee_shares: i128,
+    defindex_fee_shares: i128,
+) {
+    let event = TransactionFeeEvent {
+        payer,
+        vault_fee_shares,
+        defindex_fee_shares,
+    };
+
+    e.events()
+        .publish(("DeFindexVault", symbol_short!("txfees")), event);
+}
+
 // REBALANCE WITHDRAW EVENT
-#[contracttype]
+#[contracttype(export = false)]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct UnwindEvent {
     pub call_params: Vec<(Address, i128, Address)>,
@@ -241,7 +675,7 @@ pub(crate) fn emit_rebalance_unwind_event(
 }
 
 // REBALANCE INVEST EVENT
-#[contracttype]
+#[contracttype(export = false)]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct InvestEvent {
     pub asset_investments: Vec<AssetInvestmentAllocation>,
@@ -266,7 +700,7 @@ pub(crate) fn emit_rebalance_invest_event(
 
 // SWAP EXACT IN EVENT
 
-#[contracttype]
+#[contracttype(export = false)]
 #[derive(Clone, Debug, Eq, PartialEq)]
 
 pub struct SwapExactInEvent {
@@ -288,7 +722,7 @@ pub(crate) fn emit_rebalance_swap_exact_in_event(
 }
 
 // SWAP EXACT OUT EVENT
-#[contracttype]
+#[contracttype(export = false)]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct SwapExactOutEvent {
     pub swap_args: Vec<Val>,
@@ -309,8 +743,7 @@ pub(crate) fn emit_rebalance_swap_exact_out_event(
 }
 
 // ZAPPER EVENT
-//Waiting for zapper implementation
-/* #[contracttype]
+#[contracttype(export = false)]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct ZapperEvent {
     pub zap_args: Vec<Val>,
@@ -331,4 +764,4 @@ pub(crate) fn emit_rebalance_zapper_event(
 
     e.events()
         .publish(("DeFindexVault", symbol_short!("rebalance")), event);
-} */
\ No newline at end of file
+}
\ No newline at end of file
@@ -108,7 +108,8 @@ pub fn fetch_invested_funds_for_asset(
 /// # Arguments
 /// * `e` - The current environment instance.
 /// * `lock_fees` - A flag indicating whether to update strategy reports and lock new fees before calculating invested funds.
-///   If `true`, new fees are locked; if `false`, only existing locked fees are subtracted from invested funds.
+///   If `true`, new fees are locked and the management fee is accrued by minting shares; if `false`, only existing locked
+///   fees are subtracted from invested funds.
 ///
 /// # Returns
 /// * A vector where each entry represents an asset's total managed balance, including idle and invested funds (net of locked fees).
@@ -117,6 +118,10 @@ pub fn fetch_total_managed_funds(
     e: &Env,
     lock_fees: bool,
 ) -> Result<Vec<CurrentAssetInvestmentAllocation>, ContractError> {
+    if lock_fees {
+        report::accrue_management_fee(e)?;
+    }
+
     let assets = get_assets(e)?;
     let mut allocations: Vec<CurrentAssetInvestmentAllocation> = Vec::new(e);
     for asset in &assets {
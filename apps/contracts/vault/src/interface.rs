@@ -1,9 +1,12 @@
-use soroban_sdk::{Address, BytesN, Env, Map, String, Vec};
+use soroban_sdk::{Address, Env, Map, String, Vec};
 
 use crate::{
-    models::{AssetInvestmentAllocation, CurrentAssetInvestmentAllocation, Instruction}, report::Report, ContractError
+    models::{AssetInvestmentAllocation, AssetTargetAllocation, CurrentAssetInvestmentAllocation, FeeDistributionMode, FeeReceiver, Instruction, PauseFlags, Proposal, ProposalAction, QueuedManager, RebalanceSimulation, WithdrawRequest}, report::Report, ContractError
 };
-use common::models::AssetStrategySet;
+use common::models::{AssetStrategySet, Strategy};
+
+/// Amounts deposited for each asset, shares minted and optional investment allocations of a deposit.
+pub type DepositResult = (Vec<i128>, i128, Option<Vec<Option<AssetInvestmentAllocation>>>);
 
 pub trait VaultTrait {
     /// Initializes the DeFindex Vault contract with the required parameters.
@@ -16,6 +19,7 @@ pub trait VaultTrait {
     ///   - Vault Fee Receiver: For receiving vault fees
     ///   - Manager: For primary vault control
     ///   - Rebalance Manager: For rebalancing operations
+    ///   - Allowlist Manager (optional): For maintaining the depositor allowlist
     /// * `vault_fee` - Vault-specific fee in basis points (0-2000 for 0-20%)
     /// * `defindex_protocol_receiver` - Address receiving protocol fees
     /// * `defindex_protocol_rate` - Protocol fee rate in basis points (0-9000 for 0-90%)
@@ -31,6 +35,7 @@ pub trait VaultTrait {
     ///    - Sets Vault Fee Receiver
     ///    - Sets Manager
     ///    - Sets Rebalance Manager
+    ///    - Sets Allowlist Manager, if provided
     ///
     /// 2. **Fee Configuration**:
     ///    - Sets vault fee rate
@@ -91,7 +96,7 @@ pub trait VaultTrait {
     /// # Returns
     /// * `Result<(Vec<i128>, i128, Option<Vec<Option<AssetInvestmentAllocation>>>), ContractError>` - Returns:
     ///   - A vector of actual deposited amounts
-    ///   - The number of shares minted
+    ///   - The number of shares minted to the depositor, net of the entry fee
     ///   - Optional investment allocations if `invest` is true
     ///
     /// # Function Flow
@@ -122,6 +127,8 @@ pub trait VaultTrait {
     ///   - Input validation fails
     ///   - Asset transfers fail
     ///   - Share calculations encounter arithmetic errors
+    ///   - The deposit exceeds an asset deposit cap or the per-address share cap (`DepositCapExceeded`)
+    ///   - The allowlist is enabled and the depositor is not on it (`NotAllowlisted`)
     ///   - Investment execution fails (when `invest` is true)
     fn deposit(
         e: Env,
@@ -129,17 +136,58 @@ pub trait VaultTrait {
         amounts_min: Vec<i128>,
         from: Address,
         invest: bool,
-    ) -> Result<(Vec<i128>, i128, Option<Vec<Option<AssetInvestmentAllocation>>>), ContractError>;
+    ) -> Result<DepositResult, ContractError>;
+
+    /// Handles a single-asset deposit into a (possibly multi-asset) DeFindex Vault.
+    ///
+    /// The depositor provides only `asset`. Part of the deposited amount is swapped through the configured
+    /// Soroswap router into each of the other vault assets so that the resulting amounts match the vault's
+    /// current ratio, and vault shares are minted for the matched amounts. Any leftover caused by swap rounding
+    /// is returned to the depositor.
+    ///
+    /// # Parameters
+    /// * `e` - The current environment reference (`Env`).
+    /// * `asset` - The address of the asset being deposited. Must be one of the vault assets.
+    /// * `amount` - The amount of `asset` to deposit.
+    /// * `min_shares` - The minimum amount of vault shares that must be minted for the transaction to proceed.
+    /// * `from` - The address of the user making the deposit.
+    /// * `invest` - Whether to immediately invest the deposited funds into the vault's strategies.
+    ///
+    /// # Returns
+    /// * `Result<(Vec<i128>, i128, Option<Vec<Option<AssetInvestmentAllocation>>>), ContractError>` - Returns:
+    ///   - A vector of the amounts deposited for each asset after the swaps
+    ///   - The number of shares minted to the depositor, net of the entry fee
+    ///   - Optional investment allocations if `invest` is true
+    ///
+    /// # Errors
+    /// * `ContractError::UnsupportedAsset` - If `asset` is not managed by the vault.
+    /// * `ContractError::InsufficientManagedFunds` - If the vault has several assets and no shares yet; its first
+    ///   deposit must go through `deposit`.
+    /// * `ContractError::InsufficientOutputAmount` - If fewer than `min_shares` shares would be minted.
+    /// * `ContractError::SwapExactInError` - If any of the swaps fails.
+    /// * `VaultError::DepositCapExceeded` - If the deposit exceeds an asset deposit cap or the per-address
+    ///   share cap.
+    /// * `ContractError::NotAllowlisted` - If the allowlist is enabled and `from` is not on it.
+    fn deposit_single_asset(
+        e: Env,
+        asset: Address,
+        amount: i128,
+        min_shares: i128,
+        from: Address,
+        invest: bool,
+    ) -> Result<DepositResult, ContractError>;
 
     /// Handles user withdrawals from the DeFindex Vault by burning shares and returning assets.
     ///
     /// This function processes a withdrawal request by burning the specified amount of vault shares
     /// and returning a proportional amount of the vault's assets to the user. It can unwind positions
-    /// from strategies if necessary to fulfill the withdrawal.
+    /// from strategies if necessary to fulfill the withdrawal. If an exit fee is set, that share of
+    /// the withdrawn shares is paid to the fee receivers and only the rest is burned.
     ///
     /// ## Parameters:
     /// - `e`: The contract environment (`Env`).
     /// - `withdraw_shares`: The number of vault shares to withdraw.
+    /// - `min_amounts_out`: A vector of minimum amounts required for each asset to be withdrawn.
     /// - `from`: The address initiating the withdrawal.
     ///
     /// ## Returns
@@ -151,13 +199,176 @@ pub trait VaultTrait {
     /// - `ContractError::AmountOverTotalSupply`: If the specified shares exceed the total supply.
     /// - `ContractError::ArithmeticError`: If any arithmetic operation fails during calculations.
     /// - `ContractError::WrongAmountsLength`: If there is a mismatch in asset allocation data.
+    /// - `VaultError::WithdrawRequestNotFirst`: If withdrawal requests are pending in the withdrawal queue.
     fn withdraw(e: Env, df_amount: i128, min_amounts_out: Vec<i128>, from: Address) -> Result<Vec<i128>, ContractError>;
 
+    /// Handles user withdrawals from the DeFindex Vault into a single asset.
+    ///
+    /// Burns `withdraw_shares` and unwinds the proportional amount of every vault asset exactly as `withdraw`
+    /// does, then swaps every asset other than `out_asset` through the configured Soroswap router so that the
+    /// user receives `out_asset` only.
+    ///
+    /// ## Parameters:
+    /// - `e`: The contract environment (`Env`).
+    /// - `withdraw_shares`: The number of vault shares to withdraw.
+    /// - `out_asset`: The vault asset the user wants to receive.
+    /// - `min_out`: The minimum amount of `out_asset` the user accepts to receive.
+    /// - `from`: The address initiating the withdrawal.
+    ///
+    /// ## Returns
+    /// * `Result<i128, ContractError>` - On success, returns the amount of `out_asset` sent to `from`.
+    ///
+    /// ## Errors:
+    /// - `ContractError::UnsupportedAsset`: If `out_asset` is not managed by the vault.
+    /// - `ContractError::InsufficientOutputAmount`: If the user would receive less than `min_out`.
+    /// - `ContractError::AmountOverTotalSupply`: If the specified shares exceed the total supply.
+    /// - `ContractError::SwapExactInError`: If any of the swaps fails.
+    /// - `VaultError::WithdrawRequestNotFirst`: If withdrawal requests are pending in the withdrawal queue.
+    fn withdraw_to_asset(
+        e: Env,
+        withdraw_shares: i128,
+        out_asset: Address,
+        min_out: i128,
+        from: Address,
+    ) -> Result<i128, ContractError>;
+
+    /// Handles user withdrawals of exact asset amounts from the DeFindex Vault.
+    ///
+    /// Calculates the number of shares needed to deliver exactly `amounts_out` of each asset, rounding up in
+    /// favour of the vault, burns them and transfers the requested amounts, unwinding strategies if necessary.
+    /// If an exit fee is set, it is paid in shares on top of the burned shares.
+    ///
+    /// The burned shares are those needed for the asset requested in the largest proportion of its total managed
+    /// funds. If `amounts_out` is not proportional to the vault's holdings, the value of the other assets that
+    /// these shares are worth but that is not requested stays in the vault, to the benefit of the remaining
+    /// holders. Use `preview_withdraw` to check the shares to burn beforehand.
+    ///
+    /// ## Parameters:
+    /// - `e`: The contract environment (`Env`).
+    /// - `amounts_out`: The exact amount of each asset to withdraw, in the vault's asset order.
+    /// - `max_shares_in`: The maximum number of vault shares the user accepts to burn.
+    /// - `from`: The address initiating the withdrawal.
+    ///
+    /// ## Returns
+    /// * `Result<i128, ContractError>` - On success, returns the number of vault shares given up, exit fee included.
+    ///
+    /// ## Errors:
+    /// - `ContractError::ExcessiveInputAmount`: If more than `max_shares_in` shares would be burned.
+    /// - `ContractError::InsufficientManagedFunds`: If the vault does not hold the requested amounts.
+    /// - `ContractError::WrongAmountsLength`: If `amounts_out` does not match the number of assets.
+    /// - `VaultError::WithdrawRequestNotFirst`: If withdrawal requests are pending in the withdrawal queue.
+    fn withdraw_exact_amounts(
+        e: Env,
+        amounts_out: Vec<i128>,
+        max_shares_in: i128,
+        from: Address,
+    ) -> Result<i128, ContractError>;
+
+    /// Requests an asynchronous withdrawal from the DeFindex Vault.
+    ///
+    /// The shares, net of the exit fee, are escrowed by the vault and a withdrawal request is appended to the
+    /// withdrawal queue.
+    /// Requests are completed in order with `claim_withdraw` or `process_withdraw_queue` once the vault
+    /// has enough liquidity, for example when a strategy cannot return funds right away.
+    ///
+    /// ## Parameters:
+    /// - `e`: The contract environment (`Env`).
+    /// - `withdraw_shares`: The number of vault shares to withdraw.
+    /// - `from`: The address initiating the withdrawal request.
+    ///
+    /// ## Returns
+    /// * `Result<u64, ContractError>` - On success, returns the id of the withdrawal request.
+    fn request_withdraw(e: Env, withdraw_shares: i128, from: Address) -> Result<u64, ContractError>;
+
+    /// Completes a pending withdrawal request.
+    ///
+    /// Requests are completed in order: only the oldest pending request can be claimed. The escrowed shares
+    /// are burned and the corresponding amounts, valued at the time of the claim, are sent to the owner of the
+    /// request. Anyone can claim on behalf of the owner.
+    ///
+    /// ## Parameters:
+    /// - `e`: The contract environment (`Env`).
+    /// - `request_id`: The id of the withdrawal request.
+    ///
+    /// ## Returns
+    /// * `Result<Vec<i128>, ContractError>` - On success, returns the amounts sent to the owner for each asset.
+    ///
+    /// ## Errors:
+    /// - `VaultError::WithdrawRequestNotFound`: If there is no pending request with this id.
+    /// - `VaultError::WithdrawRequestNotFirst`: If an older request is still pending.
+    /// - `ContractError::StrategyWithdrawError`: If a strategy still cannot return the funds.
+    fn claim_withdraw(e: Env, request_id: u64) -> Result<Vec<i128>, ContractError>;
+
+    /// Cancels a withdrawal request and returns the escrowed shares to its owner.
+    ///
+    /// Works for requests still in the queue and for requests parked with `park_withdraw_request`.
+    /// If the request was at the head of the queue, the head moves to the next request.
+    ///
+    /// ## Parameters:
+    /// - `e`: The contract environment (`Env`).
+    /// - `request_id`: The id of the withdrawal request. Requires the authorization of its owner.
+    ///
+    /// ## Returns
+    /// * `Result<i128, ContractError>` - On success, returns the number of shares returned to the owner.
+    ///
+    /// ## Errors:
+    /// - `VaultError::WithdrawRequestNotFound`: If there is no pending request with this id.
+    fn cancel_withdraw_request(e: Env, request_id: u64) -> Result<i128, ContractError>;
+
+    /// Parks the withdrawal request at the head of the queue so the following requests can proceed.
+    ///
+    /// Meant for a head request that cannot complete, for example because its owner can no longer receive
+    /// one of the assets. The request keeps its escrowed shares but can no longer be claimed; its owner can
+    /// recover the shares with `cancel_withdraw_request`. Only the Emergency Manager can call this function.
+    ///
+    /// ## Parameters:
+    /// - `e`: The contract environment (`Env`).
+    /// - `request_id`: The id of the withdrawal request at the head of the queue.
+    ///
+    /// ## Returns
+    /// * `Result<(), ContractError>` - Ok if successful, otherwise returns a ContractError.
+    ///
+    /// ## Errors:
+    /// - `VaultError::WithdrawRequestNotFound`: If there is no pending request with this id.
+    /// - `VaultError::WithdrawRequestNotFirst`: If the request is not at the head of the queue.
+    fn park_withdraw_request(e: Env, request_id: u64) -> Result<(), ContractError>;
+
+    /// Completes up to `max_requests` pending withdrawal requests, oldest first.
+    ///
+    /// Intended for keepers. If any of the requests cannot be completed yet, the whole call fails, so
+    /// keepers should retry later or with a smaller `max_requests`.
+    ///
+    /// ## Parameters:
+    /// - `e`: The contract environment (`Env`).
+    /// - `max_requests`: The maximum number of requests to complete.
+    ///
+    /// ## Returns
+    /// * `Result<Vec<u64>, ContractError>` - On success, returns the ids of the completed requests.
+    fn process_withdraw_queue(e: Env, max_requests: u32) -> Result<Vec<u64>, ContractError>;
+
+    /// Returns a pending withdrawal request.
+    ///
+    /// ## Parameters:
+    /// - `e`: The contract environment (`Env`).
+    /// - `request_id`: The id of the withdrawal request.
+    ///
+    /// ## Returns
+    /// * `Result<WithdrawRequest, ContractError>` - The request, or `VaultError::WithdrawRequestNotFound`
+    ///   if it does not exist or has already been claimed.
+    fn get_withdraw_request(e: Env, request_id: u64) -> Result<WithdrawRequest, ContractError>;
+
+    /// Returns the bounds of the withdrawal queue.
+    ///
+    /// ## Returns
+    /// * `(u64, u64)` - The id of the oldest pending request and the id that will be assigned to the next request.
+    ///   The queue is empty when both are equal.
+    fn get_withdraw_queue(e: Env) -> (u64, u64);
+
     /// Executes rescue (formerly emergency withdrawal) from a specific strategy.
     ///
     /// This function allows the emergency manager or manager to withdraw all assets from a particular strategy
     /// and store them as idle funds within the vault. It also pauses the strategy to prevent further use until
-    /// unpaused.
+    /// unpaused. Unrecovered losses of the strategy are kept, so fees are only charged again once they are recovered.
     ///
     /// # Arguments:
     /// * `e` - The environment.
@@ -208,6 +419,28 @@ pub trait VaultTrait {
         caller: Address,
     ) -> Result<(), ContractError>;
 
+    /// Sets the vault-wide pause flags.
+    ///
+    /// This function allows the emergency manager to halt deposits, withdrawals and rebalances of the whole vault,
+    /// independently of each other. Strategy level pauses and `rescue` are not affected.
+    ///
+    /// # Arguments:
+    /// * `e` - The environment.
+    /// * `flags` - The new pause flags.
+    ///
+    /// # Returns:
+    /// * `()` - No return value.
+    fn set_pause_flags(e: Env, flags: PauseFlags);
+
+    /// Retrieves the vault-wide pause flags.
+    ///
+    /// # Arguments:
+    /// * `e` - The environment.
+    ///
+    /// # Returns:
+    /// * `PauseFlags` - Which of deposits, withdrawals and rebalances are paused.
+    fn get_pause_flags(e: Env) -> PauseFlags;
+
     /// Retrieves the list of assets managed by the DeFindex Vault.
     ///
     /// # Arguments:
@@ -246,6 +479,73 @@ pub trait VaultTrait {
         vault_shares: i128,
     ) -> Result<Vec<i128>, ContractError>;
 
+    /// Simulates a deposit of `amounts_desired` and returns the amounts that would be deposited and the shares
+    /// that would be minted to the depositor, without transferring tokens or locking fees.
+    ///
+    /// Uses the same math as `deposit`. On the first deposit, the `MINIMUM_LIQUIDITY` shares kept by the vault
+    /// are subtracted from the returned shares, as is the entry fee. The management fee accrued since the last
+    /// accrual is included. As fees are not locked, the result may differ slightly from an actual deposit if
+    /// strategies have accrued gains since their last report.
+    ///
+    /// # Arguments
+    /// * `e` - The current environment reference.
+    /// * `amounts_desired` - A vector specifying the desired amounts for each asset.
+    ///
+    /// # Returns
+    /// * `Result<(Vec<i128>, i128), ContractError>` - The amounts that would be deposited for each asset and the
+    ///   shares that would be minted to the depositor.
+    fn preview_deposit(
+        e: Env,
+        amounts_desired: Vec<i128>,
+    ) -> Result<(Vec<i128>, i128), ContractError>;
+
+    /// Returns the number of vault shares that must be burned to receive at least `amounts_out` of each asset,
+    /// without locking fees.
+    ///
+    /// Shares are rounded up, so burning the returned shares always yields at least the requested amounts. The
+    /// exit fee and the management fee accrued since the last accrual are included, as in `withdraw_exact_amounts`.
+    ///
+    /// # Arguments
+    /// * `e` - The current environment reference.
+    /// * `amounts_out` - A vector specifying the amounts of each asset to receive.
+    ///
+    /// # Returns
+    /// * `Result<i128, ContractError>` - The number of vault shares to burn. Returns
+    ///   `ContractError::InsufficientManagedFunds` if the vault does not hold the requested amounts.
+    fn preview_withdraw(
+        e: Env,
+        amounts_out: Vec<i128>,
+    ) -> Result<i128, ContractError>;
+
+    /// Converts asset amounts to the equivalent number of vault shares, without locking fees.
+    ///
+    /// Unlike `preview_deposit`, the `MINIMUM_LIQUIDITY` kept by the vault on the first deposit is not subtracted.
+    ///
+    /// # Arguments
+    /// * `e` - The current environment reference.
+    /// * `amounts` - A vector specifying the amounts of each asset.
+    ///
+    /// # Returns
+    /// * `Result<i128, ContractError>` - The equivalent number of vault shares.
+    fn convert_to_shares(
+        e: Env,
+        amounts: Vec<i128>,
+    ) -> Result<i128, ContractError>;
+
+    /// Converts a number of vault shares to the equivalent amounts of each asset, without locking fees.
+    ///
+    /// # Arguments
+    /// * `e` - The current environment reference.
+    /// * `vault_shares` - The number of vault shares to convert.
+    ///
+    /// # Returns
+    /// * `Result<Vec<i128>, ContractError>` - A vector of asset amounts where each index matches the asset index
+    ///   in the vault's asset list.
+    fn convert_to_assets(
+        e: Env,
+        vault_shares: i128,
+    ) -> Result<Vec<i128>, ContractError>;
+
     /// Retrieves the current fee rates for the vault and the DeFindex protocol.
     ///
     /// This function returns the fee rates for both the vault and the DeFindex protocol.
@@ -257,9 +557,32 @@ pub trait VaultTrait {
     /// * `(u32, u32)` - A tuple containing:
     ///     - The vault fee rate as a percentage in basis points.
     ///     - The DeFindex protocol fee rate as a percentage in basis points.
+    fn get_fees(e: Env) -> (u32, u32);
+
+    /// Returns the annual management fee of the vault.
+    ///
+    /// The management fee accrues on the total managed funds over time and is paid by minting vault shares to the
+    /// fee receivers. It can only be changed through a `ProposalAction::SetManagementFee` proposal.
+    ///
+    /// # Arguments
+    /// * `e` - The environment.
     ///
+    /// # Returns
+    /// * `u32` - The management fee in basis points per year.
+    fn get_management_fee(e: Env) -> u32;
 
-    fn get_fees(e: Env) -> (u32, u32);
+    /// Returns the entry and exit fees of the vault.
+    ///
+    /// The entry fee is charged on the shares minted by a deposit and the exit fee on the shares given up by a
+    /// withdrawal. Both are paid in vault shares to the fee receivers, split as performance fees are. They can
+    /// only be changed through `ProposalAction::SetEntryFee` and `ProposalAction::SetExitFee` proposals.
+    ///
+    /// # Arguments
+    /// * `e` - The environment.
+    ///
+    /// # Returns
+    /// * `(u32, u32)` - The entry fee and the exit fee, in basis points.
+    fn get_entry_exit_fees(e: Env) -> (u32, u32);
 
     /// Generates reports for all strategies in the vault, tracking their performance and fee accrual.
     ///
@@ -300,60 +623,102 @@ pub trait VaultTrait {
 }
 
 pub trait AdminInterfaceTrait {
-    /// Sets the fee receiver for the vault.
-    ///
-    /// This function allows the manager or emergency manager to set a new fee receiver address for the vault.
+    /// Retrieves the current fee receiver address for the vault.
     ///
     /// # Arguments:
     /// * `e` - The environment.
-    /// * `caller` - The address initiating the change (must be the manager or emergency manager).
-    /// * `vault_fee_receiver` - The new fee receiver address.
     ///
     /// # Returns:
-    /// * `()` - No return value.
-    fn set_fee_receiver(e: Env, caller: Address, new_fee_receiver: Address);
+    /// * `Result<Address, ContractError>` - The fee receiver address if successful, otherwise returns a ContractError.
+    fn get_fee_receiver(e: Env) -> Result<Address, ContractError>;
 
-    /// Retrieves the current fee receiver address for the vault.
+    /// Retrieves the weighted fee receivers that split the vault's part of the fees.
     ///
     /// # Arguments:
     /// * `e` - The environment.
     ///
     /// # Returns:
-    /// * `Result<Address, ContractError>` - The fee receiver address if successful, otherwise returns a ContractError.
-    fn get_fee_receiver(e: Env) -> Result<Address, ContractError>;
+    /// * `Vec<FeeReceiver>` - The fee receivers and their weights in basis points. If empty, the vault fee receiver
+    ///   gets the whole vault's part of the fees.
+    fn get_fee_receivers(e: Env) -> Vec<FeeReceiver>;
 
-    /// Sets the manager for the vault.
+    /// Queues a new manager for the vault.
     ///
-    /// This function allows the current manager to set a new manager for the vault.
+    /// This function allows the current manager to propose a new manager. The new manager can only accept the
+    /// role once the manager timelock has elapsed, giving depositors time to exit. Queueing again replaces the
+    /// pending manager and restarts the timelock.
     ///
     /// # Arguments:
     /// * `e` - The environment.
     /// * `new_manager` - The new manager address.
     ///
     /// # Returns
-    /// * `Result<(), ContractError>` - Success (()) or ContractError if the manager change fails
-    fn set_manager(e: Env, new_manager: Address) -> Result<(), ContractError>;
+    /// * `Result<(), ContractError>` - Success (()) or ContractError if the manager cannot be queued
+    fn queue_manager(e: Env, new_manager: Address) -> Result<(), ContractError>;
 
-    /// Retrieves the current manager address for the vault.
+    /// Accepts the manager role for the queued manager.
+    ///
+    /// Must be authorized by the queued manager, once the manager timelock has elapsed.
+    ///
+    /// # Arguments:
+    /// * `e` - The environment.
+    ///
+    /// # Returns
+    /// * `Result<(), ContractError>` - Success (()) or `ContractError::QueueEmpty` if no manager is queued,
+    ///   `ContractError::SetManagerBeforeTime` if the timelock has not elapsed yet
+    fn accept_manager(e: Env) -> Result<(), ContractError>;
+
+    /// Cancels the pending manager handover.
+    ///
+    /// # Arguments:
+    /// * `e` - The environment.
+    ///
+    /// # Returns
+    /// * `Result<(), ContractError>` - Success (()) or `ContractError::QueueEmpty` if no manager is queued
+    fn cancel_manager_queue(e: Env) -> Result<(), ContractError>;
+
+    /// Retrieves the pending manager handover.
     ///
     /// # Arguments:
     /// * `e` - The environment.
     ///
     /// # Returns:
-    /// * `Result<Address, ContractError>` - The manager address if successful, otherwise returns a ContractError.
-    fn get_manager(e: Env) -> Result<Address, ContractError>;
+    /// * `Result<QueuedManager, ContractError>` - The queued manager and the time from which it can accept the role,
+    ///   or `ContractError::QueueEmpty` if no manager is queued.
+    fn get_queued_manager(e: Env) -> Result<QueuedManager, ContractError>;
 
-    /// Sets the emergency manager for the vault.
+    /// Sets the delay, in seconds, between queueing a new manager and the handover.
     ///
-    /// This function allows the current manager or emergency manager to set a new emergency manager for the vault.
+    /// This function allows the emergency manager to change the manager timelock. The new delay only takes
+    /// effect once the current delay has elapsed, and then applies to managers queued and proposals created
+    /// afterwards.
     ///
     /// # Arguments:
     /// * `e` - The environment.
-    /// * `emergency_manager` - The new emergency manager address.
+    /// * `delay` - The new delay in seconds, at least one day.
     ///
     /// # Returns:
-    /// * `()` - No return value.
-    fn set_emergency_manager(e: Env, new_emergency_manager: Address);
+    /// * `Result<(), ContractError>` - Ok if successful, or `ContractError::AmountNotAllowed` if the delay is
+    ///   shorter than one day.
+    fn set_manager_timelock(e: Env, delay: u64) -> Result<(), ContractError>;
+
+    /// Retrieves the delay, in seconds, between queueing a new manager and the handover.
+    ///
+    /// # Arguments:
+    /// * `e` - The environment.
+    ///
+    /// # Returns:
+    /// * `u64` - The manager timelock in seconds.
+    fn get_manager_timelock(e: Env) -> u64;
+
+    /// Retrieves the current manager address for the vault.
+    ///
+    /// # Arguments:
+    /// * `e` - The environment.
+    ///
+    /// # Returns:
+    /// * `Result<Address, ContractError>` - The manager address if successful, otherwise returns a ContractError.
+    fn get_manager(e: Env) -> Result<Address, ContractError>;
 
     /// Retrieves the current emergency manager address for the vault.
     ///
@@ -385,19 +750,83 @@ pub trait AdminInterfaceTrait {
     /// * `Result<Address, ContractError>` - The rebalance manager address if successful, otherwise returns a ContractError.
     fn get_rebalance_manager(e: Env) -> Result<Address, ContractError>;
 
-    /// Upgrades the contract with new WebAssembly (WASM) code.
+    /// Sets the allowlist manager for the vault.
     ///
-    /// This function updates the contract with new WASM code provided by the `new_wasm_hash`.
+    /// This function allows the current manager to set the address that maintains the depositor allowlist.
     ///
-    /// # Arguments
+    /// # Arguments:
+    /// * `e` - The environment.
+    /// * `new_allowlist_manager` - The new allowlist manager address.
     ///
-    /// * `e` - The runtime environment.
-    /// * `new_wasm_hash` - The hash of the new WASM code to upgrade the contract to.
+    /// # Returns:
+    /// * `()` - No return value.
+    fn set_allowlist_manager(e: Env, new_allowlist_manager: Address);
+
+    /// Retrieves the current allowlist manager address for the vault.
     ///
-    /// # Returns
-    /// * `Result<(), ContractError>` - Returns Ok(()) on success, ContractError if upgrade fails
+    /// # Arguments:
+    /// * `e` - The environment.
     ///
-    fn upgrade(e: Env, new_wasm_hash: BytesN<32>) -> Result<(), ContractError>;
+    /// # Returns:
+    /// * `Result<Address, ContractError>` - The allowlist manager address if set, otherwise `ContractError::RoleNotFound`.
+    fn get_allowlist_manager(e: Env) -> Result<Address, ContractError>;
+
+    /// Proposes a change of a sensitive vault parameter.
+    ///
+    /// This function allows the manager to queue a `ProposalAction`. The proposal can only be executed once the
+    /// manager timelock has elapsed, giving depositors time to exit, and the emergency manager can veto it
+    /// in the meantime.
+    ///
+    /// # Arguments:
+    /// * `e` - The environment.
+    /// * `action` - The parameter change to apply.
+    ///
+    /// # Returns:
+    /// * `Result<u64, ContractError>` - The id of the proposal, `ContractError::MaximumFeeExceeded` if the proposed
+    ///   vault fee is above 9000 basis points, the management fee above 1000 basis points or the entry or exit fee
+    ///   above 500 basis points, `ContractError::AssetNotFound`, `ContractError::DuplicatedStrategy` or
+    ///   `ContractError::StrategyDoesNotSupportAsset` if the proposed strategy cannot be added, or
+    ///   `ContractError::NotUpgradable` if the vault cannot be upgraded. Panics with `VaultError::FeeModeNotSupported`
+    ///   if `FeeDistributionMode::Shares` is proposed for a vault with more than one asset.
+    fn propose(e: Env, action: ProposalAction) -> Result<u64, ContractError>;
+
+    /// Executes a proposal once its timelock has elapsed.
+    ///
+    /// A vault fee change first locks the pending fees at the current rate, so the new rate only applies to
+    /// gains made after the change. Likewise, a management fee change first accrues the management fee at the
+    /// current rate.
+    ///
+    /// # Arguments:
+    /// * `e` - The environment.
+    /// * `proposal_id` - The id of the proposal.
+    ///
+    /// # Returns:
+    /// * `Result<(), ContractError>` - Ok if successful, `VaultError::ProposalNotFound` if the proposal does not exist
+    ///   or `VaultError::ProposalNotReady` if its timelock has not elapsed yet.
+    fn execute_proposal(e: Env, proposal_id: u64) -> Result<(), ContractError>;
+
+    /// Vetoes a pending proposal.
+    ///
+    /// This function allows the emergency manager to discard a proposal before it is executed.
+    ///
+    /// # Arguments:
+    /// * `e` - The environment.
+    /// * `proposal_id` - The id of the proposal.
+    ///
+    /// # Returns:
+    /// * `Result<(), ContractError>` - Ok if successful, otherwise `VaultError::ProposalNotFound`.
+    fn veto_proposal(e: Env, proposal_id: u64) -> Result<(), ContractError>;
+
+    /// Retrieves a pending proposal.
+    ///
+    /// # Arguments:
+    /// * `e` - The environment.
+    /// * `proposal_id` - The id of the proposal.
+    ///
+    /// # Returns:
+    /// * `Result<Proposal, ContractError>` - The proposal, or `VaultError::ProposalNotFound` if it does not exist,
+    ///   was executed or was vetoed.
+    fn get_proposal(e: Env, proposal_id: u64) -> Result<Proposal, ContractError>;
 }
 
 pub trait VaultManagementTrait {
@@ -406,22 +835,31 @@ pub trait VaultManagementTrait {
     /// # Arguments:
     /// * `e` - The environment.
     /// * `instructions` - A vector of `Instruction` structs representing actions (withdraw, invest, swap, zapper) to be taken.
+    /// * `max_loss_bps` - Optional maximum loss, in basis points, of the vault's managed funds over the whole rebalance.
+    ///   Funds are valued in the first asset of the vault, pricing the other assets with the reserves of their Soroswap
+    ///   pair with it before the rebalance. If `None`, no check is made.
     ///
     /// # Returns:
-    /// * `Result<(), ContractError>` - Ok if successful, otherwise returns a ContractError.
-    fn rebalance(e: Env, caller: Address, instructions: Vec<Instruction>) -> Result<(), ContractError>;
+    /// * `Result<(), ContractError>` - Ok if successful, otherwise returns a ContractError, or
+    ///   `ContractError::MaxLossExceeded` if the rebalance lost more than `max_loss_bps` of the vault's value.
+    fn rebalance(
+        e: Env,
+        caller: Address,
+        instructions: Vec<Instruction>,
+        max_loss_bps: Option<u32>,
+    ) -> Result<(), ContractError>;
 
     /// Locks fees for all assets and their strategies.
     ///
-    /// Iterates through each asset and its strategies, locking fees based on `new_fee_bps` or the default vault fee.
+    /// Iterates through each asset and its strategies, locking fees at the current vault fee.
+    /// The vault fee itself can only be changed through a `ProposalAction::SetVaultFee` proposal.
     ///
     /// # Arguments
     /// * `e` - The environment reference.
-    /// * `new_fee_bps` - Optional fee basis points to override the default.
     ///
     /// # Returns
-    /// * `Result<Vec<(Address, i128)>, ContractError>` - A vector of tuples with strategy addresses and locked fee amounts in their underlying_asset.
-    fn lock_fees(e: Env, new_fee_bps: Option<u32>) -> Result<Vec<Report>, ContractError>;
+    /// * `Result<Vec<Report>, ContractError>` - The report of every strategy after locking the fees.
+    fn lock_fees(e: Env) -> Result<Vec<Report>, ContractError>;
 
     /// Releases locked fees for a specific strategy.
     ///
@@ -439,6 +877,8 @@ pub trait VaultManagementTrait {
     /// This function iterates through each asset and its strategies, calculating the fees to be distributed
     /// to the vault fee receiver and the DeFindex protocol fee receiver based on their respective fee rates.
     /// It ensures proper authorization and validation checks before proceeding with the distribution.
+    /// Depending on the fee distribution mode, the fees are either unwound and paid in the underlying asset or
+    /// paid by minting vault shares to the receivers, leaving the funds invested.
     ///
     /// # Arguments
     /// * `e` - The environment reference.
@@ -447,4 +887,268 @@ pub trait VaultManagementTrait {
     /// # Returns
     /// * `Result<Vec<(Address, i128)>, ContractError>` - A vector of tuples with asset addresses and the total distributed fee amounts.
     fn distribute_fees(e: Env, caller: Address) -> Result<Vec<(Address, i128)>, ContractError>;
+
+    /// Sets the target allocation policy of an asset.
+    ///
+    /// The target defines the weight of each strategy, in basis points of the asset's total managed funds, plus
+    /// an idle buffer kept in the vault. Strategies not listed in the target have a weight of zero. Once set,
+    /// `deposit` with `invest = true` allocates the deposited amounts according to these weights.
+    ///
+    /// # Arguments
+    /// * `e` - The environment reference.
+    /// * `target_allocation` - The target allocation of the asset.
+    ///
+    /// # Returns
+    /// * `Result<(), ContractError>` - Ok if successful, otherwise returns a ContractError.
+    ///
+    /// # Errors
+    /// * `ContractError::AssetNotFound` - If the asset is not managed by the vault.
+    /// * `ContractError::StrategyNotFound` - If a target references a strategy not supporting the asset.
+    /// * `ContractError::DuplicatedStrategy` - If a strategy appears more than once.
+    /// * `ContractError::InvalidRatio` - If `idle_bps` plus every weight does not add up to 10_000.
+    fn set_target_allocation(e: Env, target_allocation: AssetTargetAllocation) -> Result<(), ContractError>;
+
+    /// Returns the target allocation policy of an asset, if the Manager has set one.
+    ///
+    /// # Arguments
+    /// * `e` - The environment reference.
+    /// * `asset` - The address of the asset.
+    ///
+    /// # Returns
+    /// * `Option<AssetTargetAllocation>` - The target allocation of the asset, or `None` if not set.
+    fn get_target_allocation(e: Env, asset: Address) -> Option<AssetTargetAllocation>;
+
+    /// Rebalances every asset with a target allocation back within the rebalance tolerance band.
+    ///
+    /// This function is permissionless. It computes the `Unwind` and `Invest` instructions needed to move each
+    /// strategy deviating from its target weight by more than the tolerance band back to its target, and executes
+    /// at most `max_instructions` of them. Unwinds of an asset are executed before its investments, and the idle
+    /// buffer of the asset is never invested. Calling it again continues where the previous call stopped.
+    ///
+    /// # Arguments
+    /// * `e` - The environment reference.
+    /// * `max_instructions` - The maximum number of instructions to execute in this call.
+    ///
+    /// # Returns
+    /// * `Result<Vec<Instruction>, ContractError>` - The executed instructions; empty if every asset is within the band.
+    fn rebalance_to_targets(e: Env, max_instructions: u32) -> Result<Vec<Instruction>, ContractError>;
+
+    /// Sets the rebalance tolerance band used by `rebalance_to_targets`.
+    ///
+    /// # Arguments
+    /// * `e` - The environment reference.
+    /// * `tolerance_bps` - The tolerance band, in basis points of an asset's total managed funds.
+    ///
+    /// # Returns
+    /// * `Result<(), ContractError>` - Ok if successful, `ContractError::InvalidRatio` if `tolerance_bps` exceeds 10_000.
+    fn set_rebalance_tolerance(e: Env, tolerance_bps: u32) -> Result<(), ContractError>;
+
+    /// Returns the rebalance tolerance band used by `rebalance_to_targets`, in basis points.
+    ///
+    /// # Arguments
+    /// * `e` - The environment reference.
+    ///
+    /// # Returns
+    /// * `u32` - The tolerance band in basis points.
+    fn get_rebalance_tolerance(e: Env) -> u32;
+
+    /// Sets the idle reserve of an asset.
+    ///
+    /// The idle reserve is the share of the asset's total managed funds kept idle in the vault so that small
+    /// withdrawals never touch the strategies. `deposit` with `invest = true` keeps back whatever is needed to refill
+    /// the reserve, and `withdraw` tops it up when it has to unwind strategies anyway.
+    ///
+    /// # Arguments
+    /// * `e` - The environment reference.
+    /// * `asset` - The address of the asset.
+    /// * `reserve_bps` - The idle reserve, in basis points of the asset's total managed funds.
+    ///
+    /// # Returns
+    /// * `Result<(), ContractError>` - Ok if successful, `ContractError::AssetNotFound` if the asset is not managed
+    ///   by the vault, or `ContractError::InvalidRatio` if `reserve_bps` exceeds 10_000.
+    fn set_idle_reserve(e: Env, asset: Address, reserve_bps: u32) -> Result<(), ContractError>;
+
+    /// Returns the idle reserve of an asset, in basis points of its total managed funds.
+    ///
+    /// # Arguments
+    /// * `e` - The environment reference.
+    /// * `asset` - The address of the asset.
+    ///
+    /// # Returns
+    /// * `u32` - The idle reserve in basis points; zero if not set.
+    fn get_idle_reserve(e: Env, asset: Address) -> u32;
+
+    /// Sets the order in which the strategies of an asset are drained by withdrawals.
+    ///
+    /// Withdrawals that need to unwind strategies drain them one after the other in this order, followed by
+    /// the strategies not listed in it, instead of unwinding every strategy proportionally. An empty order
+    /// restores proportional unwinding.
+    ///
+    /// # Arguments
+    /// * `e` - The environment reference.
+    /// * `asset` - The address of the asset.
+    /// * `strategies` - The strategy addresses, most liquid first.
+    ///
+    /// # Returns
+    /// * `Result<(), ContractError>` - Ok if successful, otherwise `ContractError::AssetNotFound`,
+    ///   `ContractError::StrategyNotFound` or `ContractError::DuplicatedStrategy`.
+    fn set_withdraw_order(e: Env, asset: Address, strategies: Vec<Address>) -> Result<(), ContractError>;
+
+    /// Returns the order in which the strategies of an asset are drained by withdrawals.
+    ///
+    /// # Arguments
+    /// * `e` - The environment reference.
+    /// * `asset` - The address of the asset.
+    ///
+    /// # Returns
+    /// * `Vec<Address>` - The strategy addresses; empty if strategies are unwound proportionally.
+    fn get_withdraw_order(e: Env, asset: Address) -> Vec<Address>;
+
+    /// Proposes adding a new strategy to one of the vault assets.
+    ///
+    /// Queues a `ProposalAction::AddStrategy` proposal: the strategy is only added by `execute_proposal` once
+    /// the manager timelock has elapsed, and the emergency manager can veto it in the meantime.
+    ///
+    /// # Arguments
+    /// * `e` - The environment reference.
+    /// * `asset_address` - The address of the asset the strategy manages.
+    /// * `strategy` - The strategy to add.
+    ///
+    /// # Returns
+    /// * `Result<u64, ContractError>` - The id of the proposal, or `ContractError::AssetNotFound`,
+    ///   `ContractError::DuplicatedStrategy` or `ContractError::StrategyDoesNotSupportAsset` if the strategy
+    ///   cannot be added.
+    fn add_strategy(e: Env, asset_address: Address, strategy: Strategy) -> Result<u64, ContractError>;
+
+    /// Removes a strategy from the vault.
+    ///
+    /// The vault must not hold any balance in the strategy; unwind or rescue it first. Its locked fees must have
+    /// been distributed too. The strategy report is cleared, its target weight is added to the idle weight of
+    /// the asset and it is removed from the asset withdraw order.
+    ///
+    /// # Arguments
+    /// * `e` - The environment reference.
+    /// * `strategy_address` - The address of the strategy to remove.
+    ///
+    /// # Returns
+    /// * `Result<(), ContractError>` - Ok if successful, otherwise `ContractError::StrategyNotFound`, or
+    ///   `ContractError::StrategyWithdrawError` if the strategy still holds funds or locked fees of the vault.
+    fn remove_strategy(e: Env, strategy_address: Address) -> Result<(), ContractError>;
+
+    /// Sets the maximum total amount of an asset the vault may manage after a deposit.
+    ///
+    /// Deposits that would bring the total managed amount of the asset (idle and invested) above the cap
+    /// fail. The cap does not affect funds already in the vault. `None` removes the cap.
+    ///
+    /// # Arguments
+    /// * `e` - The environment reference.
+    /// * `asset` - The address of the asset.
+    /// * `cap` - The maximum total managed amount of the asset, or `None` for no cap.
+    ///
+    /// # Returns
+    /// * `Result<(), ContractError>` - Ok if successful, otherwise `ContractError::AssetNotFound` or
+    ///   `ContractError::AmountNotAllowed` if the cap is negative.
+    fn set_deposit_cap(e: Env, asset: Address, cap: Option<i128>) -> Result<(), ContractError>;
+
+    /// Returns the deposit cap of an asset.
+    ///
+    /// # Arguments
+    /// * `e` - The environment reference.
+    /// * `asset` - The address of the asset.
+    ///
+    /// # Returns
+    /// * `Option<i128>` - The maximum total managed amount of the asset; `None` if not capped.
+    fn get_deposit_cap(e: Env, asset: Address) -> Option<i128>;
+
+    /// Sets the maximum amount of vault shares a single address may hold after a deposit.
+    ///
+    /// Only deposits are checked; shares received through transfers are not limited. `None` removes the cap.
+    ///
+    /// # Arguments
+    /// * `e` - The environment reference.
+    /// * `cap` - The maximum share balance per address, or `None` for no cap.
+    ///
+    /// # Returns
+    /// * `Result<(), ContractError>` - Ok if successful, otherwise `ContractError::AmountNotAllowed` if the
+    ///   cap is negative.
+    fn set_user_share_cap(e: Env, cap: Option<i128>) -> Result<(), ContractError>;
+
+    /// Returns the per-address share cap.
+    ///
+    /// # Arguments
+    /// * `e` - The environment reference.
+    ///
+    /// # Returns
+    /// * `Option<i128>` - The maximum share balance per address; `None` if not capped.
+    fn get_user_share_cap(e: Env) -> Option<i128>;
+
+    /// Turns the depositor allowlist on or off.
+    ///
+    /// While enabled, only allowlisted addresses can deposit or receive vault shares through `transfer` and
+    /// `transfer_from`. Shares already held by other addresses can still be withdrawn. Only the Allowlist
+    /// Manager can call this function.
+    ///
+    /// # Arguments
+    /// * `e` - The environment reference.
+    /// * `enabled` - Whether the allowlist is enforced.
+    fn set_allowlist_enabled(e: Env, enabled: bool);
+
+    /// Returns whether the depositor allowlist is enforced.
+    ///
+    /// # Arguments
+    /// * `e` - The environment reference.
+    ///
+    /// # Returns
+    /// * `bool` - `true` if only allowlisted addresses can hold new shares.
+    fn is_allowlist_enabled(e: Env) -> bool;
+
+    /// Adds an address to or removes it from the depositor allowlist.
+    ///
+    /// Only the Allowlist Manager can call this function. Removing an address does not burn its shares.
+    ///
+    /// # Arguments
+    /// * `e` - The environment reference.
+    /// * `address` - The address to update.
+    /// * `allowed` - Whether the address is allowlisted.
+    fn set_allowlisted(e: Env, address: Address, allowed: bool);
+
+    /// Returns whether an address is on the depositor allowlist.
+    ///
+    /// # Arguments
+    /// * `e` - The environment reference.
+    /// * `address` - The address to check.
+    ///
+    /// # Returns
+    /// * `bool` - `true` if the address is allowlisted.
+    fn is_allowlisted(e: Env, address: Address) -> bool;
+
+    /// Returns how locked performance fees are paid by `distribute_fees`, `rebalance` and `rescue`.
+    ///
+    /// In `FeeDistributionMode::Assets` mode, the locked fees are unwound from the strategy and the underlying asset
+    /// is transferred to the fee receivers. In `FeeDistributionMode::Shares` mode, vault shares worth the locked fees
+    /// are minted to the fee receivers instead and the funds stay invested; this mode is only available in single-asset
+    /// vaults. The mode can only be changed through a `ProposalAction::SetFeeDistributionMode` proposal.
+    ///
+    /// # Arguments
+    /// * `e` - The environment reference.
+    ///
+    /// # Returns
+    /// * `FeeDistributionMode` - The fee distribution mode; `FeeDistributionMode::Assets` by default.
+    fn get_fee_distribution_mode(e: Env) -> FeeDistributionMode;
+
+    /// Simulates `rebalance` with the given instructions without executing anything.
+    ///
+    /// Every instruction is checked as `rebalance` would check it, and swaps are priced from the current Soroswap
+    /// pair reserves with the Soroswap pair formula. Reserves are not updated between the simulated swaps,
+    /// so the expected outputs of several swaps through the same pair are estimates. Rebalances do not need to be
+    /// enabled, so that a rebalance can be planned while they are paused.
+    ///
+    /// # Arguments
+    /// * `e` - The environment reference.
+    /// * `instructions` - The rebalance instructions to simulate.
+    ///
+    /// # Returns
+    /// * `Result<RebalanceSimulation, ContractError>` - The projected idle and strategy balances of every asset and
+    ///   the expected swaps, or the error the first failing instruction would return.
+    fn simulate_rebalance(e: Env, instructions: Vec<Instruction>) -> Result<RebalanceSimulation, ContractError>;
 }
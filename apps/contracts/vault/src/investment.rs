@@ -1,14 +1,20 @@
 use soroban_sdk::{Env, Vec};
 
 use crate::{
+    constants::SCALAR_BPS,
     models::{AssetInvestmentAllocation, CurrentAssetInvestmentAllocation, StrategyAllocation},
+    storage::{get_idle_reserve, get_target_allocation},
+    strategies::invest_in_strategy,
+    utils::calculate_idle_reserve_deficit,
     ContractError,
 };
 
 /// Generates investment allocations for a set of assets and their associated strategies.
 ///
-/// This function calculates the distribution of funds across strategies for each asset based
-/// on the current state of strategy investments. The allocations are returned as a vector,
+/// This function calculates the distribution of funds across strategies for each asset. The part of each amount
+/// needed to refill the asset idle reserve is always kept in the vault. If the Manager
+/// has set a target allocation for the asset, funds are split according to the target weights and the
+/// idle buffer is left in the vault; otherwise, they follow the current state of strategy investments. The allocations are returned as a vector,
 /// where each entry corresponds to an asset's investment allocation or `None` if no allocation
 /// is required.
 ///
@@ -27,9 +33,10 @@ use crate::{
 /// # Function Flow
 /// 1. **Iterate Over Assets**:
 ///    - For each entry in `total_managed_funds`:
-///      - Match the corresponding amount from the `amounts` vector.
-///      - Skip assets with zero amounts or no prior investments.
-///      - Calculate the allocation of funds across strategies proportionally based on the current state.
+///      - Match the corresponding amount from the `amounts` vector, minus the idle reserve deficit of the asset.
+///      - Skip assets with zero amounts, or with no prior investments and no target allocation.
+///      - If a target allocation is set, invest `amount * weight_bps / SCALAR_BPS` in each strategy.
+///      - Otherwise, calculate the allocation of funds across strategies proportionally based on the current state.
 /// 2. **Proportional Distribution**:
 ///    - For each strategy within an asset, determine the proportional investment based on its existing allocation.
 ///    - Ensure that all amounts are correctly calculated without overflows or division errors.
@@ -42,7 +49,7 @@ use crate::{
 /// # Notes
 /// - This function does not execute the investments; it only prepares the allocations.
 /// - It assumes that the provided `total_managed_funds` contains valid and complete data.
-/// - The function ensures that the last strategy allocation balances any remaining amounts to avoid rounding issues.
+/// - Without a target allocation, the last strategy allocation balances any remaining amounts to avoid rounding issues.
 ///
 /// # Example
 /// ```rust
@@ -88,17 +95,44 @@ pub fn generate_investment_allocations(
 
     // Iterate through the total managed funds and match it with the corresponding amount
     for (i, current_asset_allocation) in total_managed_funds.iter().enumerate() {
-        let amount = amounts.get(i as u32).unwrap_or(0);
+        let deposited_amount = amounts.get(i as u32).unwrap_or(0);
         let asset_invested_funds = current_asset_allocation.invested_amount;
+        let target_allocation = get_target_allocation(e, &current_asset_allocation.asset);
 
-        // Skip assets with zero allocation or no previous investments
-        if amount > 0 && asset_invested_funds > 0 {
+        // Keep back whatever is needed to refill the idle reserve of the asset
+        let reserve_deficit = calculate_idle_reserve_deficit(
+            current_asset_allocation
+                .total_amount
+                .checked_add(deposited_amount)
+                .ok_or(ContractError::Overflow)?,
+            current_asset_allocation.idle_amount,
+            get_idle_reserve(e, &current_asset_allocation.asset),
+        )?;
+        let amount = deposited_amount
+            .checked_sub(reserve_deficit.min(deposited_amount.max(0)))
+            .ok_or(ContractError::Underflow)?;
+
+        // Skip assets with zero allocation, or with no previous investments and no target allocation
+        if amount > 0 && (target_allocation.is_some() || asset_invested_funds > 0) {
             let mut strategy_allocations = Vec::new(&e);
             let mut remaining_amount = amount;
 
             for (j, strategy_allocation) in current_asset_allocation.strategy_allocations.iter().enumerate() {
                 // Calculate the investment amount for the strategy
-                let invest_amount = if j == (current_asset_allocation.strategy_allocations.len() as usize).checked_sub(1).unwrap() {
+                let invest_amount = if let Some(target_allocation) = &target_allocation {
+                    // Follow the target weight of the strategy; rounding dust and the idle buffer stay idle
+                    let weight_bps = target_allocation
+                        .strategy_targets
+                        .iter()
+                        .find(|target| target.strategy_address == strategy_allocation.strategy_address)
+                        .map(|target| target.weight_bps)
+                        .unwrap_or(0);
+
+                    amount
+                        .checked_mul(weight_bps as i128)
+                        .and_then(|v| v.checked_div(SCALAR_BPS))
+                        .unwrap_or(0)
+                } else if j == (current_asset_allocation.strategy_allocations.len() as usize).checked_sub(1).unwrap() {
                     remaining_amount
                 } else {
                     let strategy_invested_funds = strategy_allocation.amount;
@@ -136,3 +170,26 @@ pub fn generate_investment_allocations(
 
     Ok(asset_investments)
 }
+
+/// Executes the investment allocations produced by `generate_investment_allocations`.
+///
+/// Every non-empty strategy allocation is invested into its strategy; assets or strategies with
+/// a `None` allocation are skipped and their funds remain idle in the vault.
+///
+/// # Arguments
+/// - `e` - Reference to the current environment.
+/// - `allocations` - The investment allocations to execute, one entry per vault asset.
+///
+/// # Returns
+/// - `Ok(())` if every investment succeeded, otherwise the `ContractError` returned by the strategy.
+pub fn execute_investment_allocations(
+    e: &Env,
+    allocations: &Vec<Option<AssetInvestmentAllocation>>,
+) -> Result<(), ContractError> {
+    for allocation in allocations.iter().flatten() {
+        for strategy in allocation.strategy_allocations.iter().flatten() {
+            invest_in_strategy(e, &allocation.asset, &strategy.strategy_address, &strategy.amount)?;
+        }
+    }
+    Ok(())
+}
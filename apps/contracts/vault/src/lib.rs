@@ -1,6 +1,6 @@
 #![no_std]
 use report::Report;
-use soroban_sdk::{contract, contractimpl, panic_with_error, token::TokenClient, vec, Address, BytesN, Env, IntoVal, Map, String, Val, Vec
+use soroban_sdk::{contract, contractimpl, panic_with_error, Address, Env, Map, String, Vec
 };
 use soroban_token_sdk::metadata::TokenMetadata;
 
@@ -14,104 +14,73 @@ mod funds;
 mod interface;
 mod investment;
 mod models;
+mod rebalance;
 mod report;
 mod storage;
 mod strategies;
 mod test;
 mod token;
 mod utils;
+mod withdraw;
 
 use access::{AccessControl, AccessControlTrait, RolesDataKey};
-use router::{internal_swap_exact_tokens_for_tokens, internal_swap_tokens_for_exact_tokens};
-use deposit::process_deposit;
+use constants::{MAX_ENTRY_EXIT_FEE_BPS, MAX_MANAGEMENT_FEE_BPS, MIN_MANAGER_TIMELOCK, SCALAR_BPS};
+use rebalance::{
+    check_max_loss, execute_instruction, generate_target_rebalance_instructions, simulate_instructions,
+    snapshot_managed_value,
+};
+use deposit::{calculate_deposit_shares, check_deposit_limits, process_deposit, process_single_asset_deposit};
 use funds::{fetch_strategy_invested_funds, fetch_total_managed_funds};
 use interface::{AdminInterfaceTrait, VaultManagementTrait, VaultTrait};
-use investment::generate_investment_allocations;
-use models::{AssetInvestmentAllocation, CurrentAssetInvestmentAllocation, Instruction, StrategyAllocation};
+use investment::{execute_investment_allocations, generate_investment_allocations};
+use models::{AssetInvestmentAllocation, AssetTargetAllocation, CurrentAssetInvestmentAllocation, FeeDistributionMode, FeeReceiver, Instruction, ManagerTimelock, PauseFlags, Proposal, ProposalAction, QueuedManager, RebalanceSimulation, WithdrawRequest};
 use storage::{
-    extend_instance_ttl, get_assets, get_defindex_protocol_fee_rate,
-    get_report, get_vault_fee, set_asset,
-    set_defindex_protocol_fee_rate, set_defindex_protocol_fee_receiver, set_report,
-    set_soroswap_router, set_total_assets, set_vault_fee, set_is_upgradable, update_report_prev_balance
+    extend_instance_ttl, get_assets, get_defindex_protocol_fee_rate, get_idle_reserve,
+    get_manager_timelock, get_next_withdraw_request_id, get_proposal, get_queued_manager, get_rebalance_tolerance, get_report, get_target_allocation,
+    get_vault_fee, get_withdraw_order, get_withdraw_queue_head, get_withdraw_request, set_asset,
+    set_defindex_protocol_fee_rate, set_defindex_protocol_fee_receiver, set_idle_reserve,
+    set_next_withdraw_request_id, set_rebalance_tolerance, set_report, set_soroswap_router,
+    set_target_allocation, set_total_assets, set_vault_fee, set_is_upgradable, set_withdraw_order,
+    set_withdraw_request, set_manager_timelock, set_queued_manager, remove_queued_manager,
+    remove_proposal, get_pause_flags, set_pause_flags,
+    get_deposit_cap, set_deposit_cap, get_user_share_cap, set_user_share_cap,
+    is_allowlist_enabled, is_allowlisted, set_allowlist_enabled, set_allowlisted,
+    get_fee_distribution_mode, set_fee_distribution_mode,
+    get_fee_receivers, set_fee_receivers,
+    get_management_fee, set_management_fee, get_entry_fee, set_entry_fee, get_exit_fee, set_exit_fee,
 };
 use strategies::{
-    get_strategy_asset, get_strategy_client, get_strategy_struct, invest_in_strategy,
-    pause_strategy, unpause_strategy, unwind_from_strategy,
+    add_strategy, get_strategy_asset, get_strategy_client, get_strategy_struct,
+    pause_strategy, remove_strategy, require_vault_asset, unpause_strategy, unwind_from_strategy, validate_new_strategy,
+};
+use token::{internal_burn, internal_transfer, write_metadata};
+use withdraw::{
+    calculate_withdraw_amounts, cancel_withdraw_request, park_withdraw_request, process_withdraw,
+    process_withdraw_request, process_withdraw_to_asset, require_withdraw_queue_empty,
 };
-use token::{internal_burn, write_metadata};
 use utils::{
-    calculate_asset_amounts_per_vault_shares, validate_amount, validate_assets
+    calculate_asset_amounts_per_vault_shares, calculate_vault_shares_for_asset_amounts, validate_amount,
+    queue_proposal, require_allowlisted, require_deposits_enabled, require_rebalances_enabled,
+    require_withdrawals_enabled, validate_assets, validate_target_allocation, validate_withdraw_order
 };
 
-use common::{models::AssetStrategySet, utils::StringExtensions};
+use common::{models::{AssetStrategySet, Strategy}, utils::StringExtensions};
 use defindex_strategy_core::DeFindexStrategyClient;
 
 use crate::token::VaultToken;
 
 static MINIMUM_LIQUIDITY: i128 = 1000;
 
-pub use error::ContractError;
+pub use error::{ContractError, VaultError};
 
 #[contract]
 pub struct DeFindexVault;
 
+// Doc comments of contract functions are embedded in the contract spec and count towards the WASM size, so the
+// entry points below are documented on their traits in `interface.rs` only.
 #[contractimpl]
 impl VaultTrait for DeFindexVault {
 
-    /// Initializes the DeFindex Vault contract with the required parameters.
-    ///
-    /// # Arguments
-    /// * `e` - The environment reference.
-    /// * `assets` - List of asset allocations for the vault, including strategies for each asset.
-    /// * `roles` - Map of role IDs to addresses containing:
-    ///   - Emergency Manager: For emergency control
-    ///   - Vault Fee Receiver: For receiving vault fees
-    ///   - Manager: For primary vault control
-    ///   - Rebalance Manager: For rebalancing operations
-    /// * `vault_fee` - Vault-specific fee in basis points (0_2000 for 0.20%)
-    /// * `defindex_protocol_receiver` - Address receiving protocol fees
-    /// * `defindex_protocol_rate` - Protocol fee rate in basis points (0-9000 for 0-90%)
-    /// * `soroswap_router` - Soroswap router address
-    /// * `name_symbol` - Map containing:
-    ///   - "name": Vault token name
-    ///   - "symbol": Vault token symbol
-    /// * `upgradable` - Boolean flag for contract upgradeability
-    ///
-    /// # Function Flow
-    /// 1. **Role Assignment**:
-    ///    - Sets Emergency Manager
-    ///    - Sets Vault Fee Receiver
-    ///    - Sets Manager
-    ///    - Sets Rebalance Manager
-    ///
-    /// 2. **Fee Configuration**:
-    ///    - Sets vault fee rate
-    ///    - Sets protocol fee receiver
-    ///    - Validates and sets protocol fee rate
-    ///
-    /// 3. **Contract Setup**:
-    ///    - Sets factory address
-    ///    - Sets upgradeability status
-    ///    - Sets Soroswap router
-    ///
-    /// 4. **Asset Validation & Setup**:
-    ///    - Validates asset list is not empty
-    ///    - Stores total asset count
-    ///    - For each asset:
-    ///      - Validates strategy compatibility
-    ///      - Stores asset configuration
-    ///
-    /// 5. **Token Initialization**:
-    ///    - Sets token decimals (7)
-    ///    - Sets token name and symbol
-    ///
-    /// # Errors
-    /// * `ContractError::RolesIncomplete` - If required roles are missing
-    /// * `ContractError::MetadataIncomplete` - If name or symbol is missing
-    /// * `ContractError::MaximumFeeExceeded` - If protocol fee > 9000 basis points
-    /// * `ContractError::NoAssetAllocation` - If assets vector is empty
-    /// * `ContractError::StrategyDoesNotSupportAsset` - If strategy validation fails
-    ///
     fn __constructor(
         e: Env,
         assets: Vec<AssetStrategySet>,
@@ -129,6 +98,9 @@ impl VaultTrait for DeFindexVault {
         access_control.set_role(&RolesDataKey::VaultFeeReceiver, &roles.get(RolesDataKey::VaultFeeReceiver as u32).unwrap_or_else(|| panic_with_error!(&e, ContractError::RolesIncomplete)));
         access_control.set_role(&RolesDataKey::Manager, &roles.get(RolesDataKey::Manager as u32).unwrap_or_else(|| panic_with_error!(&e, ContractError::RolesIncomplete)));
         access_control.set_role(&RolesDataKey::RebalanceManager, &roles.get(RolesDataKey::RebalanceManager as u32).unwrap_or_else(|| panic_with_error!(&e, ContractError::RolesIncomplete)));
+        if let Some(allowlist_manager) = roles.get(RolesDataKey::AllowlistManager as u32) {
+            access_control.set_role(&RolesDataKey::AllowlistManager, &allowlist_manager);
+        }
 
         let prefix = String::from_str(&e, "DeFindex-Vault-");
         let vault_name = name_symbol.get(String::from_str(&e, "name")).unwrap_or_else(|| panic_with_error!(&e, ContractError::MetadataIncomplete));
@@ -177,57 +149,6 @@ impl VaultTrait for DeFindexVault {
         );
     }
 
-    /// Handles user deposits into the DeFindex Vault and optionally allocates investments automatically.
-    ///
-    /// This function processes a deposit by transferring each specified asset amount from the user's address to
-    /// the vault and mints vault shares that represent the user's proportional share in the vault. Additionally, 
-    /// if the `invest` parameter is set to `true`, the function will immediately generate and execute investment 
-    /// allocations based on the vault's strategy configuration.
-    ///
-    /// # Parameters
-    /// * `e` - The current environment reference (`Env`), for access to the contract state and utilities.
-    /// * `amounts_desired` - A vector specifying the user's intended deposit amounts for each asset.
-    /// * `amounts_min` - A vector of minimum deposit amounts required for the transaction to proceed.
-    /// * `from` - The address of the user making the deposit.
-    /// * `invest` - A boolean flag indicating whether to immediately invest the deposited funds into the vault's strategies:
-    ///     - `true`: Generate and execute investments after the deposit.
-    ///     - `false`: Leave the deposited funds as idle assets in the vault.
-    ///
-    /// # Returns
-    /// * `Result<(Vec<i128>, i128, Option<Vec<Option<AssetInvestmentAllocation>>>), ContractError>` - Returns:
-    ///   - A vector of actual deposited amounts
-    ///   - The number of shares minted
-    ///   - Optional investment allocations if `invest` is true
-    ///
-    /// # Function Flow
-    /// 1. **Validation**:
-    ///    - Checks contract initialization
-    ///    - Verifies authorization of the depositor
-    ///    - Validates input parameters
-    /// 2. **Current State Assessment**:
-    ///    - Fetches total managed funds to calculate share ratios
-    /// 3. **Deposit Processing**:
-    ///    - Calculates shares to mint based on deposit amounts and current vault state
-    ///    - Transfers assets from user to vault
-    ///    - Mints vault shares to represent ownership
-    /// 4. **Investment Processing** (if `invest` is true):
-    ///    - Generates investment allocations based on current strategy ratios
-    ///    - Executes investments by deploying idle funds to strategies
-    /// 5. **Event Emission**:
-    ///    - Emits deposit event with amounts and minted shares
-    ///
-    /// # Notes
-    /// - The function maintains proportional share minting across multiple assets
-    /// - Investment allocations follow existing strategy ratios when `invest` is true
-    /// - Deposited funds remain idle if `invest` is false
-    ///
-    /// # Errors
-    /// - Returns a `ContractError` if:
-    ///   - Contract is not initialized
-    ///   - Input validation fails
-    ///   - Asset transfers fail
-    ///   - Share calculations encounter arithmetic errors
-    ///   - Investment execution fails (when `invest` is true)
     fn deposit(
         e: Env,
         amounts_desired: Vec<i128>,
@@ -237,6 +158,8 @@ impl VaultTrait for DeFindexVault {
     ) -> Result<(Vec<i128>, i128, Option<Vec<Option<AssetInvestmentAllocation>>>), ContractError> {
         extend_instance_ttl(&e);
         from.require_auth();
+        require_deposits_enabled(&e);
+        require_allowlisted(&e, &from)?;
 
         // Fetches the total managed funds for all assets, including idle and invested funds (net of locked fees).
         // Setting the flag to `true` ensures that strategy reports are updated and new fees are locked during the process.
@@ -249,25 +172,51 @@ impl VaultTrait for DeFindexVault {
             &amounts_min,
             &from,
         )?;
+        check_deposit_limits(&e, &total_managed_funds, &amounts, &from)?;
         events::emit_deposit_event(&e, from, amounts.clone(), shares_to_mint.clone(), total_shares_supply, total_managed_funds.clone());
 
         let asset_investments = if invest {
             let allocations = generate_investment_allocations(&e, &total_managed_funds, &amounts)?;
-            //iterate between allocations to execute investments
-            for allocation in allocations.iter() {
-                //Validate if alloctation is not empty
-                if let Some(allocation) = allocation.clone() {
-                    let strategies = allocation.strategy_allocations;
-                    for strategy in strategies {
-                        if let Some(strategy) = strategy {
-                            let strategy_address = strategy.strategy_address;
-                            let amount = strategy.amount;
-                            invest_in_strategy(&e, &allocation.asset, &strategy_address, &amount)?;
-                        }
-
-                    }
-                }
-            }
+            execute_investment_allocations(&e, &allocations)?;
+            Some(allocations)
+        } else {
+            None
+        };
+        Ok((amounts, shares_to_mint, asset_investments))
+    }
+
+    fn deposit_single_asset(
+        e: Env,
+        asset: Address,
+        amount: i128,
+        min_shares: i128,
+        from: Address,
+        invest: bool,
+    ) -> Result<(Vec<i128>, i128, Option<Vec<Option<AssetInvestmentAllocation>>>), ContractError> {
+        extend_instance_ttl(&e);
+        from.require_auth();
+        require_deposits_enabled(&e);
+        require_allowlisted(&e, &from)?;
+
+        // Fetches the total managed funds before the deposited asset reaches the vault, locking new fees.
+        let total_managed_funds = fetch_total_managed_funds(&e, true)?;
+
+        let (amounts, shares_to_mint, total_shares_supply) = process_single_asset_deposit(
+            &e,
+            &total_managed_funds,
+            &asset,
+            amount,
+            &from,
+        )?;
+        if shares_to_mint < min_shares {
+            return Err(ContractError::InsufficientOutputAmount);
+        }
+        check_deposit_limits(&e, &total_managed_funds, &amounts, &from)?;
+        events::emit_deposit_event(&e, from, amounts.clone(), shares_to_mint.clone(), total_shares_supply, total_managed_funds.clone());
+
+        let asset_investments = if invest {
+            let allocations = generate_investment_allocations(&e, &total_managed_funds, &amounts)?;
+            execute_investment_allocations(&e, &allocations)?;
             Some(allocations)
         } else {
             None
@@ -275,30 +224,11 @@ impl VaultTrait for DeFindexVault {
         Ok((amounts, shares_to_mint, asset_investments))
     }
 
-    /// Handles user withdrawals from the DeFindex Vault by burning shares and returning assets.
-    ///
-    /// This function processes a withdrawal request by burning the specified amount of vault shares
-    /// and returning a proportional amount of the vault's assets to the user. It can unwind positions
-    /// from strategies if necessary to fulfill the withdrawal.
-    ///
-    /// ## Parameters:
-    /// - `e`: The contract environment (`Env`).
-    /// - `withdraw_shares`: The number of vault shares to withdraw.
-    /// - `min_amounts_out`: A vector of minimum amounts required for each asset to be withdrawn.
-    /// - `from`: The address initiating the withdrawal.
-    ///
-    /// ## Returns
-    /// * `Result<Vec<i128>, ContractError>` - On success, returns a vector of withdrawn amounts 
-    ///   where each index corresponds to the asset index in the vault's asset list.
-    ///   Returns ContractError if the withdrawal fails.
-    ///
-    /// ## Errors:
-    /// - `ContractError::AmountOverTotalSupply`: If the specified shares exceed the total supply.
-    /// - `ContractError::ArithmeticError`: If any arithmetic operation fails during calculations.
-    /// - `ContractError::WrongAmountsLength`: If there is a mismatch in asset allocation data.
     fn withdraw(e: Env, withdraw_shares: i128, min_amounts_out: Vec<i128>, from: Address) -> Result<Vec<i128>, ContractError> {
         extend_instance_ttl(&e);
         from.require_auth();
+        require_withdrawals_enabled(&e);
+        require_withdraw_queue_empty(&e);
         
         if withdraw_shares <= 0 {
             return Err(ContractError::AmountNotAllowed);
@@ -325,109 +255,190 @@ impl VaultTrait for DeFindexVault {
             return Err(ContractError::AmountOverTotalSupply);
         }
 
-        // Burn the shares after calculating the withdrawal amounts
+        // The exit fee is paid in shares, only the remaining shares are burned
         // This will panic with error if the user does not have enough balance
+        let exit_fee_shares = report::calculate_exit_fee_shares(&e, withdraw_shares)?;
+        report::pay_fee_shares(&e, &from, exit_fee_shares)?;
+        let withdraw_shares = withdraw_shares - exit_fee_shares;
         internal_burn(e.clone(), from.clone(), withdraw_shares);
         
-        let mut withdrawn_amounts: Vec<i128> = Vec::new(&e);
-        
-        // Loop through each asset to handle the withdrawal
-        for (i, asset) in total_managed_funds.iter().enumerate() {
-            // Use assets instead of asset_withdrawal_amounts
-            let asset_address = &asset.asset;
-
-            // Calculate the requested withdrawal amount for this asset
-            let requested_withdrawal_amount = asset
-                .total_amount
-                .checked_mul(withdraw_shares)
-                .ok_or(ContractError::ArithmeticError)?
-                .checked_div(total_shares_supply)
-                .ok_or(ContractError::ArithmeticError)?;
-
-            if requested_withdrawal_amount < min_amounts_out.get(i as u32).unwrap() {
+        let amounts = calculate_withdraw_amounts(&e, &total_managed_funds, withdraw_shares, total_shares_supply)?;
+        for (i, amount) in amounts.iter().enumerate() {
+            if amount < min_amounts_out.get(i as u32).unwrap() {
                 panic_with_error!(&e, ContractError::InsufficientOutputAmount);
             }
-            if requested_withdrawal_amount > 0 {
-                // Process the withdrawal if the requested amount is greater than zero
-                let idle_funds = asset.idle_amount;
-                if idle_funds >= requested_withdrawal_amount {
-                    TokenClient::new(&e, asset_address).transfer(
-                        &e.current_contract_address(),
-                        &from,
-                        &requested_withdrawal_amount,
-                    );
-                    withdrawn_amounts.push_back(requested_withdrawal_amount);
-                } else {
-                    if idle_funds != 0 {
-                        TokenClient::new(&e, asset_address).transfer(
-                            &e.current_contract_address(),
-                            &from,
-                            &idle_funds,
-                        );
-                    }
-                    let mut cumulative_amount_for_asset = idle_funds;
-                    let remaining_amount_to_unwind =
-                        requested_withdrawal_amount.checked_sub(idle_funds).unwrap();
-                    // Iterate through the strategies to unwind the remaining amount
-                    for (i, strategy_allocation) in
-                        asset.strategy_allocations.iter().enumerate()
-                    {
-                        // If the current strategy is the last one, unwind the remaining amount
-                        let strategy_amount_to_unwind: i128 =
-                            if i == asset.strategy_allocations.len().checked_sub(1).unwrap_or(0) as usize {
-                                requested_withdrawal_amount
-                                    .checked_sub(cumulative_amount_for_asset)
-                                    .unwrap()
-                            } else {
-                                // Calculate the proportional amount to unwind from this strategy
-                                remaining_amount_to_unwind
-                                    .checked_mul(strategy_allocation.amount)
-                                    .and_then(|result| result.checked_div(asset.invested_amount))
-                                    .unwrap_or(0)
-                            };
-    
-                        if strategy_amount_to_unwind > 0 {
-                            let remaining_balance = unwind_from_strategy(
-                                &e,
-                                &strategy_allocation.strategy_address,
-                                &strategy_amount_to_unwind,
-                                &from,
-                            )?;
-                            update_report_prev_balance(
-                                &e, 
-                                &strategy_allocation.strategy_address, 
-                                remaining_balance
-                            );
-                            cumulative_amount_for_asset = cumulative_amount_for_asset.checked_add(strategy_amount_to_unwind).ok_or(ContractError::Overflow)?;
-                        }
-                    }
-                    withdrawn_amounts.push_back(cumulative_amount_for_asset);
-                }
-            } else {
-                // Push zero to 'withdrawn_amounts' to indicate no withdrawal for this asset
-                withdrawn_amounts.push_back(0);
-            }
-            
         }
 
+        let withdrawn_amounts = process_withdraw(&e, &total_managed_funds, &amounts, &from)?;
+
         events::emit_withdraw_event(&e, from, withdraw_shares, withdrawn_amounts.clone(), total_shares_supply, total_managed_funds);
 
         Ok(withdrawn_amounts)
     }
 
-    /// Executes rescue (formerly emergency withdrawal) from a specific strategy.
-    ///
-    /// This function allows the emergency manager or manager to withdraw all assets from a particular strategy
-    /// and store them as idle funds within the vault. It also pauses the strategy to prevent further use until
-    /// unpaused.
-    ///
-    /// # Arguments:
-    /// * `e` - The environment.
-    /// * `strategy_address` - The address of the strategy to withdraw from.
-    /// * `caller` - The address initiating the emergency withdrawal (must be the manager or emergency manager).
-    ///
-    /// # Returns
-    /// * `Result<(), ContractError>` - Success (()) or ContractError if the rescue operation fails
+    fn withdraw_to_asset(
+        e: Env,
+        withdraw_shares: i128,
+        out_asset: Address,
+        min_out: i128,
+        from: Address,
+    ) -> Result<i128, ContractError> {
+        extend_instance_ttl(&e);
+        from.require_auth();
+        require_withdrawals_enabled(&e);
+        require_withdraw_queue_empty(&e);
+
+        if withdraw_shares <= 0 {
+            return Err(ContractError::AmountNotAllowed);
+        }
+        validate_amount(min_out)?;
+
+        // Fetches the total managed funds for all assets, locking new fees as `withdraw` does.
+        let total_managed_funds = fetch_total_managed_funds(&e, true)?;
+        if !total_managed_funds.iter().any(|asset| asset.asset == out_asset) {
+            return Err(ContractError::UnsupportedAsset);
+        }
+
+        let total_shares_supply = VaultToken::total_supply(e.clone());
+        if withdraw_shares > total_shares_supply {
+            return Err(ContractError::AmountOverTotalSupply);
+        }
+
+        // The exit fee is paid in shares, only the remaining shares are burned
+        // This will panic with error if the user does not have enough balance
+        let exit_fee_shares = report::calculate_exit_fee_shares(&e, withdraw_shares)?;
+        report::pay_fee_shares(&e, &from, exit_fee_shares)?;
+        let withdraw_shares = withdraw_shares - exit_fee_shares;
+        internal_burn(e.clone(), from.clone(), withdraw_shares);
+
+        let amounts = calculate_withdraw_amounts(&e, &total_managed_funds, withdraw_shares, total_shares_supply)?;
+
+        // Unwind everything into the vault first, so that non-target assets can be swapped
+        let withdrawn_amounts = process_withdraw(&e, &total_managed_funds, &amounts, &e.current_contract_address())?;
+        let amount_out = process_withdraw_to_asset(&e, &withdrawn_amounts, &out_asset, min_out, &from)?;
+
+        events::emit_withdraw_event(&e, from, withdraw_shares, withdrawn_amounts, total_shares_supply, total_managed_funds);
+
+        Ok(amount_out)
+    }
+
+    fn withdraw_exact_amounts(
+        e: Env,
+        amounts_out: Vec<i128>,
+        max_shares_in: i128,
+        from: Address,
+    ) -> Result<i128, ContractError> {
+        extend_instance_ttl(&e);
+        from.require_auth();
+        require_withdrawals_enabled(&e);
+        require_withdraw_queue_empty(&e);
+
+        // Fetches the total managed funds for all assets, locking new fees as `withdraw` does.
+        let total_managed_funds = fetch_total_managed_funds(&e, true)?;
+        let total_shares_supply = VaultToken::total_supply(e.clone());
+
+        let withdraw_shares = calculate_vault_shares_for_asset_amounts(&total_managed_funds, &amounts_out, total_shares_supply)?;
+        if withdraw_shares <= 0 {
+            return Err(ContractError::AmountNotAllowed);
+        }
+        if withdraw_shares > total_shares_supply {
+            return Err(ContractError::AmountOverTotalSupply);
+        }
+        let exit_fee_shares = report::calculate_exit_fee_shares_on_top(&e, withdraw_shares)?;
+        let shares_in = withdraw_shares.checked_add(exit_fee_shares).ok_or(ContractError::Overflow)?;
+        if shares_in > max_shares_in {
+            return Err(ContractError::ExcessiveInputAmount);
+        }
+
+        // This will panic with error if the user does not have enough balance
+        report::pay_fee_shares(&e, &from, exit_fee_shares)?;
+        internal_burn(e.clone(), from.clone(), withdraw_shares);
+
+        let withdrawn_amounts = process_withdraw(&e, &total_managed_funds, &amounts_out, &from)?;
+
+        events::emit_withdraw_event(&e, from, withdraw_shares, withdrawn_amounts, total_shares_supply, total_managed_funds);
+
+        Ok(shares_in)
+    }
+
+    fn request_withdraw(e: Env, withdraw_shares: i128, from: Address) -> Result<u64, ContractError> {
+        extend_instance_ttl(&e);
+        from.require_auth();
+        require_withdrawals_enabled(&e);
+
+        if withdraw_shares <= 0 {
+            return Err(ContractError::AmountNotAllowed);
+        }
+
+        // The exit fee is paid when requesting, only the remaining shares are escrowed.
+        // This will panic with error if the user does not have enough balance
+        let exit_fee_shares = report::calculate_exit_fee_shares(&e, withdraw_shares)?;
+        report::pay_fee_shares(&e, &from, exit_fee_shares)?;
+        let withdraw_shares = withdraw_shares - exit_fee_shares;
+        internal_transfer(e.clone(), from.clone(), e.current_contract_address(), withdraw_shares);
+
+        let request_id = get_next_withdraw_request_id(&e);
+        set_withdraw_request(&e, request_id, &WithdrawRequest {
+            owner: from.clone(),
+            shares: withdraw_shares,
+        });
+        set_next_withdraw_request_id(&e, &(request_id + 1));
+
+        events::emit_withdraw_requested_event(&e, request_id, from, withdraw_shares);
+        Ok(request_id)
+    }
+
+    fn claim_withdraw(e: Env, request_id: u64) -> Result<Vec<i128>, ContractError> {
+        extend_instance_ttl(&e);
+        require_withdrawals_enabled(&e);
+        let (_, withdrawn_amounts) = process_withdraw_request(&e, request_id)?;
+        Ok(withdrawn_amounts)
+    }
+
+    fn cancel_withdraw_request(e: Env, request_id: u64) -> Result<i128, ContractError> {
+        extend_instance_ttl(&e);
+        let request = cancel_withdraw_request(&e, request_id)?;
+        events::emit_withdraw_request_cancelled_event(&e, request_id, request.owner, request.shares);
+        Ok(request.shares)
+    }
+
+    fn park_withdraw_request(e: Env, request_id: u64) -> Result<(), ContractError> {
+        extend_instance_ttl(&e);
+        let access_control = AccessControl::new(&e);
+        access_control.require_role(&RolesDataKey::EmergencyManager);
+
+        let request = park_withdraw_request(&e, request_id)?;
+        events::emit_withdraw_request_parked_event(&e, request_id, request.owner, request.shares);
+        Ok(())
+    }
+
+    fn process_withdraw_queue(e: Env, max_requests: u32) -> Result<Vec<u64>, ContractError> {
+        extend_instance_ttl(&e);
+        require_withdrawals_enabled(&e);
+
+        let mut processed: Vec<u64> = Vec::new(&e);
+        let next_request_id = get_next_withdraw_request_id(&e);
+        let mut request_id = get_withdraw_queue_head(&e);
+        while request_id < next_request_id && processed.len() < max_requests {
+            process_withdraw_request(&e, request_id)?;
+            processed.push_back(request_id);
+            // Cancelled requests are skipped by the queue head
+            request_id = get_withdraw_queue_head(&e);
+        }
+
+        Ok(processed)
+    }
+
+    fn get_withdraw_request(e: Env, request_id: u64) -> Result<WithdrawRequest, ContractError> {
+        extend_instance_ttl(&e);
+        Ok(get_withdraw_request(&e, request_id))
+    }
+
+    fn get_withdraw_queue(e: Env) -> (u64, u64) {
+        extend_instance_ttl(&e);
+        (get_withdraw_queue_head(&e), get_next_withdraw_request_id(&e))
+    }
+
     fn rescue(
         e: Env,
         strategy_address: Address,
@@ -466,12 +477,10 @@ impl VaultTrait for DeFindexVault {
                 &e.current_contract_address(),
             )?;
             
-            // Create a new zeroed report directly instead of getting the existing one
-            let report = Report {
-                prev_balance: 0,
-                gains_or_losses: 0,
-                locked_fee: 0,
-            };
+            // The strategy is now empty, but its unrecovered losses are carried over to its next investment
+            let mut report = get_report(&e, &strategy_address);
+            report.prev_balance = 0;
+            report.locked_fee = 0;
             set_report(&e, &strategy_address, &report);
         }
 
@@ -482,18 +491,6 @@ impl VaultTrait for DeFindexVault {
         Ok(())
     }
 
-    /// Pauses a strategy to prevent it from being used in the vault.
-    ///
-    /// This function pauses a strategy by setting its `paused` field to `true`. Only the manager or emergency
-    /// manager can pause a strategy.
-    ///
-    /// # Arguments:
-    /// * `e` - The environment.
-    /// * `strategy_address` - The address of the strategy to pause.
-    /// * `caller` - The address initiating the pause (must be the manager or emergency manager).
-    ///
-    /// # Returns
-    /// * `Result<(), ContractError>` - Success (()) or ContractError if the pause operation fails
     fn pause_strategy(
         e: Env,
         strategy_address: Address,
@@ -512,18 +509,6 @@ impl VaultTrait for DeFindexVault {
         Ok(())
     }
 
-    /// Unpauses a previously paused strategy.
-    ///
-    /// This function unpauses a strategy by setting its `paused` field to `false`, allowing it to be used
-    /// again in the vault.
-    ///
-    /// # Arguments:
-    /// * `e` - The environment.
-    /// * `strategy_address` - The address of the strategy to unpause.
-    /// * `caller` - The address initiating the unpause (must be the manager or emergency manager).
-    ///
-    /// # Returns:
-    /// * `Result<(), ContractError>` - Ok if successful, otherwise returns a ContractError.
     fn unpause_strategy(
         e: Env,
         strategy_address: Address,
@@ -542,28 +527,25 @@ impl VaultTrait for DeFindexVault {
         Ok(())
     }
 
-    /// Retrieves the list of assets managed by the DeFindex Vault.
-    ///
-    /// # Arguments:
-    /// * `e` - The environment.
-    ///
-    /// # Returns:
-    /// * `Vec<AssetStrategySet>` - A vector of `AssetStrategySet` structs representing the assets managed by the vault.
+    fn set_pause_flags(e: Env, flags: PauseFlags) {
+        extend_instance_ttl(&e);
+        let access_control = AccessControl::new(&e);
+        access_control.require_role(&RolesDataKey::EmergencyManager);
+
+        set_pause_flags(&e, &flags);
+        events::emit_pause_flags_changed_event(&e, flags);
+    }
+
+    fn get_pause_flags(e: Env) -> PauseFlags {
+        extend_instance_ttl(&e);
+        get_pause_flags(&e)
+    }
+
     fn get_assets(e: Env) -> Result<Vec<AssetStrategySet>, ContractError> {
         extend_instance_ttl(&e);
         get_assets(&e)
     }
 
-    /// Returns the total managed funds of the vault, including both invested and idle funds.
-    ///
-    /// This function provides a vector of `CurrentAssetInvestmentAllocation` structs containing information
-    /// about each asset's current allocation, including both invested amounts in strategies and idle amounts.
-    ///
-    /// # Arguments:
-    /// * `e` - The environment.
-    ///
-    /// # Returns:
-    /// * `Result<Vec<CurrentAssetInvestmentAllocation>, ContractError>` - A vector of asset allocations or error
     fn fetch_total_managed_funds(e: &Env) -> Result<Vec<CurrentAssetInvestmentAllocation>, ContractError> {
         extend_instance_ttl(&e);
         let total_managed_funds = fetch_total_managed_funds(e, false)?;
@@ -572,17 +554,6 @@ impl VaultTrait for DeFindexVault {
     }
 
     // Calculates the corresponding amounts of each asset per a given number of vault shares.
-    /// This function extends the contract's time-to-live and calculates how much of each asset corresponds
-    /// per the provided number of vault shares (`vault_shares`). It provides proportional allocations for each asset
-    /// in the vault relative to the specified shares.
-    ///
-    /// # Arguments
-    /// * `e` - The current environment reference.
-    /// * `vault_shares` - The number of vault shares for which the corresponding asset amounts are calculated.
-    ///
-    /// # Returns
-    /// * `Result<Vec<i128>, ContractError>` - A vector of asset amounts corresponding to the vault shares, where each index
-    ///   matches the asset index in the vault's asset list. Returns ContractError if calculation fails.
     fn get_asset_amounts_per_shares(
         e: Env,
         vault_shares: i128,
@@ -599,18 +570,86 @@ impl VaultTrait for DeFindexVault {
         )?)
     }
 
-    /// Retrieves the current fee rates for the vault and the DeFindex protocol.
-    ///
-    /// This function returns the fee rates for both the vault and the DeFindex protocol.
-    ///
-    /// # Arguments
-    /// * `e` - The environment.
-    ///
-    /// # Returns
-    /// * `(u32, u32)` - A tuple containing:
-    ///     - The vault fee rate as a percentage in basis points.
-    ///     - The DeFindex protocol fee rate as a percentage in basis points.
-    ///
+    fn preview_deposit(
+        e: Env,
+        amounts_desired: Vec<i128>,
+    ) -> Result<(Vec<i128>, i128), ContractError> {
+        extend_instance_ttl(&e);
+        let total_managed_funds = fetch_total_managed_funds(&e, false)?;
+        let total_supply = VaultToken::total_supply(e.clone())
+            .checked_add(report::get_pending_management_fee_shares(&e)?)
+            .ok_or(ContractError::Overflow)?;
+
+        let mut amounts_min: Vec<i128> = Vec::new(&e);
+        for _ in amounts_desired.iter() {
+            amounts_min.push_back(0);
+        }
+        let (amounts, shares_to_mint) = calculate_deposit_shares(
+            &e,
+            &total_managed_funds,
+            &amounts_desired,
+            &amounts_min,
+            total_supply,
+        )?;
+
+        let depositor_shares = if total_supply == 0 {
+            if shares_to_mint <= MINIMUM_LIQUIDITY {
+                return Err(ContractError::InsufficientAmount);
+            }
+            shares_to_mint - MINIMUM_LIQUIDITY
+        } else {
+            shares_to_mint
+        };
+        Ok((amounts, depositor_shares - report::calculate_entry_fee_shares(&e, depositor_shares)?))
+    }
+
+    fn preview_withdraw(
+        e: Env,
+        amounts_out: Vec<i128>,
+    ) -> Result<i128, ContractError> {
+        extend_instance_ttl(&e);
+        let total_managed_funds = fetch_total_managed_funds(&e, false)?;
+        let total_supply = VaultToken::total_supply(e.clone())
+            .checked_add(report::get_pending_management_fee_shares(&e)?)
+            .ok_or(ContractError::Overflow)?;
+
+        let shares = calculate_vault_shares_for_asset_amounts(&total_managed_funds, &amounts_out, total_supply)?;
+        shares
+            .checked_add(report::calculate_exit_fee_shares_on_top(&e, shares)?)
+            .ok_or(ContractError::Overflow)
+    }
+
+    fn convert_to_shares(
+        e: Env,
+        amounts: Vec<i128>,
+    ) -> Result<i128, ContractError> {
+        extend_instance_ttl(&e);
+        let total_managed_funds = fetch_total_managed_funds(&e, false)?;
+        let total_supply = VaultToken::total_supply(e.clone());
+
+        let mut amounts_min: Vec<i128> = Vec::new(&e);
+        for _ in amounts.iter() {
+            amounts_min.push_back(0);
+        }
+        let (_, shares) = calculate_deposit_shares(
+            &e,
+            &total_managed_funds,
+            &amounts,
+            &amounts_min,
+            total_supply,
+        )?;
+        Ok(shares)
+    }
+
+    fn convert_to_assets(
+        e: Env,
+        vault_shares: i128,
+    ) -> Result<Vec<i128>, ContractError> {
+        extend_instance_ttl(&e);
+        let total_managed_funds = fetch_total_managed_funds(&e, false)?;
+        calculate_asset_amounts_per_vault_shares(&e, vault_shares, &total_managed_funds)
+    }
+
     fn get_fees(e: Env) -> (u32, u32) {
         extend_instance_ttl(&e);
         let defindex_protocol_fee = get_defindex_protocol_fee_rate(&e);
@@ -618,41 +657,16 @@ impl VaultTrait for DeFindexVault {
         (vault_fee, defindex_protocol_fee)
     }
 
-    /// Generates reports for all strategies in the vault, tracking their performance and fee accrual.
-    ///
-    /// This function iterates through all assets and their associated strategies to generate
-    /// performance reports. It updates each strategy's report with current balances and
-    /// calculates gains or losses since the last report.
-    ///
-    /// # Arguments
-    /// * `e` - The environment reference.
-    ///
-    /// # Function Flow
-    /// 1. **Instance Extension**:
-    ///    - Extends contract TTL
-    ///
-    /// 2. **Asset & Strategy Retrieval**:
-    ///    - Gets all assets and their strategies
-    ///    - Initializes reports vector
-    ///
-    /// 3. **Report Generation**:
-    ///    - For each asset:
-    ///      - For each strategy:
-    ///        - Gets current strategy balance
-    ///        - Updates report with new balance
-    ///        - Stores updated report
-    ///
-    /// # Returns
-    /// * `Result<Vec<Report>, ContractError>` - On success, returns a vector of reports 
-    ///   where each report contains performance metrics for a strategy. Returns 
-    ///   ContractError if report generation fails.
-    ///
-    /// # Note
-    /// Reports track:
-    /// - Current strategy balance
-    /// - Gains or losses since last report
-    /// - Locked fees
-    /// - Fee distribution status
+    fn get_management_fee(e: Env) -> u32 {
+        extend_instance_ttl(&e);
+        get_management_fee(&e)
+    }
+
+    fn get_entry_exit_fees(e: Env) -> (u32, u32) {
+        extend_instance_ttl(&e);
+        (get_entry_fee(&e), get_exit_fee(&e))
+    }
+
     fn report(e: Env) -> Result<Vec<Report>, ContractError> {
         extend_instance_ttl(&e);
 
@@ -681,111 +695,115 @@ impl VaultTrait for DeFindexVault {
 
 #[contractimpl]
 impl AdminInterfaceTrait for DeFindexVault {
-    /// Sets the fee receiver for the vault.
-    ///
-    /// This function allows the manager or the vault fee receiver to set a new fee receiver address for the vault.
-    ///
-    /// # Arguments:
-    /// * `e` - The environment.
-    /// * `caller` - The address initiating the change (must be the manager or the vault fee receiver).
-    /// * `vault_fee_receiver` - The new fee receiver address.
-    ///
-    /// # Returns:
-    /// * `()` - No return value.
-    fn set_fee_receiver(e: Env, caller: Address, new_fee_receiver: Address) {
+    fn get_fee_receiver(e: Env) -> Result<Address, ContractError> {
         extend_instance_ttl(&e);
         let access_control = AccessControl::new(&e);
-        access_control.set_fee_receiver(&caller, &new_fee_receiver);
+        access_control.get_fee_receiver()
+    }
 
-        events::emit_fee_receiver_changed_event(&e, new_fee_receiver, caller);
+    fn get_fee_receivers(e: Env) -> Vec<FeeReceiver> {
+        extend_instance_ttl(&e);
+        get_fee_receivers(&e)
     }
 
-    /// Retrieves the current fee receiver address for the vault.
-    ///
-    /// # Arguments:
-    /// * `e` - The environment.
-    ///
-    /// # Returns:
-    /// * `Result<Address, ContractError>` - The fee receiver address if successful, otherwise returns a ContractError.
-    fn get_fee_receiver(e: Env) -> Result<Address, ContractError> {
+    fn queue_manager(e: Env, new_manager: Address) -> Result<(), ContractError> {
         extend_instance_ttl(&e);
         let access_control = AccessControl::new(&e);
-        access_control.get_fee_receiver()
+        access_control.require_role(&RolesDataKey::Manager);
+
+        let available_at = e
+            .ledger()
+            .timestamp()
+            .checked_add(get_manager_timelock(&e))
+            .ok_or(ContractError::Overflow)?;
+        set_queued_manager(
+            &e,
+            &QueuedManager {
+                new_manager: new_manager.clone(),
+                available_at,
+            },
+        );
+
+        events::emit_manager_queued_event(&e, new_manager, available_at);
+        Ok(())
     }
 
-    /// Sets the manager for the vault.
-    ///
-    /// This function allows the current manager to set a new manager for the vault.
-    ///
-    /// # Arguments:
-    /// * `e` - The environment.
-    /// * `new_manager` - The new manager address.
-    ///
-    /// # Returns
-    /// * `Result<(), ContractError>` - Success (()) or ContractError if the manager change fails
-    fn set_manager(e: Env, new_manager: Address) -> Result<(), ContractError> {
+    fn accept_manager(e: Env) -> Result<(), ContractError> {
         extend_instance_ttl(&e);
+        let queued_manager = get_queued_manager(&e)?;
+        if e.ledger().timestamp() < queued_manager.available_at {
+            return Err(ContractError::SetManagerBeforeTime);
+        }
+
         let access_control = AccessControl::new(&e);
-        
-        access_control.set_manager(&new_manager);
-        events::emit_manager_changed_event(&e, new_manager);
+        access_control.set_manager(&queued_manager.new_manager);
+        remove_queued_manager(&e);
+
+        events::emit_manager_changed_event(&e, queued_manager.new_manager);
         Ok(())
     }
 
-    /// Retrieves the current manager address for the vault.
-    ///
-    /// # Arguments:
-    /// * `e` - The environment.
-    ///
-    /// # Returns:
-    /// * `Result<Address, ContractError>` - The manager address if successful, otherwise returns a ContractError.
-    fn get_manager(e: Env) -> Result<Address, ContractError> {
+    fn cancel_manager_queue(e: Env) -> Result<(), ContractError> {
         extend_instance_ttl(&e);
         let access_control = AccessControl::new(&e);
-        access_control.get_manager()
+        access_control.require_role(&RolesDataKey::Manager);
+
+        let queued_manager = get_queued_manager(&e)?;
+        remove_queued_manager(&e);
+
+        events::emit_manager_queue_cancelled_event(&e, queued_manager.new_manager);
+        Ok(())
     }
 
-    /// Sets the emergency manager for the vault.
-    ///
-    /// This function allows the current manager to set a new emergency manager for the vault.
-    ///
-    /// # Arguments:
-    /// * `e` - The environment.
-    /// * `emergency_manager` - The new emergency manager address.
-    ///
-    /// # Returns:
-    /// * `()` - No return value.
-    fn set_emergency_manager(e: Env, emergency_manager: Address) {
+    fn get_queued_manager(e: Env) -> Result<QueuedManager, ContractError> {
+        extend_instance_ttl(&e);
+        get_queued_manager(&e)
+    }
+
+    fn set_manager_timelock(e: Env, delay: u64) -> Result<(), ContractError> {
         extend_instance_ttl(&e);
         let access_control = AccessControl::new(&e);
-        access_control.set_emergency_manager(&emergency_manager);
+        access_control.require_role(&RolesDataKey::EmergencyManager);
+
+        if delay < MIN_MANAGER_TIMELOCK {
+            return Err(ContractError::AmountNotAllowed);
+        }
+
+        let previous_delay = get_manager_timelock(&e);
+        let effective_at = e
+            .ledger()
+            .timestamp()
+            .checked_add(previous_delay)
+            .ok_or(ContractError::Overflow)?;
+        set_manager_timelock(
+            &e,
+            &ManagerTimelock {
+                delay,
+                previous_delay,
+                effective_at,
+            },
+        );
+        events::emit_manager_timelock_changed_event(&e, delay, effective_at);
+        Ok(())
+    }
+
+    fn get_manager_timelock(e: Env) -> u64 {
+        extend_instance_ttl(&e);
+        get_manager_timelock(&e)
+    }
 
-        events::emit_emergency_manager_changed_event(&e, emergency_manager);
+    fn get_manager(e: Env) -> Result<Address, ContractError> {
+        extend_instance_ttl(&e);
+        let access_control = AccessControl::new(&e);
+        access_control.get_manager()
     }
 
-    /// Retrieves the current emergency manager address for the vault.
-    ///
-    /// # Arguments:
-    /// * `e` - The environment.
-    ///
-    /// # Returns:
-    /// * `Result<Address, ContractError>` - The emergency manager address if successful, otherwise returns a ContractError.
     fn get_emergency_manager(e: Env) -> Result<Address, ContractError> {
         extend_instance_ttl(&e);
         let access_control = AccessControl::new(&e);
         access_control.get_emergency_manager()
     }
 
-    /// Sets the rebalance manager for the vault.
-    ///
-    /// This function allows the current manager to set a new rebalance manager for the vault.
-    ///
-    /// # Arguments:
-    /// * `e` - The environment.
-    /// * `new_rebalance_manager` - The new rebalance manager address.
-    ///
-    /// # Returns:
-    /// * `()` - No return value.
     fn set_rebalance_manager(e: Env, new_rebalance_manager: Address) {
         extend_instance_ttl(&e);
         let access_control = AccessControl::new(&e);
@@ -794,56 +812,149 @@ impl AdminInterfaceTrait for DeFindexVault {
         events::emit_rebalance_manager_changed_event(&e, new_rebalance_manager);
     }
 
-    /// Retrieves the current rebalance manager address for the vault.
-    ///
-    /// # Arguments:
-    /// * `e` - The environment.
-    ///
-    /// # Returns:
-    /// * `Result<Address, ContractError>` - The rebalance manager address if successful, otherwise returns a ContractError.
     fn get_rebalance_manager(e: Env) -> Result<Address, ContractError> {
         extend_instance_ttl(&e);
         let access_control = AccessControl::new(&e);
         access_control.get_rebalance_manager()
     }
 
-    /// Upgrades the contract with new WebAssembly (WASM) code.
-    ///
-    /// This function updates the contract with new WASM code provided by the `new_wasm_hash`.
-    ///
-    /// # Arguments
-    ///
-    /// * `e` - The runtime environment.
-    /// * `new_wasm_hash` - The hash of the new WASM code to upgrade the contract to.
-    ///
-    /// # Returns
-    /// * `Result<(), ContractError>` - Returns Ok(()) on success, ContractError if upgrade fails
-    ///
-    fn upgrade(e: Env, new_wasm_hash: BytesN<32>) -> Result<(), ContractError> {
-        if !storage::is_upgradable(&e) {
-            return Err(ContractError::NotUpgradable);
+    fn set_allowlist_manager(e: Env, new_allowlist_manager: Address) {
+        extend_instance_ttl(&e);
+        let access_control = AccessControl::new(&e);
+        access_control.set_allowlist_manager(&new_allowlist_manager);
+
+        events::emit_allowlist_manager_changed_event(&e, new_allowlist_manager);
+    }
+
+    fn get_allowlist_manager(e: Env) -> Result<Address, ContractError> {
+        extend_instance_ttl(&e);
+        let access_control = AccessControl::new(&e);
+        access_control.get_allowlist_manager()
+    }
+
+    fn propose(e: Env, action: ProposalAction) -> Result<u64, ContractError> {
+        extend_instance_ttl(&e);
+        let access_control = AccessControl::new(&e);
+        access_control.require_role(&RolesDataKey::Manager);
+
+        match &action {
+            ProposalAction::SetVaultFee(fee_bps) => {
+                if *fee_bps > 9000 {
+                    return Err(ContractError::MaximumFeeExceeded);
+                }
+            }
+            ProposalAction::SetFeeReceiver(_) | ProposalAction::SetEmergencyManager(_) => {}
+            ProposalAction::SetFeeDistributionMode(mode) => {
+                // Fee shares are priced against a single asset, so they would be over-minted in multi-asset vaults
+                if *mode == FeeDistributionMode::Shares && get_assets(&e)?.len() > 1 {
+                    panic_with_error!(&e, VaultError::FeeModeNotSupported);
+                }
+            }
+            ProposalAction::SetFeeReceivers(fee_receivers) => report::validate_fee_receivers(fee_receivers)?,
+            ProposalAction::SetManagementFee(fee_bps) => {
+                if *fee_bps > MAX_MANAGEMENT_FEE_BPS {
+                    return Err(ContractError::MaximumFeeExceeded);
+                }
+            }
+            ProposalAction::SetEntryFee(fee_bps) | ProposalAction::SetExitFee(fee_bps) => {
+                if *fee_bps > MAX_ENTRY_EXIT_FEE_BPS {
+                    return Err(ContractError::MaximumFeeExceeded);
+                }
+            }
+            ProposalAction::AddStrategy(asset, strategy) => validate_new_strategy(&e, asset, strategy)?,
+            ProposalAction::Upgrade(_) => {
+                if !storage::is_upgradable(&e) {
+                    return Err(ContractError::NotUpgradable);
+                }
+            }
         }
+
+        queue_proposal(&e, action)
+    }
+
+    fn execute_proposal(e: Env, proposal_id: u64) -> Result<(), ContractError> {
         extend_instance_ttl(&e);
         let access_control = AccessControl::new(&e);
         access_control.require_role(&RolesDataKey::Manager);
-        
-        e.deployer().update_current_contract_wasm(new_wasm_hash);
+
+        let proposal = get_proposal(&e, proposal_id);
+        if e.ledger().timestamp() < proposal.executable_at {
+            panic_with_error!(&e, VaultError::ProposalNotReady);
+        }
+        remove_proposal(&e, proposal_id);
+
+        match proposal.action {
+            ProposalAction::SetVaultFee(fee_bps) => {
+                report::lock_all_fees(&e)?;
+                set_vault_fee(&e, &fee_bps);
+            }
+            ProposalAction::SetFeeReceiver(new_fee_receiver) => {
+                access_control.set_fee_receiver(&new_fee_receiver);
+                events::emit_fee_receiver_changed_event(&e, new_fee_receiver, access_control.get_manager()?);
+            }
+            ProposalAction::SetEmergencyManager(new_emergency_manager) => {
+                access_control.set_emergency_manager(&new_emergency_manager);
+                events::emit_emergency_manager_changed_event(&e, new_emergency_manager);
+            }
+            ProposalAction::SetFeeReceivers(fee_receivers) => {
+                set_fee_receivers(&e, &fee_receivers);
+                events::emit_fee_receivers_changed_event(&e, fee_receivers, access_control.get_manager()?);
+            }
+            ProposalAction::SetManagementFee(fee_bps) => {
+                report::accrue_management_fee(&e)?;
+                set_management_fee(&e, &fee_bps);
+            }
+            ProposalAction::SetFeeDistributionMode(mode) => {
+                set_fee_distribution_mode(&e, &mode);
+                events::emit_fee_distribution_mode_changed_event(&e, mode);
+            }
+            ProposalAction::SetEntryFee(fee_bps) => set_entry_fee(&e, &fee_bps),
+            ProposalAction::SetExitFee(fee_bps) => set_exit_fee(&e, &fee_bps),
+            ProposalAction::AddStrategy(asset, strategy) => {
+                let strategy_address = strategy.address.clone();
+                add_strategy(&e, &asset, strategy)?;
+                events::emit_strategy_added_event(&e, asset, strategy_address);
+            }
+            ProposalAction::Upgrade(new_wasm_hash) => {
+                if !storage::is_upgradable(&e) {
+                    return Err(ContractError::NotUpgradable);
+                }
+                e.deployer().update_current_contract_wasm(new_wasm_hash);
+            }
+        }
+
+        events::emit_proposal_executed_event(&e, proposal_id);
         Ok(())
     }
+
+    fn veto_proposal(e: Env, proposal_id: u64) -> Result<(), ContractError> {
+        extend_instance_ttl(&e);
+        let access_control = AccessControl::new(&e);
+        access_control.require_role(&RolesDataKey::EmergencyManager);
+
+        // Panics with `ProposalNotFound` if the proposal was already executed or vetoed
+        get_proposal(&e, proposal_id);
+        remove_proposal(&e, proposal_id);
+
+        events::emit_proposal_vetoed_event(&e, proposal_id);
+        Ok(())
+    }
+
+    fn get_proposal(e: Env, proposal_id: u64) -> Result<Proposal, ContractError> {
+        extend_instance_ttl(&e);
+        Ok(get_proposal(&e, proposal_id))
+    }
 }
 
 #[contractimpl]
 impl VaultManagementTrait for DeFindexVault {
 
-    /// Rebalances the vault by executing a series of instructions.
-    ///
-    /// # Arguments:
-    /// * `e` - The environment.
-    /// * `instructions` - A vector of `Instruction` structs representing actions (withdraw, invest, swap, zapper) to be taken.
-    ///
-    /// # Returns:
-    /// * `Result<(), ContractError>` - Ok if successful, otherwise returns a ContractError.
-    fn rebalance(e: Env, caller: Address, instructions: Vec<Instruction>) -> Result<(), ContractError> {
+    fn rebalance(
+        e: Env,
+        caller: Address,
+        instructions: Vec<Instruction>,
+        max_loss_bps: Option<u32>,
+    ) -> Result<(), ContractError> {
         extend_instance_ttl(&e);
 
         let access_control = AccessControl::new(&e);
@@ -851,166 +962,31 @@ impl VaultManagementTrait for DeFindexVault {
             &[RolesDataKey::RebalanceManager, RolesDataKey::Manager],
             &caller,
         );
+        require_rebalances_enabled(&e);
 
         if instructions.is_empty() {
             panic_with_error!(&e, ContractError::NoInstructions);
         }
+        let snapshot = max_loss_bps.map(|_| snapshot_managed_value(&e)).transpose()?;
         for instruction in instructions.iter() {
-            match instruction {
-                Instruction::Unwind(strategy_address, amount) => {
-                    let asset_address = get_strategy_asset(&e, &strategy_address)?;
-                    let strategy_invested_funds = fetch_strategy_invested_funds(&e, &strategy_address, true)?;
-                    if amount <= 0 {
-                        panic_with_error!(&e, ContractError::AmountNotAllowed);
-                    }
-                    if amount > strategy_invested_funds {
-                        return Err(ContractError::UnwindMoreThanAvailable);
-                    } else {
-                        report::distribute_strategy_fees(&e, &strategy_address, &access_control, &asset_address.address)?;
-                        unwind_from_strategy(
-                            &e,
-                            &strategy_address,
-                            &amount,
-                            &e.current_contract_address(),
-                        )?;
-                        let mut report = get_report(&e, &strategy_address);
-                        report.prev_balance = strategy_invested_funds - amount;
-                        set_report(&e, &strategy_address, &report);
-                        let call_params = vec![&e, (strategy_address.clone(), amount, e.current_contract_address())];
-                        events::emit_rebalance_unwind_event(&e, call_params, report);
-                    }
-                }
-                Instruction::Invest(strategy_address, amount) => {
-                    let asset_address = get_strategy_asset(&e, &strategy_address)?;
-                    
-                    // Check if strategy is paused before investing
-                    let strategy = get_strategy_struct(&strategy_address, &asset_address)?;
-                    if strategy.paused {
-                        panic_with_error!(&e, ContractError::StrategyPaused);
-                    }
-                    if amount <= 0 {
-                        panic_with_error!(&e, ContractError::AmountNotAllowed);
-                    }
-                    let report = invest_in_strategy(&e, &asset_address.address, &strategy_address, &amount)?;
-                    let call_params = AssetInvestmentAllocation {
-                        asset: asset_address.address.clone(),
-                        strategy_allocations: vec![&e, Some(StrategyAllocation {
-                            strategy_address: strategy_address.clone(),
-                            amount: amount.clone(),
-                            paused: strategy.paused
-                        })],
-                    };
-                    report::distribute_strategy_fees(&e, &strategy_address, &access_control, &asset_address.address)?;
-                    events::emit_rebalance_invest_event(&e, vec![&e, call_params], report);
-                }
-                Instruction::SwapExactIn(
-                    token_in,
-                    token_out,
-                    amount_in,
-                    amount_out_min,
-                    deadline,
-                ) => {
-                    internal_swap_exact_tokens_for_tokens(
-                        &e,
-                        &token_in,
-                        &token_out,
-                        &amount_in,
-                        &amount_out_min,
-                        &deadline,
-                    )?;
-                    let swap_args: Vec<Val> = vec![
-                        &e,
-                        amount_in.into_val(&e),
-                        amount_out_min.into_val(&e),
-                        vec![&e, token_in.to_val(), token_out.to_val()].into_val(&e), // path
-                        e.current_contract_address().to_val(),
-                        deadline.into_val(&e),
-                    ];
-                    events::emit_rebalance_swap_exact_in_event(&e, swap_args);
-                }
-                Instruction::SwapExactOut(
-                    token_in,
-                    token_out,
-                    amount_out,
-                    amount_in_max,
-                    deadline,
-                ) => {
-                    internal_swap_tokens_for_exact_tokens(
-                        &e,
-                        &token_in,
-                        &token_out,
-                        &amount_out,
-                        &amount_in_max,
-                        &deadline,
-                    )?;
-                    let swap_args: Vec<Val> = vec![
-                        &e,
-                        amount_out.into_val(&e),
-                        amount_in_max.into_val(&e),
-                        vec![&e, token_in.to_val(), token_out.to_val()].into_val(&e), // path
-                        e.current_contract_address().to_val(),
-                        deadline.into_val(&e),
-                    ];
-                    events::emit_rebalance_swap_exact_out_event(&e, swap_args);
-                }
-            }
+            execute_instruction(&e, &access_control, instruction)?;
+        }
+        if let (Some(snapshot), Some(max_loss_bps)) = (snapshot, max_loss_bps) {
+            check_max_loss(&e, &snapshot, max_loss_bps)?;
         }
 
         Ok(())
     }
 
-    /// Locks fees for all assets and their strategies.
-    ///
-    /// Iterates through each asset and its strategies, locking fees based on `new_fee_bps` or the default vault fee.
-    ///
-    /// # Arguments
-    /// * `e` - The environment reference.
-    /// * `new_fee_bps` - Optional fee basis points to override the default.
-    ///
-    /// # Returns
-    /// * `Result<Vec<(Address, i128)>, ContractError>` - A vector of tuples with strategy addresses and locked fee amounts in their underlying_asset.
-    fn lock_fees(e: Env, new_fee_bps: Option<u32>) -> Result<Vec<Report>, ContractError> {
+    fn lock_fees(e: Env) -> Result<Vec<Report>, ContractError> {
         extend_instance_ttl(&e);
 
         let access_control = AccessControl::new(&e);
         access_control.require_role(&RolesDataKey::Manager);
 
-        // If a new fee basis points is provided, set the new vault fee
-        if let Some(fee_bps) = new_fee_bps {
-            set_vault_fee(&e, &fee_bps);
-        }
-
-        // Get the current vault fee
-        let current_vault_fee = get_vault_fee(&e);
-
-        // Get all assets and their strategies
-        let assets = get_assets(&e)?;
-        let mut reports: Vec<Report> = Vec::new(&e);
-
-        // Loop through each asset and its strategies to lock the fees
-        for asset in assets.iter() {
-            for strategy in asset.strategies.iter() {
-                let mut report = get_report(&e, &strategy.address);
-                if report.gains_or_losses > 0 {
-                    report.lock_fee(current_vault_fee)?;
-                    set_report(&e, &strategy.address, &report);
-                }
-                reports.push_back(report);
-            }
-        }
-
-        Ok(reports)
+        report::lock_all_fees(&e)
     }
 
-    /// Releases locked fees for a specific strategy.
-    ///
-    /// # Arguments
-    /// * `e` - The environment reference.
-    /// * `strategy` - The address of the strategy for which to release fees.
-    /// * `amount` - The amount of fees to release.
-    ///
-    /// # Returns
-    /// * `Result<Report, ContractError>` - A report of the released fees or a `ContractError` if the operation fails.
     fn release_fees(e: Env, strategy: Address, amount: i128) -> Result<Report, ContractError> {
         extend_instance_ttl(&e);
         validate_amount(amount)?;
@@ -1024,18 +1000,6 @@ impl VaultManagementTrait for DeFindexVault {
         Ok(report)
     }
 
-    /// Distributes the locked fees for all assets and their strategies.
-    ///
-    /// This function iterates through each asset and its strategies, calculating the fees to be distributed
-    /// to the vault fee receiver and the DeFindex protocol fee receiver based on their respective fee rates.
-    /// It ensures proper authorization and validation checks before proceeding with the distribution.
-    ///
-    /// # Arguments
-    /// * `e` - The environment reference.
-    /// * `caller` - The address initiating the fee distribution.
-    ///
-    /// # Returns
-    /// * `Result<Vec<(Address, i128)>, ContractError>` - A vector of tuples with asset addresses and the total distributed fee amounts.
     fn distribute_fees(e: Env, caller: Address) -> Result<Vec<(Address, i128)>, ContractError> {
         extend_instance_ttl(&e);
 
@@ -1070,4 +1034,193 @@ impl VaultManagementTrait for DeFindexVault {
 
         Ok(distributed_fees)
     }
+
+    fn set_target_allocation(e: Env, target_allocation: AssetTargetAllocation) -> Result<(), ContractError> {
+        extend_instance_ttl(&e);
+        let access_control = AccessControl::new(&e);
+        access_control.require_role(&RolesDataKey::Manager);
+
+        let assets = get_assets(&e)?;
+        validate_target_allocation(&e, &assets, &target_allocation)?;
+        set_target_allocation(&e, &target_allocation);
+
+        events::emit_target_allocation_changed_event(&e, target_allocation);
+        Ok(())
+    }
+
+    fn get_target_allocation(e: Env, asset: Address) -> Option<AssetTargetAllocation> {
+        extend_instance_ttl(&e);
+        get_target_allocation(&e, &asset)
+    }
+
+    fn rebalance_to_targets(e: Env, max_instructions: u32) -> Result<Vec<Instruction>, ContractError> {
+        extend_instance_ttl(&e);
+        require_rebalances_enabled(&e);
+
+        let access_control = AccessControl::new(&e);
+        let instructions = generate_target_rebalance_instructions(&e, max_instructions)?;
+        for instruction in instructions.iter() {
+            execute_instruction(&e, &access_control, instruction)?;
+        }
+
+        Ok(instructions)
+    }
+
+    fn set_rebalance_tolerance(e: Env, tolerance_bps: u32) -> Result<(), ContractError> {
+        extend_instance_ttl(&e);
+        let access_control = AccessControl::new(&e);
+        access_control.require_role(&RolesDataKey::Manager);
+
+        if tolerance_bps as i128 > SCALAR_BPS {
+            return Err(ContractError::InvalidRatio);
+        }
+        set_rebalance_tolerance(&e, &tolerance_bps);
+
+        events::emit_rebalance_tolerance_changed_event(&e, tolerance_bps);
+        Ok(())
+    }
+
+    fn get_rebalance_tolerance(e: Env) -> u32 {
+        extend_instance_ttl(&e);
+        get_rebalance_tolerance(&e)
+    }
+
+    fn set_idle_reserve(e: Env, asset: Address, reserve_bps: u32) -> Result<(), ContractError> {
+        extend_instance_ttl(&e);
+        let access_control = AccessControl::new(&e);
+        access_control.require_role(&RolesDataKey::Manager);
+
+        require_vault_asset(&e, &asset)?;
+        if reserve_bps as i128 > SCALAR_BPS {
+            return Err(ContractError::InvalidRatio);
+        }
+        set_idle_reserve(&e, &asset, &reserve_bps);
+
+        events::emit_idle_reserve_changed_event(&e, asset, reserve_bps);
+        Ok(())
+    }
+
+    fn get_idle_reserve(e: Env, asset: Address) -> u32 {
+        extend_instance_ttl(&e);
+        get_idle_reserve(&e, &asset)
+    }
+
+    fn set_withdraw_order(e: Env, asset: Address, strategies: Vec<Address>) -> Result<(), ContractError> {
+        extend_instance_ttl(&e);
+        let access_control = AccessControl::new(&e);
+        access_control.require_role(&RolesDataKey::Manager);
+
+        let assets = get_assets(&e)?;
+        validate_withdraw_order(&e, &assets, &asset, &strategies)?;
+        set_withdraw_order(&e, &asset, &strategies);
+
+        events::emit_withdraw_order_changed_event(&e, asset, strategies);
+        Ok(())
+    }
+
+    fn get_withdraw_order(e: Env, asset: Address) -> Vec<Address> {
+        extend_instance_ttl(&e);
+        get_withdraw_order(&e, &asset)
+    }
+
+    fn add_strategy(e: Env, asset_address: Address, strategy: Strategy) -> Result<u64, ContractError> {
+        extend_instance_ttl(&e);
+        let access_control = AccessControl::new(&e);
+        access_control.require_role(&RolesDataKey::Manager);
+
+        validate_new_strategy(&e, &asset_address, &strategy)?;
+        queue_proposal(&e, ProposalAction::AddStrategy(asset_address, strategy))
+    }
+
+    fn remove_strategy(e: Env, strategy_address: Address) -> Result<(), ContractError> {
+        extend_instance_ttl(&e);
+        let access_control = AccessControl::new(&e);
+        access_control.require_role(&RolesDataKey::Manager);
+
+        let asset = remove_strategy(&e, &strategy_address)?;
+
+        events::emit_strategy_removed_event(&e, asset, strategy_address);
+        Ok(())
+    }
+
+    fn set_deposit_cap(e: Env, asset: Address, cap: Option<i128>) -> Result<(), ContractError> {
+        extend_instance_ttl(&e);
+        let access_control = AccessControl::new(&e);
+        access_control.require_role(&RolesDataKey::Manager);
+
+        if let Some(cap) = cap {
+            validate_amount(cap)?;
+        }
+        require_vault_asset(&e, &asset)?;
+        set_deposit_cap(&e, &asset, &cap);
+
+        events::emit_deposit_cap_changed_event(&e, asset, cap);
+        Ok(())
+    }
+
+    fn get_deposit_cap(e: Env, asset: Address) -> Option<i128> {
+        extend_instance_ttl(&e);
+        get_deposit_cap(&e, &asset)
+    }
+
+    fn set_user_share_cap(e: Env, cap: Option<i128>) -> Result<(), ContractError> {
+        extend_instance_ttl(&e);
+        let access_control = AccessControl::new(&e);
+        access_control.require_role(&RolesDataKey::Manager);
+
+        if let Some(cap) = cap {
+            validate_amount(cap)?;
+        }
+        set_user_share_cap(&e, &cap);
+
+        events::emit_user_share_cap_changed_event(&e, cap);
+        Ok(())
+    }
+
+    fn get_user_share_cap(e: Env) -> Option<i128> {
+        extend_instance_ttl(&e);
+        get_user_share_cap(&e)
+    }
+
+    fn set_allowlist_enabled(e: Env, enabled: bool) {
+        extend_instance_ttl(&e);
+        let access_control = AccessControl::new(&e);
+        access_control.require_role(&RolesDataKey::AllowlistManager);
+
+        set_allowlist_enabled(&e, enabled);
+        events::emit_allowlist_enabled_event(&e, enabled);
+    }
+
+    fn is_allowlist_enabled(e: Env) -> bool {
+        extend_instance_ttl(&e);
+        is_allowlist_enabled(&e)
+    }
+
+    fn set_allowlisted(e: Env, address: Address, allowed: bool) {
+        extend_instance_ttl(&e);
+        let access_control = AccessControl::new(&e);
+        access_control.require_role(&RolesDataKey::AllowlistManager);
+
+        set_allowlisted(&e, &address, allowed);
+        events::emit_allowlist_changed_event(&e, address, allowed);
+    }
+
+    fn is_allowlisted(e: Env, address: Address) -> bool {
+        extend_instance_ttl(&e);
+        is_allowlisted(&e, &address)
+    }
+
+    fn get_fee_distribution_mode(e: Env) -> FeeDistributionMode {
+        extend_instance_ttl(&e);
+        get_fee_distribution_mode(&e)
+    }
+
+    fn simulate_rebalance(e: Env, instructions: Vec<Instruction>) -> Result<RebalanceSimulation, ContractError> {
+        extend_instance_ttl(&e);
+
+        if instructions.is_empty() {
+            return Err(ContractError::NoInstructions);
+        }
+        simulate_instructions(&e, &instructions)
+    }
 }
@@ -1,4 +1,5 @@
-use soroban_sdk::{contracttype, Address, Vec};
+use common::models::Strategy;
+use soroban_sdk::{contracttype, Address, BytesN, Vec};
 
 // Investment Allocation in Strategies
 #[contracttype]
@@ -27,6 +28,133 @@ pub struct AssetInvestmentAllocation {
     pub strategy_allocations: Vec<Option<StrategyAllocation>>,
 }
 
+// Pending withdrawal request, its shares are escrowed by the vault until claimed
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WithdrawRequest {
+    pub owner: Address,
+    pub shares: i128,
+}
+
+// Manager handover waiting for its timelock to expire
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct QueuedManager {
+    pub new_manager: Address,
+    pub available_at: u64,
+}
+
+// Manager timelock, a new delay only applies once the previous one has elapsed
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ManagerTimelock {
+    pub delay: u64,
+    pub previous_delay: u64,
+    pub effective_at: u64,
+}
+
+// Vault-wide circuit breaker, set by the Emergency Manager
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PauseFlags {
+    pub deposits: bool,
+    pub withdrawals: bool,
+    pub rebalances: bool,
+}
+
+// How locked performance fees are paid to the fee receivers
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum FeeDistributionMode {
+    /// Transfer the underlying asset of the locked fees.
+    Assets,
+
+    /// Mint vault shares worth the locked fees.
+    // The fees stay invested in the strategy. Only available in single-asset vaults
+    Shares,
+}
+
+// Weighted receiver of the vault's part of the fees
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FeeReceiver {
+    pub address: Address,
+    pub bps: u32,
+}
+
+// Sensitive parameter change, executable once the manager timelock has elapsed
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ProposalAction {
+    /// Set the vault fee, in basis points.
+    SetVaultFee(u32),
+
+    /// Set the vault fee receiver.
+    SetFeeReceiver(Address),
+
+    /// Set the emergency manager, who can veto proposals.
+    SetEmergencyManager(Address),
+
+    /// Split the vault's part of the fees between weighted receivers.
+    // The weights must add up to 10000 basis points; an empty list pays the vault fee receiver again
+    SetFeeReceivers(Vec<FeeReceiver>),
+
+    /// Set the annual management fee, in basis points.
+    SetManagementFee(u32),
+
+    /// Set how locked performance fees are paid.
+    SetFeeDistributionMode(FeeDistributionMode),
+
+    /// Set the entry fee, in basis points of the minted shares.
+    SetEntryFee(u32),
+
+    /// Set the exit fee, in basis points of the withdrawn shares.
+    SetExitFee(u32),
+
+    /// Add a strategy to an asset of the vault.
+    // The strategy must report the asset through `asset()` and must not be used by the vault yet
+    AddStrategy(Address, Strategy), // (asset, strategy)
+
+    /// Upgrade the vault to the given WASM hash.
+    Upgrade(BytesN<32>),
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Proposal {
+    pub action: ProposalAction,
+    pub executable_at: u64,
+}
+
+// Target weight of a strategy, in basis points of the asset's total managed funds
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StrategyTarget {
+    pub strategy_address: Address,
+    pub weight_bps: u32,
+}
+
+// Target allocation policy of an asset. `idle_bps` plus every strategy weight must add up to 10_000
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AssetTargetAllocation {
+    pub asset: Address,
+    pub idle_bps: u32,
+    pub strategy_targets: Vec<StrategyTarget>,
+}
+
+// Move of funds from a strategy of one asset to a strategy of another asset through a swap
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ZapperInstruction {
+    pub strategy_from: Address,
+    pub strategy_to: Address,
+    pub amount: i128,         // amount to unwind from `strategy_from` and swap
+    pub path: Vec<Address>,   // swap path, from the asset of `strategy_from` to the asset of `strategy_to`
+    pub amount_out_min: i128, // minimum swap output invested into `strategy_to`
+    pub deadline: u64,
+}
+
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum Instruction {
@@ -38,21 +166,38 @@ pub enum Instruction {
 
     /// Perform a swap with an exact input amount.
     SwapExactIn(
-        Address, // token_in
-        Address, // token_out
-        i128,    // amount_in
-        i128,    // amount_out_min
-        u64,     // deadline
+        Vec<Address>, // path, from token_in to token_out
+        i128,         // amount_in
+        i128,         // amount_out_min
+        u64,          // deadline
     ),
 
     /// Perform a swap with an exact output amount.
     SwapExactOut(
-        Address, // token_in
-        Address, // token_out
-        i128,    // amount_out
-        i128,    // amount_in_max
-        u64,     // deadline
+        Vec<Address>, // path, from token_in to token_out
+        i128,         // amount_out
+        i128,         // amount_in_max
+        u64,          // deadline
     ),
-    // /// Placeholder for zap operations (commented for future use).
-    // Zapper(Vec<ZapperInstruction>), // instructions
+
+    /// Move funds between strategies of different assets, unwinding, swapping and investing in one step.
+    Zapper(Vec<ZapperInstruction>), // instructions
+}
+
+// Expected result of a swap of a simulated rebalance
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SwapSimulation {
+    pub token_in: Address,
+    pub token_out: Address,
+    pub amount_in: i128,
+    pub amount_out: i128,
+}
+
+// Projected state of the vault after a rebalance, without executing it
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RebalanceSimulation {
+    pub allocations: Vec<CurrentAssetInvestmentAllocation>, // projected idle and strategy balances of every asset
+    pub swaps: Vec<SwapSimulation>,                         // expected swaps, in instruction order
 }
@@ -0,0 +1,480 @@
+use soroban_sdk::{panic_with_error, vec, Address, Env, IntoVal, Val, Vec};
+
+use crate::{
+    access::AccessControl,
+    constants::SCALAR_BPS,
+    events,
+    funds::{fetch_strategy_invested_funds, fetch_total_managed_funds},
+    models::{
+        AssetInvestmentAllocation, CurrentAssetInvestmentAllocation, Instruction, RebalanceSimulation,
+        StrategyAllocation, SwapSimulation, ZapperInstruction,
+    },
+    report,
+    router::{
+        get_pair_reserves, get_swap_args, internal_swap_exact_tokens_for_tokens, internal_swap_tokens_for_exact_tokens,
+        simulate_swap_exact_tokens_for_tokens, simulate_swap_tokens_for_exact_tokens,
+    },
+    storage::{get_rebalance_tolerance, get_report, get_target_allocation, set_report},
+    strategies::{get_strategy_asset, get_strategy_struct, invest_in_strategy, unwind_from_strategy},
+    ContractError,
+};
+
+/// Executes a single rebalance instruction and emits the corresponding rebalance event.
+///
+/// Authorization is not checked here; callers are responsible for it.
+pub fn execute_instruction(
+    e: &Env,
+    access_control: &AccessControl,
+    instruction: Instruction,
+) -> Result<(), ContractError> {
+    match instruction {
+        Instruction::Unwind(strategy_address, amount) => {
+            let report = unwind(e, access_control, &strategy_address, amount)?;
+            let call_params = vec![e, (strategy_address.clone(), amount, e.current_contract_address())];
+            events::emit_rebalance_unwind_event(e, call_params, report);
+        }
+        Instruction::Invest(strategy_address, amount) => {
+            let (asset_address, report) = invest(e, access_control, &strategy_address, amount)?;
+            let call_params = AssetInvestmentAllocation {
+                asset: asset_address,
+                strategy_allocations: vec![e, Some(StrategyAllocation {
+                    strategy_address: strategy_address.clone(),
+                    amount,
+                    paused: false
+                })],
+            };
+            events::emit_rebalance_invest_event(e, vec![e, call_params], report);
+        }
+        Instruction::SwapExactIn(
+            path,
+            amount_in,
+            amount_out_min,
+            deadline,
+        ) => {
+            internal_swap_exact_tokens_for_tokens(
+                e,
+                &path,
+                &amount_in,
+                &amount_out_min,
+                &deadline,
+            )?;
+            let swap_args = get_swap_args(e, &path, (&amount_in, &amount_out_min), &deadline);
+            events::emit_rebalance_swap_exact_in_event(e, swap_args);
+        }
+        Instruction::SwapExactOut(
+            path,
+            amount_out,
+            amount_in_max,
+            deadline,
+        ) => {
+            internal_swap_tokens_for_exact_tokens(
+                e,
+                &path,
+                &amount_out,
+                &amount_in_max,
+                &deadline,
+            )?;
+            let swap_args = get_swap_args(e, &path, (&amount_out, &amount_in_max), &deadline);
+            events::emit_rebalance_swap_exact_out_event(e, swap_args);
+        }
+        Instruction::Zapper(zaps) => {
+            for zap in zaps.iter() {
+                let report = execute_zap(e, access_control, &zap)?;
+                let zap_args: Vec<Val> = vec![
+                    e,
+                    zap.strategy_from.into_val(e),
+                    zap.strategy_to.into_val(e),
+                    zap.amount.into_val(e),
+                    zap.path.into_val(e),
+                    zap.amount_out_min.into_val(e),
+                    zap.deadline.into_val(e),
+                ];
+                events::emit_rebalance_zapper_event(e, zap_args, report);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Unwinds `amount` from a strategy back to the vault, distributing its locked fees first.
+///
+/// Returns the strategy's report after the unwind.
+fn unwind(
+    e: &Env,
+    access_control: &AccessControl,
+    strategy_address: &Address,
+    amount: i128,
+) -> Result<report::Report, ContractError> {
+    let asset_address = get_strategy_asset(e, strategy_address)?;
+    let strategy_invested_funds = fetch_strategy_invested_funds(e, strategy_address, true)?;
+    if amount <= 0 {
+        panic_with_error!(e, ContractError::AmountNotAllowed);
+    }
+    if amount > strategy_invested_funds {
+        return Err(ContractError::UnwindMoreThanAvailable);
+    }
+    report::distribute_strategy_fees(e, strategy_address, access_control, &asset_address.address)?;
+    let remaining_balance = unwind_from_strategy(
+        e,
+        strategy_address,
+        &amount,
+        &e.current_contract_address(),
+    )?;
+    let mut report = get_report(e, strategy_address);
+    report.prev_balance = remaining_balance;
+    set_report(e, strategy_address, &report);
+    Ok(report)
+}
+
+/// Invests `amount` of idle funds into a strategy and distributes its locked fees.
+///
+/// Returns the strategy's asset and its report after the investment.
+fn invest(
+    e: &Env,
+    access_control: &AccessControl,
+    strategy_address: &Address,
+    amount: i128,
+) -> Result<(Address, report::Report), ContractError> {
+    let asset_address = get_strategy_asset(e, strategy_address)?;
+
+    // Check if strategy is paused before investing
+    let strategy = get_strategy_struct(strategy_address, &asset_address)?;
+    if strategy.paused {
+        panic_with_error!(e, ContractError::StrategyPaused);
+    }
+    if amount <= 0 {
+        panic_with_error!(e, ContractError::AmountNotAllowed);
+    }
+    let report = invest_in_strategy(e, &asset_address.address, strategy_address, &amount)?;
+    report::distribute_strategy_fees(e, strategy_address, access_control, &asset_address.address)?;
+    Ok((asset_address.address, report))
+}
+
+/// Checks that `zap.path` starts with the asset of `zap.strategy_from` and ends with the asset of
+/// `zap.strategy_to`, returning `ContractError::UnsupportedAsset` otherwise.
+fn validate_zap_path(e: &Env, zap: &ZapperInstruction) -> Result<(), ContractError> {
+    let asset_from = get_strategy_asset(e, &zap.strategy_from)?;
+    let asset_to = get_strategy_asset(e, &zap.strategy_to)?;
+    if zap.path.first() != Some(asset_from.address) || zap.path.last() != Some(asset_to.address) {
+        return Err(ContractError::UnsupportedAsset);
+    }
+    Ok(())
+}
+
+/// Moves funds between strategies of two different assets: unwinds `zap.amount` from `zap.strategy_from`, swaps
+/// it along `zap.path` and invests the whole swap output into `zap.strategy_to`.
+///
+/// The swap must return at least `zap.amount_out_min`, which bounds the slippage of the whole move. The path must
+/// start with the asset of `zap.strategy_from` and end with the asset of `zap.strategy_to`.
+///
+/// Returns the report of `zap.strategy_to` after the investment.
+fn execute_zap(
+    e: &Env,
+    access_control: &AccessControl,
+    zap: &ZapperInstruction,
+) -> Result<report::Report, ContractError> {
+    validate_zap_path(e, zap)?;
+
+    unwind(e, access_control, &zap.strategy_from, zap.amount)?;
+    let amounts = internal_swap_exact_tokens_for_tokens(
+        e,
+        &zap.path,
+        &zap.amount,
+        &zap.amount_out_min,
+        &zap.deadline,
+    )?;
+    let amount_out = amounts.last().unwrap_or(0);
+    let (_, report) = invest(e, access_control, &zap.strategy_to, amount_out)?;
+    Ok(report)
+}
+
+/// Projects the result of executing `instructions` on the current managed funds of the vault, without executing
+/// them.
+///
+/// Instructions are checked as `execute_instruction` checks them, and swaps are priced with
+/// the Soroswap pair formula on the current pair reserves. Reserves are not updated between swaps, so
+/// several swaps through the same pair are each priced as if they were the only one. Balances are net of locked
+/// fees, as in `fetch_total_managed_funds`.
+///
+/// # Returns
+/// * `Result<RebalanceSimulation, ContractError>` - The projected allocations of every asset and the expected
+///   swaps, or the error the first failing instruction would return.
+pub fn simulate_instructions(
+    e: &Env,
+    instructions: &Vec<Instruction>,
+) -> Result<RebalanceSimulation, ContractError> {
+    let mut allocations = fetch_total_managed_funds(e, false)?;
+    let mut swaps: Vec<SwapSimulation> = Vec::new(e);
+
+    for instruction in instructions.iter() {
+        match instruction {
+            Instruction::Unwind(strategy_address, amount) => {
+                simulate_strategy_move(e, &mut allocations, &strategy_address, amount, false)?;
+            }
+            Instruction::Invest(strategy_address, amount) => {
+                simulate_strategy_move(e, &mut allocations, &strategy_address, amount, true)?;
+            }
+            Instruction::SwapExactIn(path, amount_in, amount_out_min, _deadline) => {
+                let amount_out = simulate_swap_exact_tokens_for_tokens(e, &path, &amount_in, &amount_out_min)?;
+                simulate_swap(&mut allocations, &mut swaps, &path, amount_in, amount_out)?;
+            }
+            Instruction::SwapExactOut(path, amount_out, amount_in_max, _deadline) => {
+                let amount_in = simulate_swap_tokens_for_exact_tokens(e, &path, &amount_out, &amount_in_max)?;
+                simulate_swap(&mut allocations, &mut swaps, &path, amount_in, amount_out)?;
+            }
+            Instruction::Zapper(zaps) => {
+                for zap in zaps.iter() {
+                    validate_zap_path(e, &zap)?;
+
+                    simulate_strategy_move(e, &mut allocations, &zap.strategy_from, zap.amount, false)?;
+                    let amount_out =
+                        simulate_swap_exact_tokens_for_tokens(e, &zap.path, &zap.amount, &zap.amount_out_min)?;
+                    simulate_swap(&mut allocations, &mut swaps, &zap.path, zap.amount, amount_out)?;
+                    simulate_strategy_move(e, &mut allocations, &zap.strategy_to, amount_out, true)?;
+                }
+            }
+        }
+    }
+
+    Ok(RebalanceSimulation { allocations, swaps })
+}
+
+/// Checks an `Unwind` or `Invest` of `amount` as `execute_instruction` does and moves the funds between the idle
+/// and invested balances of the strategy's asset.
+fn simulate_strategy_move(
+    e: &Env,
+    allocations: &mut Vec<CurrentAssetInvestmentAllocation>,
+    strategy_address: &Address,
+    amount: i128,
+    invest: bool,
+) -> Result<(), ContractError> {
+    let asset_address = get_strategy_asset(e, strategy_address)?;
+    if invest && get_strategy_struct(strategy_address, &asset_address)?.paused {
+        return Err(ContractError::StrategyPaused);
+    }
+    if amount <= 0 {
+        return Err(ContractError::AmountNotAllowed);
+    }
+    move_strategy_funds(allocations, strategy_address, if invest { amount } else { -amount })
+}
+
+/// Moves `amount` of idle funds into a strategy, or out of it if `amount` is negative.
+fn move_strategy_funds(
+    allocations: &mut Vec<CurrentAssetInvestmentAllocation>,
+    strategy_address: &Address,
+    amount: i128,
+) -> Result<(), ContractError> {
+    for i in 0..allocations.len() {
+        let mut allocation = allocations.get_unchecked(i);
+        let position = allocation
+            .strategy_allocations
+            .iter()
+            .position(|strategy_allocation| &strategy_allocation.strategy_address == strategy_address);
+        let j = match position {
+            Some(j) => j as u32,
+            None => continue,
+        };
+
+        let mut strategy_allocation = allocation.strategy_allocations.get_unchecked(j);
+        if amount > allocation.idle_amount {
+            return Err(ContractError::InsufficientBalance);
+        }
+        if -amount > strategy_allocation.amount {
+            return Err(ContractError::UnwindMoreThanAvailable);
+        }
+        strategy_allocation.amount = strategy_allocation.amount.checked_add(amount).ok_or(ContractError::Overflow)?;
+        allocation.idle_amount = allocation.idle_amount.checked_sub(amount).ok_or(ContractError::Underflow)?;
+        allocation.invested_amount = allocation.invested_amount.checked_add(amount).ok_or(ContractError::Overflow)?;
+        allocation.strategy_allocations.set(j, strategy_allocation);
+        allocations.set(i, allocation);
+        return Ok(());
+    }
+    Err(ContractError::StrategyNotFound)
+}
+
+/// Moves the idle funds of a swap along `path` from its input asset to its output asset and records the swap.
+fn simulate_swap(
+    allocations: &mut Vec<CurrentAssetInvestmentAllocation>,
+    swaps: &mut Vec<SwapSimulation>,
+    path: &Vec<Address>,
+    amount_in: i128,
+    amount_out: i128,
+) -> Result<(), ContractError> {
+    let swap = SwapSimulation {
+        token_in: path.first_unchecked(),
+        token_out: path.last_unchecked(),
+        amount_in,
+        amount_out,
+    };
+    update_idle_funds(allocations, &swap.token_in, -amount_in)?;
+    update_idle_funds(allocations, &swap.token_out, amount_out)?;
+    swaps.push_back(swap);
+    Ok(())
+}
+
+fn update_idle_funds(
+    allocations: &mut Vec<CurrentAssetInvestmentAllocation>,
+    asset: &Address,
+    amount: i128,
+) -> Result<(), ContractError> {
+    let i = allocations
+        .iter()
+        .position(|allocation| &allocation.asset == asset)
+        .ok_or(ContractError::UnsupportedAsset)? as u32;
+    let mut allocation = allocations.get_unchecked(i);
+    allocation.idle_amount = allocation.idle_amount.checked_add(amount).ok_or(ContractError::Overflow)?;
+    if allocation.idle_amount < 0 {
+        return Err(ContractError::InsufficientBalance);
+    }
+    allocation.total_amount = allocation.total_amount.checked_add(amount).ok_or(ContractError::Overflow)?;
+    allocations.set(i, allocation);
+    Ok(())
+}
+
+/// The value of the vault's managed funds before a rebalance, together with the prices used to compute it.
+pub struct ValueSnapshot {
+    /// `(reserve_asset, reserve_base)` of the Soroswap pair of each asset with the base asset, in asset order.
+    prices: Vec<(i128, i128)>,
+    value: i128,
+}
+
+/// Reports every strategy, locks their fees and snapshots the total managed funds of the vault, valued in its
+/// first (base) asset.
+///
+/// Every other asset is priced with the reserves of its Soroswap pair with the base asset, so a multi-asset vault
+/// needs such a pair for each of its assets.
+pub fn snapshot_managed_value(e: &Env) -> Result<ValueSnapshot, ContractError> {
+    let total_managed_funds = fetch_total_managed_funds(e, true)?;
+    let base_asset = total_managed_funds.get_unchecked(0).asset;
+
+    let mut prices: Vec<(i128, i128)> = Vec::new(e);
+    for asset_allocation in total_managed_funds.iter() {
+        if asset_allocation.asset == base_asset {
+            prices.push_back((1, 1));
+        } else {
+            prices.push_back(get_pair_reserves(e, &asset_allocation.asset, &base_asset)?);
+        }
+    }
+    let value = managed_value(&total_managed_funds, &prices)?;
+    Ok(ValueSnapshot { prices, value })
+}
+
+/// Checks that the vault's managed funds, valued at the prices of `snapshot`, did not drop by more than
+/// `max_loss_bps` of the snapshot value.
+///
+/// Prices are not read again, so the price impact of the rebalance's own swaps counts as a loss.
+///
+/// # Errors
+/// * `ContractError::MaxLossExceeded` - If the loss is greater than `max_loss_bps`.
+pub fn check_max_loss(e: &Env, snapshot: &ValueSnapshot, max_loss_bps: u32) -> Result<(), ContractError> {
+    let value = managed_value(&fetch_total_managed_funds(e, false)?, &snapshot.prices)?;
+    if value >= snapshot.value {
+        return Ok(());
+    }
+    let loss = snapshot.value.checked_sub(value).ok_or(ContractError::Underflow)?;
+    if loss > bps_of(snapshot.value, max_loss_bps as i128)? {
+        return Err(ContractError::MaxLossExceeded);
+    }
+    Ok(())
+}
+
+fn managed_value(
+    total_managed_funds: &Vec<CurrentAssetInvestmentAllocation>,
+    prices: &Vec<(i128, i128)>,
+) -> Result<i128, ContractError> {
+    let mut value: i128 = 0;
+    for (asset_allocation, (reserve_asset, reserve_base)) in total_managed_funds.iter().zip(prices.iter()) {
+        let asset_value = asset_allocation
+            .total_amount
+            .checked_mul(reserve_base)
+            .ok_or(ContractError::Overflow)?
+            .checked_div(reserve_asset)
+            .ok_or(ContractError::ArithmeticError)?;
+        value = value.checked_add(asset_value).ok_or(ContractError::Overflow)?;
+    }
+    Ok(value)
+}
+
+/// Computes the `Unwind` and `Invest` instructions needed to bring every asset with a target allocation
+/// back within the rebalance tolerance band.
+///
+/// For each asset, a strategy is only touched when its invested amount deviates from its target
+/// (`total_amount * weight_bps / SCALAR_BPS`) by more than `total_amount * tolerance_bps / SCALAR_BPS`; it is then
+/// moved exactly to its target. Unwinds of an asset always come before its investments, and investments never
+/// use the idle buffer of the asset, so any prefix of the returned instructions can be executed safely.
+/// Paused strategies are never invested in.
+///
+/// # Arguments
+/// * `e` - The environment reference.
+/// * `max_instructions` - The maximum number of instructions to return.
+///
+/// # Returns
+/// * `Result<Vec<Instruction>, ContractError>` - The instructions to execute, in order.
+pub fn generate_target_rebalance_instructions(
+    e: &Env,
+    max_instructions: u32,
+) -> Result<Vec<Instruction>, ContractError> {
+    let tolerance_bps = get_rebalance_tolerance(e) as i128;
+    let total_managed_funds = fetch_total_managed_funds(e, true)?;
+
+    let mut instructions: Vec<Instruction> = Vec::new(e);
+    for asset_allocation in total_managed_funds.iter() {
+        let target_allocation = match get_target_allocation(e, &asset_allocation.asset) {
+            Some(target_allocation) => target_allocation,
+            None => continue,
+        };
+        let total_amount = asset_allocation.total_amount;
+        if total_amount <= 0 {
+            continue;
+        }
+        let tolerance = bps_of(total_amount, tolerance_bps)?;
+        let idle_target = bps_of(total_amount, target_allocation.idle_bps as i128)?;
+
+        let mut available_idle = asset_allocation.idle_amount;
+        let mut pending_investments: Vec<(Address, i128)> = Vec::new(e);
+        for strategy_allocation in asset_allocation.strategy_allocations.iter() {
+            let weight_bps = target_allocation
+                .strategy_targets
+                .iter()
+                .find(|target| target.strategy_address == strategy_allocation.strategy_address)
+                .map(|target| target.weight_bps)
+                .unwrap_or(0);
+            let strategy_target = bps_of(total_amount, weight_bps as i128)?;
+            let deviation = strategy_allocation
+                .amount
+                .checked_sub(strategy_target)
+                .ok_or(ContractError::Underflow)?;
+
+            if deviation > tolerance {
+                instructions.push_back(Instruction::Unwind(strategy_allocation.strategy_address.clone(), deviation));
+                available_idle = available_idle.checked_add(deviation).ok_or(ContractError::Overflow)?;
+            } else if -deviation > tolerance && !strategy_allocation.paused {
+                pending_investments.push_back((strategy_allocation.strategy_address.clone(), -deviation));
+            }
+        }
+
+        // Only invest what exceeds the idle buffer of the asset
+        let mut investable = available_idle.checked_sub(idle_target).ok_or(ContractError::Underflow)?;
+        for (strategy_address, amount) in pending_investments.iter() {
+            let amount = amount.min(investable);
+            if amount <= 0 {
+                break;
+            }
+            instructions.push_back(Instruction::Invest(strategy_address, amount));
+            investable = investable.checked_sub(amount).ok_or(ContractError::Underflow)?;
+        }
+    }
+
+    if instructions.len() > max_instructions {
+        instructions = instructions.slice(0..max_instructions);
+    }
+    Ok(instructions)
+}
+
+fn bps_of(amount: i128, bps: i128) -> Result<i128, ContractError> {
+    amount
+        .checked_mul(bps)
+        .ok_or(ContractError::ArithmeticError)?
+        .checked_div(SCALAR_BPS)
+        .ok_or(ContractError::ArithmeticError)
+}
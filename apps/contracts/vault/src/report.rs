@@ -1,20 +1,36 @@
 use soroban_sdk::{
-    contracttype, panic_with_error, token::TokenClient, Address, Env
+    contracttype, panic_with_error, token::TokenClient, Address, Env, Vec
 };
 use crate::{
     access::AccessControl,
-    constants::SCALAR_BPS,
+    constants::{SCALAR_BPS, SECONDS_PER_YEAR},
+    events,
+    funds::{fetch_idle_funds_for_asset, fetch_invested_funds_for_asset},
+    models::{FeeDistributionMode, FeeReceiver},
     storage::{
+        get_assets,
         get_defindex_protocol_fee_rate,
         get_defindex_protocol_fee_receiver,
+        get_last_management_fee_accrual,
+        get_management_fee,
         get_report,
+        get_entry_fee,
+        get_exit_fee,
+        get_fee_distribution_mode,
+        get_fee_receivers,
+        set_last_management_fee_accrual,
         set_report,
         get_vault_fee
     },
     strategies::unwind_from_strategy,
+    token::{internal_mint, internal_transfer, VaultToken},
     ContractError
 };
 
+// Performance tracking of a strategy.
+// `gains_or_losses` is the profit or loss since fees were last locked. Losses are carried forward as a negative
+// value, so it also acts as the strategy's high-water mark: performance fees are only locked once previous losses
+// have been recovered and the strategy makes new profit above its previous peak.
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Report {
@@ -35,7 +51,8 @@ impl Report {
     /// 
     /// By adjusting the `fee_rate`, the manager can influence the reported APY of a strategy by locking
     /// a fee when the APY exceeds a threshold, thus reducing the displayed gains. The calculated fee is 
-    /// added to `locked_fee`, and the `gains_or_losses` are reset to zero.
+    /// added to `locked_fee`, and the `gains_or_losses` are reset to zero. Nothing is locked while
+    /// `gains_or_losses` is negative, i.e. while the strategy is below its high-water mark.
     /// 
     /// # Arguments
     /// * `fee_rate` - The fee rate (as a u32, in basis points) to apply to the current gains or losses.
@@ -98,8 +115,9 @@ impl Report {
     /// Updates the current balance, calculates the gains or losses, and updates the total 
     /// accumulated gains or losses for the contract. The previous balance is updated for the next report.
     ///
-    /// If this is the first report (i.e., `prev_balance` is 0), the current balance is used as the 
-    /// reference for the previous balance and no gains or losses are recorded.
+    /// If this is the first report (i.e., the report is still empty), the current balance is used as the 
+    /// reference for the previous balance and no gains or losses are recorded. A strategy that lost its whole
+    /// balance keeps its losses, so a later recovery is reported as gains that first offset those losses.
     ///
     /// # Arguments
     /// * `current_balance` - The current balance to compare against the previous balance to calculate the gains or losses.
@@ -113,8 +131,8 @@ impl Report {
     /// If `prev_balance = 1000` and `current_balance = 1200`, the `current_gains_or_losses` will be 200, 
     /// and the `gains_or_losses` will be updated accordingly. The `prev_balance` will also be updated to `1200`.
     pub fn report(&mut self, current_balance: i128) -> Result<(), ContractError> {
-        // Use current balance as previous balance if this is the first report (prev_balance is 0)
-        let prev_balance = if self.prev_balance == 0 {
+        // Use current balance as previous balance if this is the first report (nothing tracked yet)
+        let prev_balance = if self.prev_balance == 0 && self.gains_or_losses == 0 && self.locked_fee == 0 {
             current_balance
         } else {
             self.prev_balance
@@ -186,21 +204,94 @@ pub fn update_report_and_lock_fees(
     Ok(report)
 }
 
+/// Splits a fee between the vault and the DeFindex protocol receiver.
+///
+/// The DeFindex protocol receives `defindex_protocol_fee_rate` basis points of `fee` and the vault keeps the rest,
+/// which is paid out to its fee receivers as in `split_vault_fee`. Returns `(vault_fee, defindex_fee)`.
+pub fn split_fee(e: &Env, fee: i128) -> Result<(i128, i128), ContractError> {
+    let defindex_fee = fee
+        .checked_mul(get_defindex_protocol_fee_rate(e) as i128)
+        .ok_or(ContractError::Overflow)?
+        .checked_div(SCALAR_BPS)
+        .ok_or(ContractError::ArithmeticError)?;
+    let vault_fee = fee.checked_sub(defindex_fee).ok_or(ContractError::Underflow)?;
+    Ok((vault_fee, defindex_fee))
+}
+
+/// Splits the vault's part of a fee between the registered fee receivers, proportionally to their weights.
+///
+/// If no fee receivers are registered, the vault fee receiver gets the whole `vault_fee`. Otherwise the last
+/// receiver also gets the rounding remainder, so that the returned amounts always add up to `vault_fee`.
+pub fn split_vault_fee(e: &Env, access_control: &AccessControl, vault_fee: i128) -> Result<Vec<(Address, i128)>, ContractError> {
+    let fee_receivers = get_fee_receivers(e);
+    if fee_receivers.is_empty() {
+        return Ok(Vec::from_array(e, [(access_control.get_fee_receiver()?, vault_fee)]));
+    }
+
+    let mut amounts = Vec::new(e);
+    let mut remaining_fee = vault_fee;
+    for (index, FeeReceiver { address, bps }) in fee_receivers.iter().enumerate() {
+        let amount = if index as u32 == fee_receivers.len() - 1 {
+            remaining_fee
+        } else {
+            vault_fee
+                .checked_mul(bps as i128)
+                .ok_or(ContractError::Overflow)?
+                .checked_div(SCALAR_BPS)
+                .ok_or(ContractError::ArithmeticError)?
+        };
+        remaining_fee = remaining_fee.checked_sub(amount).ok_or(ContractError::Underflow)?;
+        amounts.push_back((address, amount));
+    }
+    Ok(amounts)
+}
+
+/// Validates that the fee receivers' weights add up to `SCALAR_BPS`. An empty list is valid.
+pub fn validate_fee_receivers(fee_receivers: &Vec<FeeReceiver>) -> Result<(), ContractError> {
+    if fee_receivers.is_empty() {
+        return Ok(());
+    }
+    let mut total_bps: i128 = 0;
+    for fee_receiver in fee_receivers.iter() {
+        if fee_receiver.bps == 0 {
+            return Err(ContractError::InvalidFeeBps);
+        }
+        total_bps = total_bps.checked_add(fee_receiver.bps as i128).ok_or(ContractError::Overflow)?;
+    }
+    if total_bps != SCALAR_BPS {
+        return Err(ContractError::InvalidFeeBps);
+    }
+    Ok(())
+}
+
 pub fn distribute_strategy_fees(e: &Env, strategy_address: &Address, access_control: &AccessControl, asset: &Address) -> Result<i128, ContractError> {
     let mut report = get_report(e, strategy_address);
     
-    let defindex_fee = get_defindex_protocol_fee_rate(&e);
     let defindex_protocol_receiver = get_defindex_protocol_fee_receiver(&e)?;
-    let vault_fee_receiver = access_control.get_fee_receiver()?;
 
     let fees_to_distribute = report.locked_fee;
 
+    if fees_to_distribute > 0 && get_fee_distribution_mode(e) == FeeDistributionMode::Shares {
+        let fee_shares = calculate_shares_for_locked_fee(e, asset, fees_to_distribute)?;
+        if fee_shares > 0 {
+            let (vault_fee_shares, defindex_fee_shares) = split_fee(e, fee_shares)?;
+            for (fee_receiver, shares) in split_vault_fee(e, access_control, vault_fee_shares)?.iter() {
+                if shares > 0 {
+                    internal_mint(e.clone(), fee_receiver, shares);
+                }
+            }
+            internal_mint(e.clone(), defindex_protocol_receiver, defindex_fee_shares);
+
+            // The fees stay invested and become part of the managed funds
+            report.locked_fee = 0;
+            set_report(e, strategy_address, &report);
+            return Ok(fees_to_distribute);
+        }
+    }
+
     if fees_to_distribute > 0 {
         // Calculate shares for each receiver based on their fee proportion
-        let numerator = fees_to_distribute.checked_mul(defindex_fee as i128).unwrap();
-        let defindex_fee_amount = numerator.checked_div(SCALAR_BPS).unwrap();
-
-        let vault_fee_amount = fees_to_distribute.checked_sub(defindex_fee_amount).ok_or(ContractError::Underflow)?;
+        let (vault_fee_amount, defindex_fee_amount) = split_fee(e, fees_to_distribute)?;
 
         let remaining_balance = unwind_from_strategy(
             &e,
@@ -211,7 +302,11 @@ pub fn distribute_strategy_fees(e: &Env, strategy_address: &Address, access_cont
 
         // Transfer fees to the respective receivers
         let asset_client = TokenClient::new(&e, &asset);
-        asset_client.transfer( &e.current_contract_address(), &vault_fee_receiver, &vault_fee_amount);
+        for (fee_receiver, amount) in split_vault_fee(e, access_control, vault_fee_amount)?.iter() {
+            if amount > 0 {
+                asset_client.transfer(&e.current_contract_address(), &fee_receiver, &amount);
+            }
+        }
         asset_client.transfer( &e.current_contract_address(), &defindex_protocol_receiver, &defindex_fee_amount);
 
         report.prev_balance = remaining_balance;
@@ -221,4 +316,199 @@ pub fn distribute_strategy_fees(e: &Env, strategy_address: &Address, access_cont
     }
 
     Ok(fees_to_distribute)
-}
\ No newline at end of file
+}
+
+/// Returns the vault shares worth `locked_fee` of `asset`, so that minting them leaves the share price unchanged
+/// once the locked fee is counted as managed funds.
+///
+/// Returns zero if the vault has no shares or no funds of the asset, in which case the fee is paid in assets.
+fn calculate_shares_for_locked_fee(e: &Env, asset: &Address, locked_fee: i128) -> Result<i128, ContractError> {
+    let total_supply = VaultToken::total_supply(e.clone());
+    let asset_strategy_set = get_assets(e)?
+        .iter()
+        .find(|asset_strategy_set| &asset_strategy_set.address == asset)
+        .ok_or(ContractError::AssetNotFound)?;
+    let (invested_amount, _) = fetch_invested_funds_for_asset(e, &asset_strategy_set, false)?;
+    let total_amount = fetch_idle_funds_for_asset(e, asset)
+        .checked_add(invested_amount)
+        .ok_or(ContractError::Overflow)?;
+    if total_supply == 0 || total_amount == 0 {
+        return Ok(0);
+    }
+
+    locked_fee
+        .checked_mul(total_supply)
+        .ok_or(ContractError::Overflow)?
+        .checked_div(total_amount)
+        .ok_or(ContractError::ArithmeticError)
+}
+
+/// Locks the fees of every strategy at the current vault fee rate.
+/// Returns the report of every strategy, in asset and strategy order.
+pub fn lock_all_fees(e: &Env) -> Result<Vec<Report>, ContractError> {
+    let current_vault_fee = get_vault_fee(e);
+
+    let assets = get_assets(e)?;
+    let mut reports: Vec<Report> = Vec::new(e);
+
+    // Loop through each asset and its strategies to lock the fees
+    for asset in assets.iter() {
+        for strategy in asset.strategies.iter() {
+            let mut report = get_report(e, &strategy.address);
+            if report.gains_or_losses > 0 {
+                report.lock_fee(current_vault_fee)?;
+                set_report(e, &strategy.address, &report);
+            }
+            reports.push_back(report);
+        }
+    }
+
+    Ok(reports)
+}
+
+/// Accrues the management fee for the time elapsed since the last accrual.
+///
+/// The fee is charged on the total managed funds by minting new vault shares, so that after minting the fee
+/// receivers hold `management_fee * elapsed / SECONDS_PER_YEAR` of the vault. The minted shares are split between
+/// the DeFindex protocol receiver, at the protocol fee rate, and the vault fee receiver. The first call only
+/// starts the accrual period.
+///
+/// # Returns
+/// * `Result<i128, ContractError>` - The total amount of shares minted.
+pub fn accrue_management_fee(e: &Env) -> Result<i128, ContractError> {
+    let now = e.ledger().timestamp();
+    let last_accrual = get_last_management_fee_accrual(e);
+    set_last_management_fee_accrual(e, &now);
+
+    let elapsed = match last_accrual {
+        Some(last_accrual) => now.saturating_sub(last_accrual),
+        None => return Ok(0),
+    };
+    let shares_to_mint = calculate_management_fee_shares(e, elapsed)?;
+    if shares_to_mint == 0 {
+        return Ok(0);
+    }
+
+    let (vault_fee_shares, defindex_fee_shares) = split_fee(e, shares_to_mint)?;
+
+    for (fee_receiver, shares) in split_vault_fee(e, &AccessControl::new(e), vault_fee_shares)?.iter() {
+        if shares > 0 {
+            internal_mint(e.clone(), fee_receiver, shares);
+        }
+    }
+    if defindex_fee_shares > 0 {
+        internal_mint(e.clone(), get_defindex_protocol_fee_receiver(e)?, defindex_fee_shares);
+    }
+
+    events::emit_management_fee_accrued_event(e, vault_fee_shares, defindex_fee_shares, elapsed);
+    Ok(shares_to_mint)
+}
+
+/// Returns the management fee shares the next accrual would mint, so that previews match the next deposit or
+/// withdrawal.
+pub fn get_pending_management_fee_shares(e: &Env) -> Result<i128, ContractError> {
+    match get_last_management_fee_accrual(e) {
+        Some(last_accrual) => {
+            calculate_management_fee_shares(e, e.ledger().timestamp().saturating_sub(last_accrual))
+        }
+        None => Ok(0),
+    }
+}
+
+/// Returns the management fee shares to mint for `elapsed` seconds. Periods longer than a year are accrued one
+/// year at a time, each year's fee being charged on the shares minted for the previous ones.
+fn calculate_management_fee_shares(e: &Env, elapsed: u64) -> Result<i128, ContractError> {
+    let fee_bps = get_management_fee(e);
+    let mut total_supply = VaultToken::total_supply(e.clone());
+    if elapsed == 0 || fee_bps == 0 || total_supply == 0 {
+        return Ok(0);
+    }
+
+    let mut remaining = elapsed;
+    let mut shares_to_mint = 0i128;
+    while remaining > 0 {
+        let period = remaining.min(SECONDS_PER_YEAR);
+        remaining -= period;
+
+        // shares = total_supply * fee / (1 - fee), with fee = fee_bps * period / (SCALAR_BPS * SECONDS_PER_YEAR)
+        let fee_numerator = (fee_bps as i128)
+            .checked_mul(period as i128)
+            .ok_or(ContractError::Overflow)?;
+        let fee_denominator = SCALAR_BPS
+            .checked_mul(SECONDS_PER_YEAR as i128)
+            .ok_or(ContractError::Overflow)?
+            .checked_sub(fee_numerator)
+            .ok_or(ContractError::Underflow)?;
+        let period_shares = total_supply
+            .checked_mul(fee_numerator)
+            .ok_or(ContractError::Overflow)?
+            .checked_div(fee_denominator)
+            .ok_or(ContractError::ArithmeticError)?;
+
+        total_supply = total_supply.checked_add(period_shares).ok_or(ContractError::Overflow)?;
+        shares_to_mint = shares_to_mint.checked_add(period_shares).ok_or(ContractError::Overflow)?;
+    }
+    Ok(shares_to_mint)
+}
+
+/// Returns the entry fee, in shares, charged on `minted_shares` newly minted to a depositor.
+pub fn calculate_entry_fee_shares(e: &Env, minted_shares: i128) -> Result<i128, ContractError> {
+    calculate_fee_shares(minted_shares, get_entry_fee(e))
+}
+
+/// Returns the exit fee, in shares, charged on `withdraw_shares` given up by a withdrawer.
+///
+/// The fee is part of `withdraw_shares`; only the remaining shares are burned.
+pub fn calculate_exit_fee_shares(e: &Env, withdraw_shares: i128) -> Result<i128, ContractError> {
+    calculate_fee_shares(withdraw_shares, get_exit_fee(e))
+}
+
+/// Returns the exit fee, in shares, to charge on top of `burned_shares` so that the fee is the exit fee rate of
+/// the total shares given up. Rounds up in favour of the fee receivers.
+pub fn calculate_exit_fee_shares_on_top(e: &Env, burned_shares: i128) -> Result<i128, ContractError> {
+    let fee_bps = get_exit_fee(e) as i128;
+    if fee_bps == 0 {
+        return Ok(0);
+    }
+    let numerator = burned_shares
+        .checked_mul(fee_bps)
+        .ok_or(ContractError::Overflow)?;
+    let denominator = SCALAR_BPS
+        .checked_sub(fee_bps)
+        .ok_or(ContractError::Underflow)?;
+    let fee_shares = numerator
+        .checked_div(denominator)
+        .ok_or(ContractError::ArithmeticError)?;
+    if numerator % denominator != 0 {
+        return fee_shares.checked_add(1).ok_or(ContractError::Overflow);
+    }
+    Ok(fee_shares)
+}
+
+fn calculate_fee_shares(shares: i128, fee_bps: u32) -> Result<i128, ContractError> {
+    shares
+        .checked_mul(fee_bps as i128)
+        .ok_or(ContractError::Overflow)?
+        .checked_div(SCALAR_BPS)
+        .ok_or(ContractError::ArithmeticError)
+}
+
+/// Transfers `fee_shares` vault shares from `payer` to the fee receivers, split as in `split_fee` and `split_vault_fee`.
+pub fn pay_fee_shares(e: &Env, payer: &Address, fee_shares: i128) -> Result<(), ContractError> {
+    if fee_shares <= 0 {
+        return Ok(());
+    }
+    let (vault_fee_shares, defindex_fee_shares) = split_fee(e, fee_shares)?;
+
+    for (fee_receiver, shares) in split_vault_fee(e, &AccessControl::new(e), vault_fee_shares)?.iter() {
+        if shares > 0 {
+            internal_transfer(e.clone(), payer.clone(), fee_receiver, shares);
+        }
+    }
+    if defindex_fee_shares > 0 {
+        internal_transfer(e.clone(), payer.clone(), get_defindex_protocol_fee_receiver(e)?, defindex_fee_shares);
+    }
+
+    events::emit_transaction_fee_event(e, payer.clone(), vault_fee_shares, defindex_fee_shares);
+    Ok(())
+}
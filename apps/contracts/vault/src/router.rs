@@ -5,141 +5,267 @@ use soroban_sdk::{
         InvokerContractAuthEntry, 
         SubContractInvocation}, 
 };
-use soroswap_library::{
-    get_amount_in, 
-    get_reserves_with_pair
-};
 use crate::{
+    constants::{SOROSWAP_FEE_DENOMINATOR, SOROSWAP_FEE_NUMERATOR},
     ContractError,
     storage::{get_assets, get_soroswap_router}
 };
 
+fn get_pair_address(e: &Env, token_a: &Address, token_b: &Address) -> Address {
+    e.try_invoke_contract::<Address, InvokeError>(
+        &get_soroswap_router(e),
+        &Symbol::new(&e, "router_pair_for"),
+        vec![e, token_a.to_val(), token_b.to_val()],
+    ).unwrap_or_else(|_| panic_with_error!(e, ContractError::SoroswapRouterError)).unwrap()
+}
+
+/// Returns the reserves of the Soroswap pair for `token_a` and `token_b`, ordered as `(reserve_a, reserve_b)`.
+pub fn get_pair_reserves(
+    e: &Env,
+    token_a: &Address,
+    token_b: &Address,
+) -> Result<(i128, i128), ContractError> {
+    let pair_address = get_pair_address(e, token_a, token_b);
+    // Soroswap pairs return their reserves sorted by token address
+    let (reserve_0, reserve_1) = e.invoke_contract::<(i128, i128)>(
+        &pair_address,
+        &Symbol::new(e, "get_reserves"),
+        vec![e],
+    );
+    if token_a < token_b {
+        Ok((reserve_0, reserve_1))
+    } else {
+        Ok((reserve_1, reserve_0))
+    }
+}
+
+/// Returns the output amount of a Soroswap pair for `amount_in`, after the pair's 0.3% fee, following
+/// `soroswap_library::get_amount_out`.
+fn get_amount_out(amount_in: i128, reserve_in: i128, reserve_out: i128) -> Option<i128> {
+    if amount_in <= 0 || reserve_in <= 0 || reserve_out <= 0 {
+        return None;
+    }
+    let fee = amount_in
+        .checked_mul(SOROSWAP_FEE_DENOMINATOR - SOROSWAP_FEE_NUMERATOR)?
+        .checked_add(SOROSWAP_FEE_DENOMINATOR - 1)?
+        / SOROSWAP_FEE_DENOMINATOR;
+    let amount_in_less_fee = amount_in.checked_sub(fee)?;
+    amount_in_less_fee
+        .checked_mul(reserve_out)?
+        .checked_div(reserve_in.checked_add(amount_in_less_fee)?)
+}
+
+/// Returns the input amount a Soroswap pair needs to output `amount_out`, following
+/// `soroswap_library::get_amount_in`.
+fn get_amount_in(amount_out: i128, reserve_in: i128, reserve_out: i128) -> Option<i128> {
+    if amount_out <= 0 || reserve_in <= 0 || reserve_out <= amount_out {
+        return None;
+    }
+    let numerator = reserve_in.checked_mul(amount_out)?.checked_mul(SOROSWAP_FEE_DENOMINATOR)?;
+    let denominator = reserve_out.checked_sub(amount_out)?.checked_mul(SOROSWAP_FEE_NUMERATOR)?;
+    let amount_in = numerator.checked_div(denominator)?;
+    if numerator % denominator != 0 {
+        amount_in.checked_add(2)
+    } else {
+        amount_in.checked_add(1)
+    }
+}
+
 fn is_supported_asset(e: &Env, token: &Address) -> Result<bool, ContractError> {
     let assets = get_assets(e)?;
     Ok(assets.iter().any(|asset| &asset.address == token))
 }
 
-pub fn internal_swap_exact_tokens_for_tokens(
-    e: &Env,
-    token_in: &Address,
-    token_out: &Address,
-    amount_in: &i128,
-    amount_out_min: &i128,
-    deadline: &u64,
-) -> Result<(), ContractError> {
-    // Check if both tokens are supported by the vault
-    if !is_supported_asset(e, token_in)? || !is_supported_asset(e, token_out)? {
+/// Returns the first and last tokens of a swap `path`, checking that the path has at least one hop and that both
+/// ends are assets of the vault. Intermediate tokens can be any token with a Soroswap pair.
+fn get_path_ends(e: &Env, path: &Vec<Address>, path_error: ContractError) -> Result<(Address, Address), ContractError> {
+    if path.len() < 2 {
+        return Err(path_error);
+    }
+    let token_in = path.first_unchecked();
+    let token_out = path.last_unchecked();
+    if !is_supported_asset(e, &token_in)? || !is_supported_asset(e, &token_out)? {
         return Err(ContractError::UnsupportedAsset);
     }
-    let swap_args: Vec<Val> = vec![
-        e,
-        amount_in.into_val(e),
-        amount_out_min.into_val(e),
-        vec![e, token_in.to_val(), token_out.to_val()].into_val(e), // path
-        e.current_contract_address().to_val(),
-        deadline.into_val(e),
-    ];
-
-    let soroswap_router = get_soroswap_router(e);
+    Ok((token_in, token_out))
+}
 
-    let pair_address: Address = e.try_invoke_contract::<Address, InvokeError>(
-        &soroswap_router,
-        &Symbol::new(&e, "router_pair_for"),
-        vec![e, token_in.to_val(), token_out.to_val()],
-    ).unwrap_or_else(|_| panic_with_error!(e, ContractError::SoroswapRouterError)).unwrap();
+/// Authorizes the router to transfer `amount_in` of the first token of `path` from the vault to the first pair.
+fn authorize_first_hop(e: &Env, path: &Vec<Address>, amount_in: &i128) {
+    let token_in = path.get_unchecked(0);
+    let pair_address = get_pair_address(e, &token_in, &path.get_unchecked(1));
 
     e.authorize_as_current_contract(vec![
         &e,
         InvokerContractAuthEntry::Contract(SubContractInvocation {
             context: ContractContext {
-                contract: token_in.clone(),
-                fn_name: Symbol::new(&e, "transfer"),
+                contract: token_in,
+                fn_name: Symbol::new(e, "transfer"),
                 args: (
                     e.current_contract_address(),
-                    pair_address.clone(),
-                    amount_in.clone(),
+                    pair_address,
+                    *amount_in,
                 )
                     .into_val(e),
             },
             sub_invocations: vec![&e],
         }),
     ]);
+}
 
-    let _result = e.try_invoke_contract::<Vec<i128>, InvokeError>(
-        &get_soroswap_router(e),
-        &Symbol::new(&e, "swap_exact_tokens_for_tokens"),
-        swap_args.clone(),
-    ).unwrap_or_else(|_| {
-        panic_with_error!(e, ContractError::SwapExactInError);
-    });
-    Ok(())
+/// Returns the input amount needed to receive `amount_out` of the last token of `path`, swapping through every
+/// pair of the path.
+fn get_amount_in_for_path(e: &Env, amount_out: &i128, path: &Vec<Address>) -> Result<i128, ContractError> {
+    let mut amount = *amount_out;
+    for i in (1..path.len()).rev() {
+        let (reserve_in, reserve_out) = get_pair_reserves(e, &path.get_unchecked(i - 1), &path.get_unchecked(i))?;
+        amount = get_amount_in(amount, reserve_in, reserve_out)
+            .ok_or(ContractError::SwapExactOutError)?;
+    }
+    Ok(amount)
 }
 
-pub fn internal_swap_tokens_for_exact_tokens(
-    e: &Env,
-    token_in: &Address,
-    token_out: &Address,
-    amount_out: &i128,
-    amount_in_max: &i128,
-    deadline: &u64,
-) -> Result<(), ContractError> {
-    // Check if both tokens are supported by the vault
-    if !is_supported_asset(e, token_in)? || !is_supported_asset(e, token_out)? {
-        return Err(ContractError::UnsupportedAsset);
+/// Returns the output amount received for `amount_in` of the first token of `path`, swapping through every pair
+/// of the path.
+fn get_amount_out_for_path(e: &Env, amount_in: &i128, path: &Vec<Address>) -> Result<i128, ContractError> {
+    let mut amount = *amount_in;
+    for i in 1..path.len() {
+        let (reserve_in, reserve_out) = get_pair_reserves(e, &path.get_unchecked(i - 1), &path.get_unchecked(i))?;
+        amount = get_amount_out(amount, reserve_in, reserve_out)
+            .ok_or(ContractError::SwapExactInError)?;
     }
-    let soroswap_router = get_soroswap_router(e);
+    Ok(amount)
+}
 
-    let pair_address: Address = e.try_invoke_contract::<Address, InvokeError>(
-        &soroswap_router,
-        &Symbol::new(&e, "router_pair_for"),
-        vec![e, token_in.to_val(), token_out.to_val()],
-    ).unwrap_or_else(|_| panic_with_error!(e, ContractError::SoroswapRouterError)).unwrap();
-
-    let (reserve_in, reserve_out) = get_reserves_with_pair(
-        e.clone(),
-        pair_address.clone(),
-        token_in.clone(),
-        token_out.clone(),
-    )?;
-    let amount_in = get_amount_in(amount_out.clone(), reserve_in, reserve_out)
-        .map_err(|_| ContractError::SwapExactOutError)?;
+/// Returns the expected output of swapping `amount_in` along `path`, computed from the current pair reserves.
+///
+/// # Errors
+/// * `ContractError::InsufficientOutputAmount` - If the expected output is lower than `amount_out_min`.
+pub fn simulate_swap_exact_tokens_for_tokens(
+    e: &Env,
+    path: &Vec<Address>,
+    amount_in: &i128,
+    amount_out_min: &i128,
+) -> Result<i128, ContractError> {
+    get_path_ends(e, path, ContractError::SwapExactInError)?;
+    let amount_out = get_amount_out_for_path(e, amount_in, path)?;
+    if amount_out < *amount_out_min {
+        return Err(ContractError::InsufficientOutputAmount);
+    }
+    Ok(amount_out)
+}
 
+/// Returns the input amount needed to receive `amount_out` along `path`, computed from the current pair reserves.
+///
+/// # Errors
+/// * `ContractError::ExcessiveInputAmount` - If the input amount is greater than `amount_in_max`.
+pub fn simulate_swap_tokens_for_exact_tokens(
+    e: &Env,
+    path: &Vec<Address>,
+    amount_out: &i128,
+    amount_in_max: &i128,
+) -> Result<i128, ContractError> {
+    get_path_ends(e, path, ContractError::SwapExactOutError)?;
+    let amount_in = get_amount_in_for_path(e, amount_out, path)?;
     if amount_in > *amount_in_max {
         return Err(ContractError::ExcessiveInputAmount);
     }
+    Ok(amount_in)
+}
 
-    let swap_args: Vec<Val> = vec![
+/// Returns the arguments of a Soroswap router swap: both swap amounts, `path`, the vault as recipient and `deadline`.
+pub fn get_swap_args(e: &Env, path: &Vec<Address>, amounts: (&i128, &i128), deadline: &u64) -> Vec<Val> {
+    vec![
         e,
-        amount_out.into_val(e),
-        amount_in_max.into_val(e),
-        vec![e, token_in.to_val(), token_out.to_val()].into_val(e), // path
+        amounts.0.into_val(e),
+        amounts.1.into_val(e),
+        path.into_val(e),
         e.current_contract_address().to_val(),
         deadline.into_val(e),
-    ];
+    ]
+}
 
-    e.authorize_as_current_contract(vec![
-        &e,
-        InvokerContractAuthEntry::Contract(SubContractInvocation {
-            context: ContractContext {
-                contract: token_in.clone(),
-                fn_name: Symbol::new(&e, "transfer"),
-                args: (
-                    e.current_contract_address(),
-                    pair_address.clone(),
-                    amount_in.clone(),
-                )
-                    .into_val(e),
-            },
-            sub_invocations: vec![&e],
-        }),
-    ]);
+/// Calls `fn_name` on the Soroswap router with the given swap amounts, authorizing the router to transfer
+/// `amount_in` to the first pair of `path`.
+fn invoke_router_swap(
+    e: &Env,
+    fn_name: &str,
+    path: &Vec<Address>,
+    amounts: (&i128, &i128),
+    amount_in: &i128,
+    deadline: &u64,
+    swap_error: ContractError,
+) -> Vec<i128> {
+    let swap_args = get_swap_args(e, path, amounts, deadline);
+
+    authorize_first_hop(e, path, amount_in);
 
-    let _result = e.try_invoke_contract::<Vec<i128>, InvokeError>(
+    e.try_invoke_contract::<Vec<i128>, InvokeError>(
         &get_soroswap_router(e),
-        &Symbol::new(&e, "swap_tokens_for_exact_tokens"),
-        swap_args.clone(),
+        &Symbol::new(&e, fn_name),
+        swap_args,
     ).unwrap_or_else(|_| {
-        panic_with_error!(e, ContractError::SwapExactOutError);
-    }).unwrap();
+        panic_with_error!(e, swap_error);
+    }).unwrap()
+}
+
+pub fn internal_swap_exact_tokens_for_tokens(
+    e: &Env,
+    path: &Vec<Address>,
+    amount_in: &i128,
+    amount_out_min: &i128,
+    deadline: &u64,
+) -> Result<Vec<i128>, ContractError> {
+    // Check if both ends of the path are supported by the vault
+    get_path_ends(e, path, ContractError::SwapExactInError)?;
+    Ok(invoke_router_swap(
+        e,
+        "swap_exact_tokens_for_tokens",
+        path,
+        (amount_in, amount_out_min),
+        amount_in,
+        deadline,
+        ContractError::SwapExactInError,
+    ))
+}
+
+/// Swaps `amount_in` of `token_in` for `token_out` through their Soroswap pair within the current ledger, with no
+/// minimum output, and returns the output amount. Callers must check the output themselves.
+pub fn swap_in_current_ledger(
+    e: &Env,
+    token_in: &Address,
+    token_out: &Address,
+    amount_in: &i128,
+) -> Result<i128, ContractError> {
+    // Soroswap requires the deadline to be strictly in the future; the swap executes in this same ledger.
+    let deadline = e.ledger().timestamp().checked_add(1).ok_or(ContractError::Overflow)?;
+    let amounts = internal_swap_exact_tokens_for_tokens(
+        e,
+        &vec![e, token_in.clone(), token_out.clone()],
+        amount_in,
+        &0,
+        &deadline,
+    )?;
+    Ok(amounts.last().unwrap_or(0))
+}
+
+pub fn internal_swap_tokens_for_exact_tokens(
+    e: &Env,
+    path: &Vec<Address>,
+    amount_out: &i128,
+    amount_in_max: &i128,
+    deadline: &u64,
+) -> Result<(), ContractError> {
+    // Check the ends of the path and the input amount needed before swapping
+    let amount_in = simulate_swap_tokens_for_exact_tokens(e, path, amount_out, amount_in_max)?;
+    invoke_router_swap(
+        e,
+        "swap_tokens_for_exact_tokens",
+        path,
+        (amount_out, amount_in_max),
+        &amount_in,
+        deadline,
+        ContractError::SwapExactOutError,
+    );
     Ok(())
 }
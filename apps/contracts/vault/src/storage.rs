@@ -1,7 +1,9 @@
 use soroban_sdk::{contracttype, Address, Env, Vec, panic_with_error};
 use common::models::AssetStrategySet;
+use crate::constants::{DEFAULT_MANAGER_TIMELOCK, DEFAULT_REBALANCE_TOLERANCE_BPS};
+use crate::models::{AssetTargetAllocation, FeeDistributionMode, FeeReceiver, ManagerTimelock, PauseFlags, Proposal, QueuedManager, WithdrawRequest};
 use crate::report::Report;
-use crate::error::ContractError;
+use crate::error::{ContractError, VaultError};
 
 const DAY_IN_LEDGERS: u32 = 17280;
 
@@ -23,12 +25,34 @@ enum DataKey {
     TotalAssets,           // Total number of tokens
     AssetStrategySet(u32), // AssetStrategySet Addresse by index
     DeFindexProtocolFeeReceiver,
+    FeeReceivers,
     Upgradable,
     VaultFee,
     SoroswapRouter,
     DeFindexProtocolFeeRate,
     Factory,
     Report(Address),
+    TargetAllocation(Address), // AssetTargetAllocation by asset address
+    RebalanceTolerance,
+    IdleReserve(Address), // Idle reserve in bps by asset address
+    WithdrawRequest(u64),
+    NextWithdrawRequestId,
+    WithdrawQueueHead,
+    WithdrawOrder(Address), // Strategy withdraw priority by asset address
+    QueuedManager,
+    ManagerTimelock,
+    Proposal(u64),
+    NextProposalId,
+    PauseFlags,
+    DepositCap(Address), // Maximum total managed amount by asset address
+    UserShareCap,
+    AllowlistEnabled,
+    Allowlisted(Address),
+    ManagementFee,
+    LastManagementFeeAccrual,
+    EntryFee,
+    ExitFee,
+    FeeDistributionMode,
 }
 
 // AssetStrategySet(index)
@@ -77,6 +101,20 @@ pub fn get_defindex_protocol_fee_receiver(e: &Env) -> Result<Address, ContractEr
         .ok_or(ContractError::NotInitialized)
 }
 
+// Vault Fee Receivers
+pub fn set_fee_receivers(e: &Env, fee_receivers: &Vec<FeeReceiver>) {
+    e.storage()
+        .instance()
+        .set(&DataKey::FeeReceivers, fee_receivers);
+}
+
+pub fn get_fee_receivers(e: &Env) -> Vec<FeeReceiver> {
+    e.storage()
+        .instance()
+        .get(&DataKey::FeeReceivers)
+        .unwrap_or(Vec::new(e))
+}
+
 // DeFindex Fee BPS
 pub fn set_defindex_protocol_fee_rate(e: &Env, value: &u32) {
     e.storage()
@@ -147,6 +185,12 @@ pub fn get_report(e: &Env, strategy_address: &Address) -> Report {
     }
 }
 
+pub fn remove_report(e: &Env, strategy_address: &Address) {
+    e.storage()
+        .persistent()
+        .remove(&DataKey::Report(strategy_address.clone()));
+}
+
 /// Updates the previous balance of a strategy's report.
 ///
 /// This function adds the specified value to the existing previous balance
@@ -175,4 +219,341 @@ pub fn set_is_upgradable(e: &Env, value: &bool) {
 
 pub fn is_upgradable(e: &Env) -> bool {
     e.storage().instance().get(&DataKey::Upgradable).unwrap_or(true)
-}
\ No newline at end of file
+}
+
+// Target Allocation
+pub fn set_target_allocation(e: &Env, target: &AssetTargetAllocation) {
+    e.storage()
+        .instance()
+        .set(&DataKey::TargetAllocation(target.asset.clone()), target);
+}
+
+pub fn get_target_allocation(e: &Env, asset: &Address) -> Option<AssetTargetAllocation> {
+    e.storage()
+        .instance()
+        .get(&DataKey::TargetAllocation(asset.clone()))
+}
+
+// Rebalance Tolerance, in basis points of an asset's total managed funds
+pub fn set_rebalance_tolerance(e: &Env, tolerance_bps: &u32) {
+    e.storage()
+        .instance()
+        .set(&DataKey::RebalanceTolerance, tolerance_bps);
+}
+
+pub fn get_rebalance_tolerance(e: &Env) -> u32 {
+    e.storage()
+        .instance()
+        .get(&DataKey::RebalanceTolerance)
+        .unwrap_or(DEFAULT_REBALANCE_TOLERANCE_BPS)
+}
+
+// Idle Reserve, in basis points of an asset's total managed funds
+pub fn set_idle_reserve(e: &Env, asset: &Address, reserve_bps: &u32) {
+    e.storage()
+        .instance()
+        .set(&DataKey::IdleReserve(asset.clone()), reserve_bps);
+}
+
+pub fn get_idle_reserve(e: &Env, asset: &Address) -> u32 {
+    e.storage()
+        .instance()
+        .get(&DataKey::IdleReserve(asset.clone()))
+        .unwrap_or(0)
+}
+
+// Withdrawal Queue
+pub fn set_withdraw_request(e: &Env, request_id: u64, request: &WithdrawRequest) {
+    let key = DataKey::WithdrawRequest(request_id);
+    e.storage()
+        .persistent()
+        .set::<DataKey, WithdrawRequest>(&key, request);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+}
+
+pub fn get_withdraw_request(e: &Env, request_id: u64) -> WithdrawRequest {
+    let key = DataKey::WithdrawRequest(request_id);
+    let request = e
+        .storage()
+        .persistent()
+        .get::<DataKey, WithdrawRequest>(&key)
+        .unwrap_or_else(|| panic_with_error!(e, VaultError::WithdrawRequestNotFound));
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+    request
+}
+
+pub fn has_withdraw_request(e: &Env, request_id: u64) -> bool {
+    e.storage()
+        .persistent()
+        .has(&DataKey::WithdrawRequest(request_id))
+}
+
+pub fn remove_withdraw_request(e: &Env, request_id: u64) {
+    e.storage()
+        .persistent()
+        .remove(&DataKey::WithdrawRequest(request_id));
+}
+
+/// Id that will be assigned to the next withdrawal request.
+pub fn set_next_withdraw_request_id(e: &Env, request_id: &u64) {
+    e.storage()
+        .instance()
+        .set(&DataKey::NextWithdrawRequestId, request_id);
+}
+
+pub fn get_next_withdraw_request_id(e: &Env) -> u64 {
+    e.storage()
+        .instance()
+        .get(&DataKey::NextWithdrawRequestId)
+        .unwrap_or(0)
+}
+
+/// Id of the oldest withdrawal request that has not been claimed yet.
+pub fn set_withdraw_queue_head(e: &Env, request_id: &u64) {
+    e.storage()
+        .instance()
+        .set(&DataKey::WithdrawQueueHead, request_id);
+}
+
+pub fn get_withdraw_queue_head(e: &Env) -> u64 {
+    e.storage()
+        .instance()
+        .get(&DataKey::WithdrawQueueHead)
+        .unwrap_or(0)
+}
+
+// Strategy Withdraw Order
+pub fn set_withdraw_order(e: &Env, asset: &Address, strategies: &Vec<Address>) {
+    e.storage()
+        .instance()
+        .set(&DataKey::WithdrawOrder(asset.clone()), strategies);
+}
+
+pub fn get_withdraw_order(e: &Env, asset: &Address) -> Vec<Address> {
+    e.storage()
+        .instance()
+        .get(&DataKey::WithdrawOrder(asset.clone()))
+        .unwrap_or(Vec::new(e))
+}
+
+// Manager Handover
+pub fn set_queued_manager(e: &Env, queued_manager: &QueuedManager) {
+    e.storage()
+        .instance()
+        .set(&DataKey::QueuedManager, queued_manager);
+}
+
+pub fn get_queued_manager(e: &Env) -> Result<QueuedManager, ContractError> {
+    e.storage()
+        .instance()
+        .get(&DataKey::QueuedManager)
+        .ok_or(ContractError::QueueEmpty)
+}
+
+pub fn remove_queued_manager(e: &Env) {
+    e.storage().instance().remove(&DataKey::QueuedManager);
+}
+
+pub fn set_manager_timelock(e: &Env, timelock: &ManagerTimelock) {
+    e.storage()
+        .instance()
+        .set(&DataKey::ManagerTimelock, timelock);
+}
+
+/// Returns the manager timelock in force, i.e. the previous delay until the last change becomes effective.
+pub fn get_manager_timelock(e: &Env) -> u64 {
+    match e
+        .storage()
+        .instance()
+        .get::<DataKey, ManagerTimelock>(&DataKey::ManagerTimelock)
+    {
+        Some(timelock) if e.ledger().timestamp() >= timelock.effective_at => timelock.delay,
+        Some(timelock) => timelock.previous_delay,
+        None => DEFAULT_MANAGER_TIMELOCK,
+    }
+}
+
+// Governance Proposals
+pub fn set_proposal(e: &Env, proposal_id: u64, proposal: &Proposal) {
+    let key = DataKey::Proposal(proposal_id);
+    e.storage()
+        .persistent()
+        .set::<DataKey, Proposal>(&key, proposal);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+}
+
+pub fn get_proposal(e: &Env, proposal_id: u64) -> Proposal {
+    let key = DataKey::Proposal(proposal_id);
+    let proposal = e
+        .storage()
+        .persistent()
+        .get::<DataKey, Proposal>(&key)
+        .unwrap_or_else(|| panic_with_error!(e, VaultError::ProposalNotFound));
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+    proposal
+}
+
+pub fn remove_proposal(e: &Env, proposal_id: u64) {
+    e.storage()
+        .persistent()
+        .remove(&DataKey::Proposal(proposal_id));
+}
+
+/// Id that will be assigned to the next proposal.
+pub fn set_next_proposal_id(e: &Env, proposal_id: &u64) {
+    e.storage()
+        .instance()
+        .set(&DataKey::NextProposalId, proposal_id);
+}
+
+pub fn get_next_proposal_id(e: &Env) -> u64 {
+    e.storage()
+        .instance()
+        .get(&DataKey::NextProposalId)
+        .unwrap_or(0)
+}
+
+// Vault Pause
+pub fn set_pause_flags(e: &Env, flags: &PauseFlags) {
+    e.storage().instance().set(&DataKey::PauseFlags, flags);
+}
+
+pub fn get_pause_flags(e: &Env) -> PauseFlags {
+    e.storage()
+        .instance()
+        .get(&DataKey::PauseFlags)
+        .unwrap_or(PauseFlags {
+            deposits: false,
+            withdrawals: false,
+            rebalances: false,
+        })
+}
+
+// Deposit Limits
+pub fn set_deposit_cap(e: &Env, asset: &Address, cap: &Option<i128>) {
+    match cap {
+        Some(cap) => e
+            .storage()
+            .instance()
+            .set(&DataKey::DepositCap(asset.clone()), cap),
+        None => e
+            .storage()
+            .instance()
+            .remove(&DataKey::DepositCap(asset.clone())),
+    }
+}
+
+pub fn get_deposit_cap(e: &Env, asset: &Address) -> Option<i128> {
+    e.storage()
+        .instance()
+        .get(&DataKey::DepositCap(asset.clone()))
+}
+
+pub fn set_user_share_cap(e: &Env, cap: &Option<i128>) {
+    match cap {
+        Some(cap) => e.storage().instance().set(&DataKey::UserShareCap, cap),
+        None => e.storage().instance().remove(&DataKey::UserShareCap),
+    }
+}
+
+pub fn get_user_share_cap(e: &Env) -> Option<i128> {
+    e.storage().instance().get(&DataKey::UserShareCap)
+}
+
+// Allowlist
+pub fn set_allowlist_enabled(e: &Env, enabled: bool) {
+    e.storage().instance().set(&DataKey::AllowlistEnabled, &enabled);
+}
+
+pub fn is_allowlist_enabled(e: &Env) -> bool {
+    e.storage()
+        .instance()
+        .get(&DataKey::AllowlistEnabled)
+        .unwrap_or(false)
+}
+
+pub fn set_allowlisted(e: &Env, address: &Address, allowed: bool) {
+    let key = DataKey::Allowlisted(address.clone());
+    if allowed {
+        e.storage().persistent().set(&key, &true);
+        e.storage()
+            .persistent()
+            .extend_ttl(&key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+    } else {
+        e.storage().persistent().remove(&key);
+    }
+}
+
+pub fn is_allowlisted(e: &Env, address: &Address) -> bool {
+    let key = DataKey::Allowlisted(address.clone());
+    let allowed = e.storage().persistent().has(&key);
+    if allowed {
+        e.storage()
+            .persistent()
+            .extend_ttl(&key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+    }
+    allowed
+}
+
+// Management Fee
+pub fn set_management_fee(e: &Env, fee_bps: &u32) {
+    e.storage().instance().set(&DataKey::ManagementFee, fee_bps);
+}
+
+pub fn get_management_fee(e: &Env) -> u32 {
+    e.storage()
+        .instance()
+        .get(&DataKey::ManagementFee)
+        .unwrap_or(0)
+}
+
+pub fn set_last_management_fee_accrual(e: &Env, timestamp: &u64) {
+    e.storage()
+        .instance()
+        .set(&DataKey::LastManagementFeeAccrual, timestamp);
+}
+
+pub fn get_last_management_fee_accrual(e: &Env) -> Option<u64> {
+    e.storage()
+        .instance()
+        .get(&DataKey::LastManagementFeeAccrual)
+}
+
+// Entry and Exit Fees
+pub fn set_entry_fee(e: &Env, fee_bps: &u32) {
+    e.storage().instance().set(&DataKey::EntryFee, fee_bps);
+}
+
+pub fn get_entry_fee(e: &Env) -> u32 {
+    e.storage().instance().get(&DataKey::EntryFee).unwrap_or(0)
+}
+
+pub fn set_exit_fee(e: &Env, fee_bps: &u32) {
+    e.storage().instance().set(&DataKey::ExitFee, fee_bps);
+}
+
+pub fn get_exit_fee(e: &Env) -> u32 {
+    e.storage().instance().get(&DataKey::ExitFee).unwrap_or(0)
+}
+
+// Fee Distribution Mode
+pub fn set_fee_distribution_mode(e: &Env, mode: &FeeDistributionMode) {
+    e.storage()
+        .instance()
+        .set(&DataKey::FeeDistributionMode, mode);
+}
+
+pub fn get_fee_distribution_mode(e: &Env) -> FeeDistributionMode {
+    e.storage()
+        .instance()
+        .get(&DataKey::FeeDistributionMode)
+        .unwrap_or(FeeDistributionMode::Assets)
+}
@@ -3,7 +3,10 @@ use soroban_sdk::auth::{ContractContext, InvokerContractAuthEntry, SubContractIn
 use soroban_sdk::{vec, Address, Env, IntoVal, Symbol, panic_with_error};
 
 use crate::report::Report;
-use crate::storage::{get_report, set_report};
+use crate::storage::{
+    get_report, get_target_allocation, get_withdraw_order, remove_report, set_report,
+    set_target_allocation, set_withdraw_order,
+};
 use crate::{
     storage::{get_asset, get_assets, get_total_assets, set_asset},
     ContractError,
@@ -125,6 +128,99 @@ pub fn unpause_strategy(e: &Env, strategy_address: Address) -> Result<(), Contra
     Err(ContractError::StrategyNotFound)
 }
 
+/// Returns `ContractError::AssetNotFound` if `asset_address` is not managed by the vault.
+pub fn require_vault_asset(e: &Env, asset_address: &Address) -> Result<(), ContractError> {
+    if !get_assets(e)?.iter().any(|asset| &asset.address == asset_address) {
+        return Err(ContractError::AssetNotFound);
+    }
+    Ok(())
+}
+
+/// Checks that a strategy can be added to the given asset: the asset must be managed by the vault, the strategy
+/// must support it and must not be used by any asset of the vault yet.
+pub fn validate_new_strategy(e: &Env, asset_address: &Address, strategy: &Strategy) -> Result<(), ContractError> {
+    require_vault_asset(e, asset_address)?;
+    if get_strategy_asset(e, &strategy.address).is_ok() {
+        return Err(ContractError::DuplicatedStrategy);
+    }
+    if &get_strategy_client(e, strategy.address.clone()).asset() != asset_address {
+        return Err(ContractError::StrategyDoesNotSupportAsset);
+    }
+    Ok(())
+}
+
+/// Adds a strategy to the given asset, once it passes `validate_new_strategy`.
+pub fn add_strategy(e: &Env, asset_address: &Address, strategy: Strategy) -> Result<(), ContractError> {
+    validate_new_strategy(e, asset_address, &strategy)?;
+
+    let total_assets = get_total_assets(e)?;
+    for i in 0..total_assets {
+        let mut asset = get_asset(e, i)?;
+        if &asset.address == asset_address {
+            asset.strategies.push_back(strategy);
+            set_asset(e, i, &asset);
+            return Ok(());
+        }
+    }
+
+    Err(ContractError::AssetNotFound)
+}
+
+/// Removes a strategy from its asset. The vault must not hold any balance in the strategy, and the strategy
+/// must not have locked fees waiting to be distributed. The strategy report is cleared, its target weight goes
+/// back to idle and it is dropped from the asset withdraw order. Returns the address of the asset the strategy
+/// belonged to.
+pub fn remove_strategy(e: &Env, strategy_address: &Address) -> Result<Address, ContractError> {
+    let total_assets = get_total_assets(e)?;
+
+    for i in 0..total_assets {
+        let mut asset = get_asset(e, i)?;
+        let Some(index) = asset
+            .strategies
+            .iter()
+            .position(|strategy| &strategy.address == strategy_address)
+        else {
+            continue;
+        };
+
+        if get_strategy_client(e, strategy_address.clone()).balance(&e.current_contract_address()) != 0
+            || get_report(e, strategy_address).locked_fee != 0
+        {
+            return Err(ContractError::StrategyWithdrawError);
+        }
+
+        asset.strategies.remove(index as u32);
+        set_asset(e, i, &asset);
+        remove_report(e, strategy_address);
+
+        if let Some(mut target) = get_target_allocation(e, &asset.address) {
+            if let Some(index) = target
+                .strategy_targets
+                .iter()
+                .position(|strategy_target| &strategy_target.strategy_address == strategy_address)
+            {
+                let strategy_target = target.strategy_targets.get(index as u32).unwrap();
+                target.idle_bps = target
+                    .idle_bps
+                    .checked_add(strategy_target.weight_bps)
+                    .ok_or(ContractError::Overflow)?;
+                target.strategy_targets.remove(index as u32);
+                set_target_allocation(e, &target);
+            }
+        }
+
+        let mut withdraw_order = get_withdraw_order(e, &asset.address);
+        if let Some(index) = withdraw_order.first_index_of(strategy_address) {
+            withdraw_order.remove(index);
+            set_withdraw_order(e, &asset.address, &withdraw_order);
+        }
+
+        return Ok(asset.address);
+    }
+
+    Err(ContractError::StrategyNotFound)
+}
+
 pub fn unwind_from_strategy(
     e: &Env,
     strategy_address: &Address,
@@ -168,12 +264,17 @@ pub fn invest_in_strategy(
     let strategy_funds = strategy_client.deposit(amount, &e.current_contract_address());
 
     // Reports
-    // Store Strategy invested funds for reports
-    report.gains_or_losses = strategy_funds
+    // Store Strategy invested funds for reports. Gains or losses made since the last report are added to the
+    // tracked ones, so unrecovered losses are not forgotten when investing more.
+    let current_gains_or_losses = strategy_funds
         .checked_sub(report.prev_balance)
         .unwrap_or_else(|| panic_with_error!(e, ContractError::ArithmeticError))
         .checked_sub(*amount)
         .unwrap_or_else(|| panic_with_error!(e, ContractError::ArithmeticError));
+    report.gains_or_losses = report
+        .gains_or_losses
+        .checked_add(current_gains_or_losses)
+        .unwrap_or_else(|| panic_with_error!(e, ContractError::ArithmeticError));
     report.prev_balance = strategy_funds;
     set_report(e, strategy_address, &report);
 
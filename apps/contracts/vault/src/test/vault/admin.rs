@@ -1,11 +1,11 @@
 use soroban_sdk::{
-    testutils::{AuthorizedFunction, AuthorizedInvocation, MockAuth, MockAuthInvoke},
+    testutils::{AuthorizedFunction, AuthorizedInvocation, Ledger, MockAuth, MockAuthInvoke},
     vec as sorobanvec, Address, IntoVal, Map, String, Symbol, Vec
 };
 
 use crate::test::{
     create_defindex_vault, create_strategy_params_token_0, create_strategy_params_token_1,
-    defindex_vault::{AssetStrategySet, ContractError, RolesDataKey}, DeFindexVaultTest,
+    defindex_vault::{AssetStrategySet, ContractError, ProposalAction, RolesDataKey, VaultError}, DeFindexVaultTest,
 };
 
 extern crate std;
@@ -13,7 +13,7 @@ extern crate alloc;
 use alloc::vec;
 
 #[test]
-fn set_new_fee_receiver_by_fee_receiver() {
+fn set_new_fee_receiver_by_proposal() {
     let test = DeFindexVaultTest::setup();
     let strategy_params_token_0 = create_strategy_params_token_0(&test);
     let strategy_params_token_1 = create_strategy_params_token_1(&test);
@@ -55,44 +55,60 @@ fn set_new_fee_receiver_by_fee_receiver() {
     assert_eq!(fee_receiver_role, test.vault_fee_receiver);
 
     let users = DeFindexVaultTest::generate_random_users(&test.env, 1);
-    // Fee Receiver is setting the new fee receiver
-    defindex_contract
+    let action = ProposalAction::SetFeeReceiver(users[0].clone());
+
+    // Manager is proposing the new fee receiver
+    let proposal_id = defindex_contract
         .mock_auths(&[MockAuth {
-            address: &test.vault_fee_receiver,
+            address: &test.manager,
             invoke: &MockAuthInvoke {
                 contract: &defindex_contract.address.clone(),
-                fn_name: "set_fee_receiver",
-                args: (&test.vault_fee_receiver, &users[0]).into_val(&test.env),
+                fn_name: "propose",
+                args: (action.clone(),).into_val(&test.env),
                 sub_invokes: &[],
             },
         }])
-        .set_fee_receiver(&test.vault_fee_receiver, &users[0]);
+        .propose(&action);
 
     let expected_auth = AuthorizedInvocation {
-        // Top-level authorized function is `deploy` with all the arguments.
         function: AuthorizedFunction::Contract((
             defindex_contract.address.clone(),
-            Symbol::new(&test.env, "set_fee_receiver"),
-            (&test.vault_fee_receiver, users[0].clone()).into_val(&test.env),
+            Symbol::new(&test.env, "propose"),
+            (action.clone(),).into_val(&test.env),
         )),
         sub_invocations: vec![],
     };
-    assert_eq!(
-        test.env.auths(),
-        vec![(test.vault_fee_receiver, expected_auth)]
-    );
+    assert_eq!(test.env.auths(), vec![(test.manager.clone(), expected_auth)]);
+
+    let proposal = defindex_contract.get_proposal(&proposal_id);
+    assert_eq!(proposal.action, action);
+    assert_eq!(defindex_contract.get_fee_receiver(), test.vault_fee_receiver);
+
+    test.env.ledger().set_timestamp(proposal.executable_at);
+    defindex_contract
+        .mock_auths(&[MockAuth {
+            address: &test.manager,
+            invoke: &MockAuthInvoke {
+                contract: &defindex_contract.address.clone(),
+                fn_name: "execute_proposal",
+                args: (proposal_id,).into_val(&test.env),
+                sub_invokes: &[],
+            },
+        }])
+        .execute_proposal(&proposal_id);
 
     let new_fee_receiver_role = defindex_contract.get_fee_receiver();
     assert_eq!(new_fee_receiver_role, users[0]);
+
+    let result = defindex_contract.try_get_proposal(&proposal_id);
+    assert_eq!(result, Err(Err(VaultError::ProposalNotFound.into())));
 }
 
 #[test]
-fn set_new_fee_receiver_by_manager() {
+fn veto_fee_receiver_proposal() {
     let test = DeFindexVaultTest::setup();
     let strategy_params_token_0 = create_strategy_params_token_0(&test);
     let strategy_params_token_1 = create_strategy_params_token_1(&test);
-    // let tokens: Vec<Address> = sorobanvec![&test.env, test.token_0.address.clone(), test.token_1.address.clone()];
-    // let ratios: Vec<u32> = sorobanvec![&test.env, 1, 1];
 
     let assets: Vec<AssetStrategySet> = sorobanvec![
         &test.env,
@@ -127,46 +143,27 @@ fn set_new_fee_receiver_by_manager() {
         name_symbol,
         true
     );
-    let fee_receiver_role = defindex_contract.get_fee_receiver();
-    assert_eq!(fee_receiver_role, test.vault_fee_receiver);
+    test.env.mock_all_auths();
 
     let users = DeFindexVaultTest::generate_random_users(&test.env, 1);
-    // Now Manager is setting the new fee receiver
-    defindex_contract
-        .mock_auths(&[MockAuth {
-            address: &test.manager.clone(),
-            invoke: &MockAuthInvoke {
-                contract: &defindex_contract.address.clone(),
-                fn_name: "set_fee_receiver",
-                args: (&test.manager, &users[0]).into_val(&test.env),
-                sub_invokes: &[],
-            },
-        }])
-        .set_fee_receiver(&test.manager, &users[0]);
+    let proposal_id = defindex_contract.propose(&ProposalAction::SetFeeReceiver(users[0].clone()));
+    defindex_contract.veto_proposal(&proposal_id);
 
-    let expected_auth = AuthorizedInvocation {
-        // Top-level authorized function is `deploy` with all the arguments.
-        function: AuthorizedFunction::Contract((
-            defindex_contract.address.clone(),
-            Symbol::new(&test.env, "set_fee_receiver"),
-            (&test.manager, users[0].clone()).into_val(&test.env),
-        )),
-        sub_invocations: vec![],
-    };
-    assert_eq!(test.env.auths(), vec![(test.manager, expected_auth)]);
+    let result = defindex_contract.try_get_proposal(&proposal_id);
+    assert_eq!(result, Err(Err(VaultError::ProposalNotFound.into())));
 
-    let new_fee_receiver_role = defindex_contract.get_fee_receiver();
-    assert_eq!(new_fee_receiver_role, users[0]);
+    test.env.ledger().set_timestamp(test.env.ledger().timestamp() + defindex_contract.get_manager_timelock());
+    let result = defindex_contract.try_execute_proposal(&proposal_id);
+    assert_eq!(result, Err(Err(VaultError::ProposalNotFound.into())));
+    assert_eq!(defindex_contract.get_fee_receiver(), test.vault_fee_receiver);
 }
 
 #[test]
-#[should_panic(expected = "HostError: Error(Contract, #130)")] // Unauthorized
-fn set_new_fee_receiver_by_emergency_manager() {
+#[should_panic(expected = "HostError: Error(Auth, InvalidAction)")] // Unauthorized
+fn propose_new_fee_receiver_by_emergency_manager() {
     let test = DeFindexVaultTest::setup();
     let strategy_params_token_0 = create_strategy_params_token_0(&test);
     let strategy_params_token_1 = create_strategy_params_token_1(&test);
-    // let tokens: Vec<Address> = sorobanvec![&test.env, test.token_0.address.clone(), test.token_1.address.clone()];
-    // let ratios: Vec<u32> = sorobanvec![&test.env, 1, 1];
 
     let assets: Vec<AssetStrategySet> = sorobanvec![
         &test.env,
@@ -201,17 +198,25 @@ fn set_new_fee_receiver_by_emergency_manager() {
         name_symbol,
         true
     );
-    let fee_receiver_role = defindex_contract.get_fee_receiver();
-    assert_eq!(fee_receiver_role, test.vault_fee_receiver);
-
     let users = DeFindexVaultTest::generate_random_users(&test.env, 1);
-    // Now Emergency Manager is setting the new fee receiver
-    defindex_contract.set_fee_receiver(&test.emergency_manager, &users[0]);
+    let action = ProposalAction::SetFeeReceiver(users[0].clone());
+
+    // Emergency Manager can veto proposals but not create them
+    defindex_contract
+        .mock_auths(&[MockAuth {
+            address: &test.emergency_manager,
+            invoke: &MockAuthInvoke {
+                contract: &defindex_contract.address.clone(),
+                fn_name: "propose",
+                args: (action.clone(),).into_val(&test.env),
+                sub_invokes: &[],
+            },
+        }])
+        .propose(&action);
 }
 
 #[test]
-#[should_panic(expected = "HostError: Error(Contract, #130)")] // Unauthorized
-fn set_new_fee_receiver_invalid_sender() {
+fn execute_proposal_before_timelock() {
     let test = DeFindexVaultTest::setup();
     let strategy_params_token_0 = create_strategy_params_token_0(&test);
     let strategy_params_token_1 = create_strategy_params_token_1(&test);
@@ -249,12 +254,78 @@ fn set_new_fee_receiver_invalid_sender() {
         name_symbol,
         true
     );
-    let fee_receiver_role = defindex_contract.get_fee_receiver();
-    assert_eq!(fee_receiver_role, test.vault_fee_receiver);
+    test.env.mock_all_auths();
 
     let users = DeFindexVaultTest::generate_random_users(&test.env, 1);
-    // Trying to set the new fee receiver with an invalid sender
-    defindex_contract.set_fee_receiver(&users[0], &users[0]);
+    let proposal_id = defindex_contract.propose(&ProposalAction::SetFeeReceiver(users[0].clone()));
+    let proposal = defindex_contract.get_proposal(&proposal_id);
+
+    test.env.ledger().set_timestamp(proposal.executable_at - 1);
+    let result = defindex_contract.try_execute_proposal(&proposal_id);
+    assert_eq!(result, Err(Err(VaultError::ProposalNotReady.into())));
+    assert_eq!(defindex_contract.get_fee_receiver(), test.vault_fee_receiver);
+}
+
+#[test]
+fn manager_cannot_skip_the_timelock() {
+    let test = DeFindexVaultTest::setup();
+    let strategy_params_token_0 = create_strategy_params_token_0(&test);
+
+    let assets: Vec<AssetStrategySet> = sorobanvec![
+        &test.env,
+        AssetStrategySet {
+            address: test.token_0.address.clone(),
+            strategies: strategy_params_token_0.clone()
+        }
+    ];
+
+    let mut roles: Map<u32, Address> = Map::new(&test.env);
+    roles.set(RolesDataKey::Manager as u32, test.manager.clone());
+    roles.set(RolesDataKey::EmergencyManager as u32, test.emergency_manager.clone());
+    roles.set(RolesDataKey::VaultFeeReceiver as u32, test.vault_fee_receiver.clone());
+    roles.set(RolesDataKey::RebalanceManager as u32, test.rebalance_manager.clone());
+
+    let mut name_symbol: Map<String, String> = Map::new(&test.env);
+    name_symbol.set(String::from_str(&test.env, "name"), String::from_str(&test.env, "dfToken"));
+    name_symbol.set(String::from_str(&test.env, "symbol"), String::from_str(&test.env, "DFT"));
+
+    let defindex_contract = create_defindex_vault(
+        &test.env,
+        assets,
+        roles,
+        2000u32,
+        test.defindex_protocol_receiver.clone(),
+        2500u32,
+        test.soroswap_router.address.clone(),
+        name_symbol,
+        true
+    );
+    test.env.mock_all_auths();
+
+    // The manager cannot make itself emergency manager right away
+    let proposal_id = defindex_contract.propose(&ProposalAction::SetEmergencyManager(test.manager.clone()));
+    let result = defindex_contract.try_execute_proposal(&proposal_id);
+    assert_eq!(result, Err(Err(VaultError::ProposalNotReady.into())));
+    assert_eq!(defindex_contract.get_emergency_manager(), test.emergency_manager);
+
+    test.env.ledger().set_timestamp(defindex_contract.get_proposal(&proposal_id).executable_at);
+    defindex_contract.execute_proposal(&proposal_id);
+    assert_eq!(defindex_contract.get_emergency_manager(), test.manager);
+
+    // Even as emergency manager, it cannot remove the timelock
+    let result = defindex_contract.try_set_manager_timelock(&0u64);
+    assert_eq!(result, Err(Ok(ContractError::AmountNotAllowed)));
+
+    // A shorter timelock only applies once the current one has elapsed
+    defindex_contract.set_manager_timelock(&86_400u64);
+    let proposal_id = defindex_contract.propose(&ProposalAction::SetVaultFee(9000u32));
+    let proposal = defindex_contract.get_proposal(&proposal_id);
+    assert_eq!(proposal.executable_at, test.env.ledger().timestamp() + 604_800u64);
+
+    test.env.ledger().set_timestamp(test.env.ledger().timestamp() + 86_400u64);
+    let result = defindex_contract.try_execute_proposal(&proposal_id);
+    assert_eq!(result, Err(Err(VaultError::ProposalNotReady.into())));
+    assert_eq!(defindex_contract.get_fees().0, 2000u32);
 }
 
 #[test]
@@ -300,18 +371,34 @@ fn set_new_manager_by_manager() {
 
     let users = DeFindexVaultTest::generate_random_users(&test.env, 2);
     
-    // Manager is setting the new manager
+    // Manager is queueing the new manager
     defindex_contract
         .mock_auths(&[MockAuth {
             address: &test.manager.clone(),
             invoke: &MockAuthInvoke {
                 contract: &defindex_contract.address.clone(),
-                fn_name: "set_manager",
+                fn_name: "queue_manager",
                 args: (&users[0],).into_val(&test.env),
                 sub_invokes: &[],
             },
         }])
-        .set_manager(&users[0]);
+        .queue_manager(&users[0]);
+
+    // The manager does not change until the new manager accepts after the timelock
+    assert_eq!(defindex_contract.get_manager(), test.manager);
+    test.env.ledger().set_timestamp(test.env.ledger().timestamp() + defindex_contract.get_manager_timelock());
+
+    defindex_contract
+        .mock_auths(&[MockAuth {
+            address: &users[0],
+            invoke: &MockAuthInvoke {
+                contract: &defindex_contract.address.clone(),
+                fn_name: "accept_manager",
+                args: ().into_val(&test.env),
+                sub_invokes: &[],
+            },
+        }])
+        .accept_manager();
 
     let new_manager_role = defindex_contract.get_manager();
     assert_eq!(new_manager_role, users[0]);
@@ -361,25 +448,24 @@ fn set_new_manager_by_unauthorized_user() {
     assert_eq!(manager_role, test.manager);
 
     let users = DeFindexVaultTest::generate_random_users(&test.env, 1);
-    defindex_contract.set_manager(&users[0]);
 
-    // Try set_manager from unauthorized user
+    // Try queue_manager from unauthorized user
     defindex_contract
         .mock_auths(&[MockAuth {
             address: &users[0],
             invoke: &MockAuthInvoke {
                 contract: &defindex_contract.address.clone(),
-                fn_name: "set_manager",
+                fn_name: "queue_manager",
                 args: (&users[0],).into_val(&test.env),
                 sub_invokes: &[],
             },
         }])
-        .set_manager(&users[0]);
+        .queue_manager(&users[0]);
     
 }
 
 #[test]
-fn lock_fees_with_new_fee() {
+fn set_vault_fee_by_proposal() {
     let test = DeFindexVaultTest::setup();
     let strategy_params_token_0 = create_strategy_params_token_0(&test);
     let strategy_params_token_1 = create_strategy_params_token_1(&test);
@@ -418,45 +504,21 @@ fn lock_fees_with_new_fee() {
         true
     );
 
-    // Try to set an excessive fee (should fail)
-    let result = defindex_contract
-        .mock_auths(&[MockAuth {
-            address: &test.manager,
-            invoke: &MockAuthInvoke {
-                contract: &defindex_contract.address.clone(),
-                fn_name: "lock_fees",
-                args: (&Some(9500u32),).into_val(&test.env),
-                sub_invokes: &[],
-            },
-        }])
-        .try_lock_fees(&Some(9500u32));
-    
+    test.env.mock_all_auths();
+
+    // Try to propose an excessive fee (should fail)
+    let result = defindex_contract.try_propose(&ProposalAction::SetVaultFee(9500u32));
     assert_eq!(result, Err(Ok(ContractError::MaximumFeeExceeded)));
 
-    // Set a valid fee (should succeed)
-    defindex_contract
-        .mock_auths(&[MockAuth {
-            address: &test.manager,
-            invoke: &MockAuthInvoke {
-                contract: &defindex_contract.address.clone(),
-                fn_name: "lock_fees",
-                args: (&Some(2000u32),).into_val(&test.env),
-                sub_invokes: &[],
-            },
-        }])
-        .lock_fees(&Some(2000u32));
+    // Propose a valid fee (should succeed)
+    let proposal_id = defindex_contract.propose(&ProposalAction::SetVaultFee(1000u32));
+    let (vault_fee, _defindex_fee) = defindex_contract.get_fees();
+    assert_eq!(vault_fee, 2000u32);
 
-    let expected_auth = AuthorizedInvocation {
-        function: AuthorizedFunction::Contract((
-            defindex_contract.address.clone(),
-            Symbol::new(&test.env, "lock_fees"),
-            (&Some(2000u32),).into_val(&test.env),
-        )),
-        sub_invocations: vec![],
-    };
-    assert_eq!(test.env.auths(), vec![(test.manager, expected_auth)]);
+    test.env.ledger().set_timestamp(defindex_contract.get_proposal(&proposal_id).executable_at);
+    defindex_contract.execute_proposal(&proposal_id);
 
     // Verify the new fee was set
     let (vault_fee, _defindex_fee) = defindex_contract.get_fees();
-    assert_eq!(vault_fee, 2000u32);
-}
\ No newline at end of file
+    assert_eq!(vault_fee, 1000u32);
+}
@@ -0,0 +1,140 @@
+use soroban_sdk::{testutils::Address as _, vec as sorobanvec, Address, Map, String, Vec};
+
+use crate::test::defindex_vault::{AssetStrategySet, ContractError, RolesDataKey};
+use crate::test::{create_defindex_vault, create_strategy_params_token_0, DeFindexVaultTest};
+
+#[test]
+fn allowlist_deposits_and_transfers() {
+    let test = DeFindexVaultTest::setup();
+    test.env.mock_all_auths();
+    let strategy_params_token_0 = create_strategy_params_token_0(&test);
+    let allowlist_manager = Address::generate(&test.env);
+
+    let assets: Vec<AssetStrategySet> = sorobanvec![
+        &test.env,
+        AssetStrategySet {
+            address: test.token_0.address.clone(),
+            strategies: strategy_params_token_0.clone()
+        }
+    ];
+
+    let mut roles: Map<u32, Address> = Map::new(&test.env);
+    roles.set(RolesDataKey::Manager as u32, test.manager.clone());
+    roles.set(RolesDataKey::EmergencyManager as u32, test.emergency_manager.clone());
+    roles.set(RolesDataKey::VaultFeeReceiver as u32, test.vault_fee_receiver.clone());
+    roles.set(RolesDataKey::RebalanceManager as u32, test.rebalance_manager.clone());
+    roles.set(RolesDataKey::AllowlistManager as u32, allowlist_manager.clone());
+
+    let mut name_symbol: Map<String, String> = Map::new(&test.env);
+    name_symbol.set(String::from_str(&test.env, "name"), String::from_str(&test.env, "dfToken"));
+    name_symbol.set(String::from_str(&test.env, "symbol"), String::from_str(&test.env, "DFT"));
+
+    let defindex_contract = create_defindex_vault(
+        &test.env,
+        assets,
+        roles,
+        2000u32,
+        test.defindex_protocol_receiver.clone(),
+        2500u32,
+        test.soroswap_router.address.clone(),
+        name_symbol,
+        true
+    );
+
+    let amount = 1_000_000_000i128;
+    let users = DeFindexVaultTest::generate_random_users(&test.env, 2);
+    test.token_0_admin_client.mint(&users[0], &(amount * 2));
+    test.token_0_admin_client.mint(&users[1], &(amount * 2));
+
+    assert_eq!(defindex_contract.get_allowlist_manager(), allowlist_manager);
+    assert!(!defindex_contract.is_allowlist_enabled());
+
+    // Disabled allowlist does not restrict anyone
+    defindex_contract.deposit(&sorobanvec![&test.env, amount], &sorobanvec![&test.env, amount], &users[0], &false);
+
+    defindex_contract.set_allowlist_enabled(&true);
+    assert!(defindex_contract.is_allowlist_enabled());
+
+    let result = defindex_contract.try_deposit(
+        &sorobanvec![&test.env, amount],
+        &sorobanvec![&test.env, amount],
+        &users[1],
+        &false,
+    );
+    assert_eq!(result, Err(Ok(ContractError::NotAllowlisted)));
+    let result = defindex_contract.try_deposit_single_asset(&test.token_0.address, &amount, &0i128, &users[1], &false);
+    assert_eq!(result, Err(Ok(ContractError::NotAllowlisted)));
+    let result = defindex_contract.try_transfer(&users[0], &users[1], &1000i128);
+    assert!(result.is_err());
+    defindex_contract.approve(&users[0], &users[1], &1000i128, &1000u32);
+    let result = defindex_contract.try_transfer_from(&users[1], &users[0], &users[1], &1000i128);
+    assert!(result.is_err());
+
+    // Holders that are not allowlisted can still withdraw
+    defindex_contract.withdraw(&1000i128, &sorobanvec![&test.env, 0i128], &users[0]);
+
+    defindex_contract.set_allowlisted(&users[1], &true);
+    assert!(defindex_contract.is_allowlisted(&users[1]));
+    assert!(!defindex_contract.is_allowlisted(&users[0]));
+    defindex_contract.deposit(&sorobanvec![&test.env, amount], &sorobanvec![&test.env, amount], &users[1], &false);
+    defindex_contract.transfer(&users[0], &users[1], &1000i128);
+    defindex_contract.transfer_from(&users[1], &users[0], &users[1], &1000i128);
+
+    defindex_contract.set_allowlisted(&users[1], &false);
+    assert!(!defindex_contract.is_allowlisted(&users[1]));
+    let result = defindex_contract.try_deposit(
+        &sorobanvec![&test.env, amount],
+        &sorobanvec![&test.env, amount],
+        &users[1],
+        &false,
+    );
+    assert_eq!(result, Err(Ok(ContractError::NotAllowlisted)));
+}
+
+#[test]
+fn allowlist_manager_is_optional() {
+    let test = DeFindexVaultTest::setup();
+    test.env.mock_all_auths();
+    let strategy_params_token_0 = create_strategy_params_token_0(&test);
+
+    let assets: Vec<AssetStrategySet> = sorobanvec![
+        &test.env,
+        AssetStrategySet {
+            address: test.token_0.address.clone(),
+            strategies: strategy_params_token_0.clone()
+        }
+    ];
+
+    let mut roles: Map<u32, Address> = Map::new(&test.env);
+    roles.set(RolesDataKey::Manager as u32, test.manager.clone());
+    roles.set(RolesDataKey::EmergencyManager as u32, test.emergency_manager.clone());
+    roles.set(RolesDataKey::VaultFeeReceiver as u32, test.vault_fee_receiver.clone());
+    roles.set(RolesDataKey::RebalanceManager as u32, test.rebalance_manager.clone());
+
+    let mut name_symbol: Map<String, String> = Map::new(&test.env);
+    name_symbol.set(String::from_str(&test.env, "name"), String::from_str(&test.env, "dfToken"));
+    name_symbol.set(String::from_str(&test.env, "symbol"), String::from_str(&test.env, "DFT"));
+
+    let defindex_contract = create_defindex_vault(
+        &test.env,
+        assets,
+        roles,
+        2000u32,
+        test.defindex_protocol_receiver.clone(),
+        2500u32,
+        test.soroswap_router.address.clone(),
+        name_symbol,
+        true
+    );
+
+    let result = defindex_contract.try_get_allowlist_manager();
+    assert_eq!(result, Err(Ok(ContractError::RoleNotFound)));
+    let result = defindex_contract.try_set_allowlist_enabled(&true);
+    assert!(result.is_err());
+
+    let allowlist_manager = Address::generate(&test.env);
+    defindex_contract.set_allowlist_manager(&allowlist_manager);
+    assert_eq!(defindex_contract.get_allowlist_manager(), allowlist_manager);
+    defindex_contract.set_allowlist_enabled(&true);
+    assert!(defindex_contract.is_allowlist_enabled());
+}
@@ -130,7 +130,7 @@ fn budget() {
             Instruction::Invest(test.strategy_client_token_0.address.clone(), 100),
         ];
     
-        let _ = defindex_contract.rebalance(&test.rebalance_manager, &invest_instructions);
+        let _ = defindex_contract.rebalance(&test.rebalance_manager, &invest_instructions, &None);
         let mem = test.env.cost_estimate().budget().memory_bytes_cost();
         let cpu = test.env.cost_estimate().budget().cpu_instruction_cost();
         std::println!(
@@ -146,7 +146,7 @@ fn budget() {
         &test.env,
         Instruction::Unwind(test.strategy_client_token_0.address.clone(), 100),
     ];
-    let _ = defindex_contract.rebalance(&test.rebalance_manager, &withdraw_instructions);
+    let _ = defindex_contract.rebalance(&test.rebalance_manager, &withdraw_instructions, &None);
     let mem = test.env.cost_estimate().budget().memory_bytes_cost();
     let cpu = test.env.cost_estimate().budget().cpu_instruction_cost();
     std::println!(
@@ -167,7 +167,7 @@ fn budget() {
         Instruction::Invest(test.strategy_client_token_0.address.clone(), 100),
     ];
 
-    let _ = defindex_contract.rebalance(&test.rebalance_manager, &several_instructions_one_strategy);
+    let _ = defindex_contract.rebalance(&test.rebalance_manager, &several_instructions_one_strategy, &None);
     let mem = test.env.cost_estimate().budget().memory_bytes_cost();
     let cpu = test.env.cost_estimate().budget().cpu_instruction_cost();
     std::println!(
@@ -188,7 +188,7 @@ fn budget() {
         Instruction::Invest(test.strategy_client_token_0.address.clone(), 100),
     ];
 
-    let _ = defindex_contract.rebalance(&test.rebalance_manager, &several_instructions_two_strategy);
+    let _ = defindex_contract.rebalance(&test.rebalance_manager, &several_instructions_two_strategy, &None);
     let mem = test.env.cost_estimate().budget().memory_bytes_cost();
     let cpu = test.env.cost_estimate().budget().cpu_instruction_cost();
     std::println!(
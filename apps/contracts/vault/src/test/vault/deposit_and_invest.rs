@@ -267,7 +267,7 @@ fn one_asset_previous_investment_success() {
         ),
     ];
 
-    defindex_contract.rebalance(&test.rebalance_manager, &invest_instructions);
+    defindex_contract.rebalance(&test.rebalance_manager, &invest_instructions, &None);
 
     // Now we should have:
     let mut total_managed_funds_expected = Vec::new(&test.env);
@@ -674,7 +674,7 @@ fn several_assets_wih_previous_investment_success() {
         Instruction::Invest(test.strategy_client_token_1.address.clone(), amount_to_invest_1),
     ];
 
-    defindex_contract.rebalance(&test.rebalance_manager, &invest_instructions);
+    defindex_contract.rebalance(&test.rebalance_manager, &invest_instructions, &None);
 
     // total managed funds
     let mut total_managed_funds_expected = Vec::new(&test.env);
@@ -979,7 +979,7 @@ fn one_asset_several_strategies() {
         Instruction::Invest(test.strategy_client_token_0.address.clone(), amount_to_invest),
     ];
 
-    defindex_contract.rebalance(&test.rebalance_manager, &invest_instructions);
+    defindex_contract.rebalance(&test.rebalance_manager, &invest_instructions, &None);
     let invested_funds = defindex_contract.fetch_total_managed_funds().get(0).unwrap().invested_amount;
     let idle_funds = test.token_0.balance(&defindex_contract.address);
     assert_eq!(invested_funds, (amount_to_invest * 4));
@@ -1111,7 +1111,7 @@ fn deposit_simple_then_deposit_and_invest() {
             amount_to_invest
         ),
     ];
-    defindex_contract.rebalance(&test.rebalance_manager, &invest_instructions);
+    defindex_contract.rebalance(&test.rebalance_manager, &invest_instructions, &None);
 
     let invested_funds = defindex_contract.fetch_total_managed_funds().get(0).unwrap().invested_amount;
     let idle_funds = test.token_0.balance(&defindex_contract.address);
@@ -1337,7 +1337,7 @@ fn several_assets_several_strategies() {
         Instruction::Invest(asset_2_strategy_2.address.clone(), deposit_amount_2 / 2),
     ];
 
-    defindex_contract.rebalance(&test.rebalance_manager, &invest_instructions);
+    defindex_contract.rebalance(&test.rebalance_manager, &invest_instructions, &None);
 
     assert_eq!(test.token_0.balance(&test.strategy_client_token_0.address), deposit_amount_0 / 4 * 2);
     assert_eq!(test.token_0.balance(&asset_0_strategy_1.address), deposit_amount_0 / 4); 
@@ -1584,7 +1584,7 @@ fn several_assets_one_strategy_paused() {
         Instruction::Invest(test.strategy_client_token_1.address.clone(), mint_amount/2),
         Instruction::Invest(asset_2_strategy.address.clone(), mint_amount/2),
     ];
-    defindex_contract.rebalance(&test.rebalance_manager, &invest_instructions);
+    defindex_contract.rebalance(&test.rebalance_manager, &invest_instructions, &None);
 
     // Check that the funds are invested
     let invested_funds_a0 = defindex_contract.fetch_total_managed_funds().get(0).unwrap().invested_amount;
@@ -1770,7 +1770,7 @@ fn several_assets_one_strategy_paused_and_rescue() {
         Instruction::Invest(test.fixed_strategy_client_token_1.address.clone(), ten_lumens),
     ];
 
-    defindex_contract.rebalance(&test.rebalance_manager, &invest_instructions);
+    defindex_contract.rebalance(&test.rebalance_manager, &invest_instructions, &None);
 
     // Check that the funds are invested
     let invested_funds_a0 = defindex_contract.fetch_total_managed_funds().get(0).unwrap().invested_amount;
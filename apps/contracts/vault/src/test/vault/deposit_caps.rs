@@ -0,0 +1,153 @@
+use soroban_sdk::{vec as sorobanvec, Address, Map, String, Vec};
+
+use crate::test::defindex_vault::{AssetStrategySet, ContractError, RolesDataKey, VaultError};
+use crate::test::{create_defindex_vault, create_strategy_params_token_0, DeFindexVaultTest};
+
+#[test]
+fn deposit_cap_per_asset() {
+    let test = DeFindexVaultTest::setup();
+    test.env.mock_all_auths();
+    let strategy_params_token_0 = create_strategy_params_token_0(&test);
+
+    let assets: Vec<AssetStrategySet> = sorobanvec![
+        &test.env,
+        AssetStrategySet {
+            address: test.token_0.address.clone(),
+            strategies: strategy_params_token_0.clone()
+        }
+    ];
+
+    let mut roles: Map<u32, Address> = Map::new(&test.env);
+    roles.set(RolesDataKey::Manager as u32, test.manager.clone());
+    roles.set(RolesDataKey::EmergencyManager as u32, test.emergency_manager.clone());
+    roles.set(RolesDataKey::VaultFeeReceiver as u32, test.vault_fee_receiver.clone());
+    roles.set(RolesDataKey::RebalanceManager as u32, test.rebalance_manager.clone());
+
+    let mut name_symbol: Map<String, String> = Map::new(&test.env);
+    name_symbol.set(String::from_str(&test.env, "name"), String::from_str(&test.env, "dfToken"));
+    name_symbol.set(String::from_str(&test.env, "symbol"), String::from_str(&test.env, "DFT"));
+
+    let defindex_contract = create_defindex_vault(
+        &test.env,
+        assets,
+        roles,
+        2000u32,
+        test.defindex_protocol_receiver.clone(),
+        2500u32,
+        test.soroswap_router.address.clone(),
+        name_symbol,
+        true
+    );
+
+    let amount = 1_000_000_000i128;
+    let users = DeFindexVaultTest::generate_random_users(&test.env, 2);
+    test.token_0_admin_client.mint(&users[0], &(amount * 2));
+    test.token_0_admin_client.mint(&users[1], &(amount * 2));
+
+    assert_eq!(defindex_contract.get_deposit_cap(&test.token_0.address), None);
+    defindex_contract.set_deposit_cap(&test.token_0.address, &Some(amount * 3 / 2));
+    assert_eq!(defindex_contract.get_deposit_cap(&test.token_0.address), Some(amount * 3 / 2));
+
+    defindex_contract.deposit(&sorobanvec![&test.env, amount], &sorobanvec![&test.env, amount], &users[0], &false);
+
+    // Going over the cap fails, invested funds included
+    let result = defindex_contract.try_deposit(
+        &sorobanvec![&test.env, amount],
+        &sorobanvec![&test.env, amount],
+        &users[1],
+        &false,
+    );
+    assert_eq!(result, Err(Err(VaultError::DepositCapExceeded.into())));
+    let result = defindex_contract.try_deposit_single_asset(&test.token_0.address, &amount, &0i128, &users[1], &false);
+    assert_eq!(result, Err(Err(VaultError::DepositCapExceeded.into())));
+    assert_eq!(test.token_0.balance(&users[1]), amount * 2);
+
+    // Up to the cap is fine
+    defindex_contract.deposit(
+        &sorobanvec![&test.env, amount / 2],
+        &sorobanvec![&test.env, amount / 2],
+        &users[1],
+        &false,
+    );
+
+    // Removing the cap allows deposits again
+    defindex_contract.set_deposit_cap(&test.token_0.address, &None);
+    assert_eq!(defindex_contract.get_deposit_cap(&test.token_0.address), None);
+    defindex_contract.deposit(&sorobanvec![&test.env, amount], &sorobanvec![&test.env, amount], &users[1], &false);
+
+    let result = defindex_contract.try_set_deposit_cap(&test.token_1.address, &Some(amount));
+    assert_eq!(result, Err(Ok(ContractError::AssetNotFound)));
+    let result = defindex_contract.try_set_deposit_cap(&test.token_0.address, &Some(-1i128));
+    assert_eq!(result, Err(Ok(ContractError::AmountNotAllowed)));
+}
+
+#[test]
+fn user_share_cap() {
+    let test = DeFindexVaultTest::setup();
+    test.env.mock_all_auths();
+    let strategy_params_token_0 = create_strategy_params_token_0(&test);
+
+    let assets: Vec<AssetStrategySet> = sorobanvec![
+        &test.env,
+        AssetStrategySet {
+            address: test.token_0.address.clone(),
+            strategies: strategy_params_token_0.clone()
+        }
+    ];
+
+    let mut roles: Map<u32, Address> = Map::new(&test.env);
+    roles.set(RolesDataKey::Manager as u32, test.manager.clone());
+    roles.set(RolesDataKey::EmergencyManager as u32, test.emergency_manager.clone());
+    roles.set(RolesDataKey::VaultFeeReceiver as u32, test.vault_fee_receiver.clone());
+    roles.set(RolesDataKey::RebalanceManager as u32, test.rebalance_manager.clone());
+
+    let mut name_symbol: Map<String, String> = Map::new(&test.env);
+    name_symbol.set(String::from_str(&test.env, "name"), String::from_str(&test.env, "dfToken"));
+    name_symbol.set(String::from_str(&test.env, "symbol"), String::from_str(&test.env, "DFT"));
+
+    let defindex_contract = create_defindex_vault(
+        &test.env,
+        assets,
+        roles,
+        2000u32,
+        test.defindex_protocol_receiver.clone(),
+        2500u32,
+        test.soroswap_router.address.clone(),
+        name_symbol,
+        true
+    );
+
+    let amount = 1_000_000_000i128;
+    let users = DeFindexVaultTest::generate_random_users(&test.env, 2);
+    test.token_0_admin_client.mint(&users[0], &(amount * 2));
+    test.token_0_admin_client.mint(&users[1], &(amount * 2));
+
+    assert_eq!(defindex_contract.get_user_share_cap(), None);
+    defindex_contract.set_user_share_cap(&Some(amount));
+    assert_eq!(defindex_contract.get_user_share_cap(), Some(amount));
+
+    defindex_contract.deposit(&sorobanvec![&test.env, amount], &sorobanvec![&test.env, amount], &users[0], &false);
+    defindex_contract.deposit(&sorobanvec![&test.env, amount], &sorobanvec![&test.env, amount], &users[1], &false);
+
+    // The cap is per address
+    let result = defindex_contract.try_deposit(
+        &sorobanvec![&test.env, 2000i128],
+        &sorobanvec![&test.env, 2000i128],
+        &users[1],
+        &false,
+    );
+    assert_eq!(result, Err(Err(VaultError::DepositCapExceeded.into())));
+
+    // The first depositor holds the minimum liquidity less than the cap
+    defindex_contract.deposit(
+        &sorobanvec![&test.env, 1000i128],
+        &sorobanvec![&test.env, 1000i128],
+        &users[0],
+        &false,
+    );
+    assert_eq!(defindex_contract.balance(&users[0]), amount);
+
+    defindex_contract.set_user_share_cap(&None);
+    defindex_contract.deposit(&sorobanvec![&test.env, amount], &sorobanvec![&test.env, amount], &users[1], &false);
+    assert_eq!(defindex_contract.balance(&users[1]), amount * 2);
+}
@@ -0,0 +1,414 @@
+use soroban_sdk::{vec as sorobanvec, Address, Map, String, Vec};
+
+use crate::test::defindex_vault::{AssetStrategySet, ContractError, Instruction, RolesDataKey};
+use crate::test::{
+    create_defindex_vault, create_strategy_params_token_0, create_strategy_params_token_1,
+    DeFindexVaultTest,
+};
+
+#[test]
+fn several_assets_success() {
+    let test = DeFindexVaultTest::setup();
+    test.env.mock_all_auths();
+    let strategy_params_token_0 = create_strategy_params_token_0(&test);
+    let strategy_params_token_1 = create_strategy_params_token_1(&test);
+
+    // initialize with 2 assets
+    let assets: Vec<AssetStrategySet> = sorobanvec![
+        &test.env,
+        AssetStrategySet {
+            address: test.token_0.address.clone(),
+            strategies: strategy_params_token_0.clone()
+        },
+        AssetStrategySet {
+            address: test.token_1.address.clone(),
+            strategies: strategy_params_token_1.clone()
+        }
+    ];
+
+    let mut roles: Map<u32, Address> = Map::new(&test.env);
+    roles.set(RolesDataKey::Manager as u32, test.manager.clone());
+    roles.set(RolesDataKey::EmergencyManager as u32, test.emergency_manager.clone());
+    roles.set(RolesDataKey::VaultFeeReceiver as u32, test.vault_fee_receiver.clone());
+    roles.set(RolesDataKey::RebalanceManager as u32, test.rebalance_manager.clone());
+
+    let mut name_symbol: Map<String, String> = Map::new(&test.env);
+    name_symbol.set(String::from_str(&test.env, "name"), String::from_str(&test.env, "dfToken"));
+    name_symbol.set(String::from_str(&test.env, "symbol"), String::from_str(&test.env, "DFT"));
+
+    let defindex_contract = create_defindex_vault(
+        &test.env,
+        assets,
+        roles,
+        2000u32,
+        test.defindex_protocol_receiver.clone(),
+        2500u32,
+        test.soroswap_router.address.clone(),
+        name_symbol,
+        true
+    );
+
+    // Soroswap pool token_0/token_1 has a 1:4 ratio, first deposit follows the same ratio
+    let amount0 = 1_000_000_000i128;
+    let amount1 = 4_000_000_000i128;
+
+    let users = DeFindexVaultTest::generate_random_users(&test.env, 2);
+
+    test.token_0_admin_client.mint(&users[0], &amount0);
+    test.token_1_admin_client.mint(&users[0], &amount1);
+
+    defindex_contract.deposit(
+        &sorobanvec![&test.env, amount0, amount1],
+        &sorobanvec![&test.env, amount0, amount1],
+        &users[0],
+        &false,
+    );
+    let total_supply_before = defindex_contract.total_supply();
+
+    // Second user only holds token_0
+    let single_amount = 1_000_000_000i128;
+    test.token_0_admin_client.mint(&users[1], &single_amount);
+
+    let (amounts, shares_minted, investments) = defindex_contract.deposit_single_asset(
+        &test.token_0.address,
+        &single_amount,
+        &0i128,
+        &users[1],
+        &false,
+    );
+    assert_eq!(investments, None);
+
+    // Roughly half of the input is swapped, so the user should get close to half of the previous supply
+    assert!(shares_minted > 0);
+    assert!(shares_minted < total_supply_before);
+    assert!(shares_minted > total_supply_before * 49 / 100);
+    assert_eq!(defindex_contract.balance(&users[1]), shares_minted);
+    assert_eq!(defindex_contract.total_supply(), total_supply_before + shares_minted);
+
+    // Deposited amounts follow the vault ratio
+    let amount_deposited_0 = amounts.get(0).unwrap();
+    let amount_deposited_1 = amounts.get(1).unwrap();
+    assert!((amount_deposited_1 - amount_deposited_0 * 4).abs() <= 4);
+
+    assert_eq!(test.token_0.balance(&defindex_contract.address), amount0 + amount_deposited_0);
+    assert_eq!(test.token_1.balance(&defindex_contract.address), amount1 + amount_deposited_1);
+
+    // Only swap rounding leftovers are returned to the user
+    assert!(test.token_0.balance(&users[1]) <= 10);
+    assert!(test.token_1.balance(&users[1]) <= 40);
+}
+
+#[test]
+fn several_assets_and_invest() {
+    let test = DeFindexVaultTest::setup();
+    test.env.mock_all_auths();
+    let strategy_params_token_0 = create_strategy_params_token_0(&test);
+    let strategy_params_token_1 = create_strategy_params_token_1(&test);
+
+    let assets: Vec<AssetStrategySet> = sorobanvec![
+        &test.env,
+        AssetStrategySet {
+            address: test.token_0.address.clone(),
+            strategies: strategy_params_token_0.clone()
+        },
+        AssetStrategySet {
+            address: test.token_1.address.clone(),
+            strategies: strategy_params_token_1.clone()
+        }
+    ];
+
+    let mut roles: Map<u32, Address> = Map::new(&test.env);
+    roles.set(RolesDataKey::Manager as u32, test.manager.clone());
+    roles.set(RolesDataKey::EmergencyManager as u32, test.emergency_manager.clone());
+    roles.set(RolesDataKey::VaultFeeReceiver as u32, test.vault_fee_receiver.clone());
+    roles.set(RolesDataKey::RebalanceManager as u32, test.rebalance_manager.clone());
+
+    let mut name_symbol: Map<String, String> = Map::new(&test.env);
+    name_symbol.set(String::from_str(&test.env, "name"), String::from_str(&test.env, "dfToken"));
+    name_symbol.set(String::from_str(&test.env, "symbol"), String::from_str(&test.env, "DFT"));
+
+    let defindex_contract = create_defindex_vault(
+        &test.env,
+        assets,
+        roles,
+        2000u32,
+        test.defindex_protocol_receiver.clone(),
+        2500u32,
+        test.soroswap_router.address.clone(),
+        name_symbol,
+        true
+    );
+
+    let amount0 = 1_000_000_000i128;
+    let amount1 = 4_000_000_000i128;
+
+    let users = DeFindexVaultTest::generate_random_users(&test.env, 2);
+
+    test.token_0_admin_client.mint(&users[0], &amount0);
+    test.token_1_admin_client.mint(&users[0], &amount1);
+
+    defindex_contract.deposit(
+        &sorobanvec![&test.env, amount0, amount1],
+        &sorobanvec![&test.env, amount0, amount1],
+        &users[0],
+        &false,
+    );
+
+    let invest_instructions = sorobanvec![
+        &test.env,
+        Instruction::Invest(test.strategy_client_token_0.address.clone(), amount0),
+        Instruction::Invest(test.strategy_client_token_1.address.clone(), amount1),
+    ];
+    defindex_contract.rebalance(&test.rebalance_manager, &invest_instructions, &None);
+
+    let single_amount = 500_000_000i128;
+    test.token_1_admin_client.mint(&users[1], &single_amount);
+
+    let (amounts, _shares_minted, investments) = defindex_contract.deposit_single_asset(
+        &test.token_1.address,
+        &single_amount,
+        &0i128,
+        &users[1],
+        &true,
+    );
+    assert!(investments.is_some());
+
+    // Everything deposited went straight into the strategies
+    assert_eq!(test.token_0.balance(&defindex_contract.address), 0);
+    assert_eq!(test.token_1.balance(&defindex_contract.address), 0);
+    assert_eq!(test.strategy_client_token_0.balance(&defindex_contract.address), amount0 + amounts.get(0).unwrap());
+    assert_eq!(test.strategy_client_token_1.balance(&defindex_contract.address), amount1 + amounts.get(1).unwrap());
+}
+
+#[test]
+fn min_shares_not_reached() {
+    let test = DeFindexVaultTest::setup();
+    test.env.mock_all_auths();
+    let strategy_params_token_0 = create_strategy_params_token_0(&test);
+    let strategy_params_token_1 = create_strategy_params_token_1(&test);
+
+    let assets: Vec<AssetStrategySet> = sorobanvec![
+        &test.env,
+        AssetStrategySet {
+            address: test.token_0.address.clone(),
+            strategies: strategy_params_token_0.clone()
+        },
+        AssetStrategySet {
+            address: test.token_1.address.clone(),
+            strategies: strategy_params_token_1.clone()
+        }
+    ];
+
+    let mut roles: Map<u32, Address> = Map::new(&test.env);
+    roles.set(RolesDataKey::Manager as u32, test.manager.clone());
+    roles.set(RolesDataKey::EmergencyManager as u32, test.emergency_manager.clone());
+    roles.set(RolesDataKey::VaultFeeReceiver as u32, test.vault_fee_receiver.clone());
+    roles.set(RolesDataKey::RebalanceManager as u32, test.rebalance_manager.clone());
+
+    let mut name_symbol: Map<String, String> = Map::new(&test.env);
+    name_symbol.set(String::from_str(&test.env, "name"), String::from_str(&test.env, "dfToken"));
+    name_symbol.set(String::from_str(&test.env, "symbol"), String::from_str(&test.env, "DFT"));
+
+    let defindex_contract = create_defindex_vault(
+        &test.env,
+        assets,
+        roles,
+        2000u32,
+        test.defindex_protocol_receiver.clone(),
+        2500u32,
+        test.soroswap_router.address.clone(),
+        name_symbol,
+        true
+    );
+
+    let amount0 = 1_000_000_000i128;
+    let amount1 = 4_000_000_000i128;
+
+    let users = DeFindexVaultTest::generate_random_users(&test.env, 2);
+
+    test.token_0_admin_client.mint(&users[0], &amount0);
+    test.token_1_admin_client.mint(&users[0], &amount1);
+
+    defindex_contract.deposit(
+        &sorobanvec![&test.env, amount0, amount1],
+        &sorobanvec![&test.env, amount0, amount1],
+        &users[0],
+        &false,
+    );
+
+    let single_amount = 1_000_000_000i128;
+    test.token_0_admin_client.mint(&users[1], &single_amount);
+
+    // Swapping half of the input can never mint as many shares as a balanced deposit would
+    let result = defindex_contract.try_deposit_single_asset(
+        &test.token_0.address,
+        &single_amount,
+        &defindex_contract.total_supply(),
+        &users[1],
+        &false,
+    );
+    assert_eq!(result, Err(Ok(ContractError::InsufficientOutputAmount)));
+}
+
+#[test]
+fn unsupported_asset() {
+    let test = DeFindexVaultTest::setup();
+    test.env.mock_all_auths();
+    let strategy_params_token_0 = create_strategy_params_token_0(&test);
+
+    let assets: Vec<AssetStrategySet> = sorobanvec![
+        &test.env,
+        AssetStrategySet {
+            address: test.token_0.address.clone(),
+            strategies: strategy_params_token_0.clone()
+        }
+    ];
+
+    let mut roles: Map<u32, Address> = Map::new(&test.env);
+    roles.set(RolesDataKey::Manager as u32, test.manager.clone());
+    roles.set(RolesDataKey::EmergencyManager as u32, test.emergency_manager.clone());
+    roles.set(RolesDataKey::VaultFeeReceiver as u32, test.vault_fee_receiver.clone());
+    roles.set(RolesDataKey::RebalanceManager as u32, test.rebalance_manager.clone());
+
+    let mut name_symbol: Map<String, String> = Map::new(&test.env);
+    name_symbol.set(String::from_str(&test.env, "name"), String::from_str(&test.env, "dfToken"));
+    name_symbol.set(String::from_str(&test.env, "symbol"), String::from_str(&test.env, "DFT"));
+
+    let defindex_contract = create_defindex_vault(
+        &test.env,
+        assets,
+        roles,
+        2000u32,
+        test.defindex_protocol_receiver.clone(),
+        2500u32,
+        test.soroswap_router.address.clone(),
+        name_symbol,
+        true
+    );
+
+    let amount = 1_000_000_000i128;
+    let users = DeFindexVaultTest::generate_random_users(&test.env, 1);
+    test.token_1_admin_client.mint(&users[0], &amount);
+
+    let result = defindex_contract.try_deposit_single_asset(
+        &test.token_1.address,
+        &amount,
+        &0i128,
+        &users[0],
+        &false,
+    );
+    assert_eq!(result, Err(Ok(ContractError::UnsupportedAsset)));
+}
+
+#[test]
+fn one_asset_vault_matches_deposit() {
+    let test = DeFindexVaultTest::setup();
+    test.env.mock_all_auths();
+    let strategy_params_token_0 = create_strategy_params_token_0(&test);
+
+    let assets: Vec<AssetStrategySet> = sorobanvec![
+        &test.env,
+        AssetStrategySet {
+            address: test.token_0.address.clone(),
+            strategies: strategy_params_token_0.clone()
+        }
+    ];
+
+    let mut roles: Map<u32, Address> = Map::new(&test.env);
+    roles.set(RolesDataKey::Manager as u32, test.manager.clone());
+    roles.set(RolesDataKey::EmergencyManager as u32, test.emergency_manager.clone());
+    roles.set(RolesDataKey::VaultFeeReceiver as u32, test.vault_fee_receiver.clone());
+    roles.set(RolesDataKey::RebalanceManager as u32, test.rebalance_manager.clone());
+
+    let mut name_symbol: Map<String, String> = Map::new(&test.env);
+    name_symbol.set(String::from_str(&test.env, "name"), String::from_str(&test.env, "dfToken"));
+    name_symbol.set(String::from_str(&test.env, "symbol"), String::from_str(&test.env, "DFT"));
+
+    let defindex_contract = create_defindex_vault(
+        &test.env,
+        assets,
+        roles,
+        2000u32,
+        test.defindex_protocol_receiver.clone(),
+        2500u32,
+        test.soroswap_router.address.clone(),
+        name_symbol,
+        true
+    );
+
+    let amount = 123456789i128;
+    let users = DeFindexVaultTest::generate_random_users(&test.env, 1);
+    test.token_0_admin_client.mint(&users[0], &amount);
+
+    let (amounts, shares_minted, _) = defindex_contract.deposit_single_asset(
+        &test.token_0.address,
+        &amount,
+        &0i128,
+        &users[0],
+        &false,
+    );
+
+    assert_eq!(amounts, sorobanvec![&test.env, amount]);
+    assert_eq!(shares_minted, amount);
+    assert_eq!(defindex_contract.balance(&users[0]), amount - 1000);
+    assert_eq!(test.token_0.balance(&users[0]), 0);
+    assert_eq!(test.token_0.balance(&defindex_contract.address), amount);
+}
+
+#[test]
+fn empty_several_assets_vault() {
+    let test = DeFindexVaultTest::setup();
+    test.env.mock_all_auths();
+    let strategy_params_token_0 = create_strategy_params_token_0(&test);
+    let strategy_params_token_1 = create_strategy_params_token_1(&test);
+
+    let assets: Vec<AssetStrategySet> = sorobanvec![
+        &test.env,
+        AssetStrategySet {
+            address: test.token_0.address.clone(),
+            strategies: strategy_params_token_0.clone()
+        },
+        AssetStrategySet {
+            address: test.token_1.address.clone(),
+            strategies: strategy_params_token_1.clone()
+        }
+    ];
+
+    let mut roles: Map<u32, Address> = Map::new(&test.env);
+    roles.set(RolesDataKey::Manager as u32, test.manager.clone());
+    roles.set(RolesDataKey::EmergencyManager as u32, test.emergency_manager.clone());
+    roles.set(RolesDataKey::VaultFeeReceiver as u32, test.vault_fee_receiver.clone());
+    roles.set(RolesDataKey::RebalanceManager as u32, test.rebalance_manager.clone());
+
+    let mut name_symbol: Map<String, String> = Map::new(&test.env);
+    name_symbol.set(String::from_str(&test.env, "name"), String::from_str(&test.env, "dfToken"));
+    name_symbol.set(String::from_str(&test.env, "symbol"), String::from_str(&test.env, "DFT"));
+
+    let defindex_contract = create_defindex_vault(
+        &test.env,
+        assets,
+        roles,
+        2000u32,
+        test.defindex_protocol_receiver.clone(),
+        2500u32,
+        test.soroswap_router.address.clone(),
+        name_symbol,
+        true
+    );
+
+    // Without shares there is no ratio to match, the first deposit must provide every asset
+    let amount = 1_000_000_000i128;
+    let users = DeFindexVaultTest::generate_random_users(&test.env, 1);
+    test.token_0_admin_client.mint(&users[0], &amount);
+
+    let result = defindex_contract.try_deposit_single_asset(
+        &test.token_0.address,
+        &amount,
+        &0i128,
+        &users[0],
+        &false,
+    );
+    assert_eq!(result, Err(Ok(ContractError::InsufficientManagedFunds)));
+    assert_eq!(test.token_0.balance(&users[0]), amount);
+    assert_eq!(test.token_0.balance(&defindex_contract.address), 0);
+}
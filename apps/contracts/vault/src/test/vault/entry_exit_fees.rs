@@ -0,0 +1,98 @@
+use soroban_sdk::{vec as sorobanvec, Address, Map, String, Vec};
+
+use crate::test::defindex_vault::{AssetStrategySet, ContractError, ProposalAction, RolesDataKey};
+use crate::test::{create_defindex_vault, create_strategy_params_token_0, DeFindexVaultTest, EnvTestUtils};
+
+#[test]
+fn entry_and_exit_fees() {
+    let test = DeFindexVaultTest::setup();
+    test.env.mock_all_auths();
+    let strategy_params_token_0 = create_strategy_params_token_0(&test);
+
+    let assets: Vec<AssetStrategySet> = sorobanvec![
+        &test.env,
+        AssetStrategySet {
+            address: test.token_0.address.clone(),
+            strategies: strategy_params_token_0.clone()
+        }
+    ];
+
+    let mut roles: Map<u32, Address> = Map::new(&test.env);
+    roles.set(RolesDataKey::Manager as u32, test.manager.clone());
+    roles.set(RolesDataKey::EmergencyManager as u32, test.emergency_manager.clone());
+    roles.set(RolesDataKey::VaultFeeReceiver as u32, test.vault_fee_receiver.clone());
+    roles.set(RolesDataKey::RebalanceManager as u32, test.rebalance_manager.clone());
+
+    let mut name_symbol: Map<String, String> = Map::new(&test.env);
+    name_symbol.set(String::from_str(&test.env, "name"), String::from_str(&test.env, "dfToken"));
+    name_symbol.set(String::from_str(&test.env, "symbol"), String::from_str(&test.env, "DFT"));
+
+    let defindex_contract = create_defindex_vault(
+        &test.env,
+        assets,
+        roles,
+        2000u32,
+        test.defindex_protocol_receiver.clone(),
+        2500u32,
+        test.soroswap_router.address.clone(),
+        name_symbol,
+        true
+    );
+
+    assert_eq!(defindex_contract.get_entry_exit_fees(), (0u32, 0u32));
+    let result = defindex_contract.try_propose(&ProposalAction::SetEntryFee(501u32));
+    assert_eq!(result, Err(Ok(ContractError::MaximumFeeExceeded)));
+    let result = defindex_contract.try_propose(&ProposalAction::SetExitFee(501u32));
+    assert_eq!(result, Err(Ok(ContractError::MaximumFeeExceeded)));
+
+    let entry_fee_proposal = defindex_contract.propose(&ProposalAction::SetEntryFee(100u32));
+    let exit_fee_proposal = defindex_contract.propose(&ProposalAction::SetExitFee(200u32));
+    test.env.jump_time(defindex_contract.get_manager_timelock());
+    defindex_contract.execute_proposal(&entry_fee_proposal);
+    defindex_contract.execute_proposal(&exit_fee_proposal);
+    assert_eq!(defindex_contract.get_entry_exit_fees(), (100u32, 200u32));
+
+    let amount = 1_000_000_000i128;
+    let users = DeFindexVaultTest::generate_random_users(&test.env, 1);
+    test.token_0_admin_client.mint(&users[0], &amount);
+
+    // Entry fee: 1% of the depositor shares, 25% of it to the DeFindex protocol
+    let (_, preview_shares) = defindex_contract.preview_deposit(&sorobanvec![&test.env, amount]);
+    let (_, shares, _) = defindex_contract.deposit(&sorobanvec![&test.env, amount], &sorobanvec![&test.env, amount], &users[0], &false);
+    let entry_fee_shares = (amount - 1000) / 100;
+    let user_shares = amount - 1000 - entry_fee_shares;
+    assert_eq!(shares, amount - entry_fee_shares);
+    assert_eq!(preview_shares, user_shares);
+    assert_eq!(defindex_contract.balance(&users[0]), user_shares);
+    assert_eq!(defindex_contract.balance(&test.defindex_protocol_receiver), entry_fee_shares / 4);
+    assert_eq!(defindex_contract.balance(&test.vault_fee_receiver), entry_fee_shares - entry_fee_shares / 4);
+    assert_eq!(defindex_contract.total_supply(), amount);
+
+    // Exit fee: 2% of the withdrawn shares is paid to the fee receivers, the rest is burned
+    let withdraw_shares = 100_000_000i128;
+    let exit_fee_shares = withdraw_shares * 200 / 10000;
+    let withdrawn = defindex_contract.withdraw(&withdraw_shares, &sorobanvec![&test.env, 0i128], &users[0]);
+    assert_eq!(withdrawn, sorobanvec![&test.env, withdraw_shares - exit_fee_shares]);
+    assert_eq!(defindex_contract.balance(&users[0]), user_shares - withdraw_shares);
+    assert_eq!(defindex_contract.total_supply(), amount - withdraw_shares + exit_fee_shares);
+    assert_eq!(
+        defindex_contract.balance(&test.defindex_protocol_receiver),
+        entry_fee_shares / 4 + exit_fee_shares / 4
+    );
+
+    // Exact amounts: the exit fee is charged on top of the burned shares
+    let amount_out = 49_000_000i128;
+    let preview_shares = defindex_contract.preview_withdraw(&sorobanvec![&test.env, amount_out]);
+    assert_eq!(preview_shares, 50_000_000i128);
+    let result = defindex_contract.try_withdraw_exact_amounts(&sorobanvec![&test.env, amount_out], &(preview_shares - 1), &users[0]);
+    assert_eq!(result, Err(Ok(ContractError::ExcessiveInputAmount)));
+    let balance_before = defindex_contract.balance(&users[0]);
+    let shares_in = defindex_contract.withdraw_exact_amounts(&sorobanvec![&test.env, amount_out], &preview_shares, &users[0]);
+    assert_eq!(shares_in, preview_shares);
+    assert_eq!(defindex_contract.balance(&users[0]), balance_before - shares_in);
+    assert_eq!(test.token_0.balance(&users[0]), withdraw_shares - exit_fee_shares + amount_out);
+
+    // Withdrawal requests escrow the shares net of the exit fee
+    let request_id = defindex_contract.request_withdraw(&withdraw_shares, &users[0]);
+    assert_eq!(defindex_contract.get_withdraw_request(&request_id).shares, withdraw_shares - exit_fee_shares);
+}
@@ -1,4 +1,4 @@
-use soroban_sdk::testutils::Events;
+use soroban_sdk::testutils::{Events, Ledger};
 use soroban_sdk::{
   symbol_short, 
   vec as sorobanvec, 
@@ -10,19 +10,15 @@ use soroban_sdk::{
   Val, 
   Vec
 };
-use crate::events::{InvestEvent, ManagerChangedEvent, SwapExactInEvent};
+use crate::events::{
+  InvestEvent, ManagerChangedEvent, SwapExactInEvent, UnwindEvent, VaultDepositEvent, VaultWithdrawEvent
+};
 
 use crate::{models, report};
 use crate::test::defindex_vault::{
   AssetStrategySet, 
   Instruction, 
-  Report, 
-  RolesDataKey, 
-  UnwindEvent,
-  VaultDepositEvent,
-  VaultWithdrawEvent,
-  StrategyAllocation,
-  CurrentAssetInvestmentAllocation
+  RolesDataKey
 };
 use crate::test::{
   create_defindex_vault, 
@@ -99,7 +95,7 @@ fn check_rebalance_events(){
     Instruction::Invest(test.strategy_client_token_0.address.clone(), amount_to_invest),
     Instruction::Invest(test.strategy_client_token_1.address.clone(), amount_to_invest),
   ];
-  defindex_contract.rebalance(&test.rebalance_manager, &invest_instructions);
+  defindex_contract.rebalance(&test.rebalance_manager, &invest_instructions, &None);
 
   let instruction_amount_0 = 2_000i128;
   let instructions = sorobanvec![
@@ -110,7 +106,7 @@ fn check_rebalance_events(){
         ),
 
     ];
-  defindex_contract.rebalance(&test.rebalance_manager, &instructions.clone());
+  defindex_contract.rebalance(&test.rebalance_manager, &instructions.clone(), &None);
 
   let events = test.env.events().all();
   std::println!("events: {:?}", events);
@@ -130,7 +126,7 @@ fn check_rebalance_events(){
     (test.strategy_client_token_0.address.clone(), instruction_amount_0, defindex_contract.address.clone()),
   ];
 
-  let expected_report = Report {
+  let expected_report = report::Report {
     prev_balance: amount_to_invest- instruction_amount_0,
     gains_or_losses: 0,
     locked_fee:0
@@ -150,7 +146,7 @@ fn check_rebalance_events(){
         ),
 
     ];
-  defindex_contract.rebalance(&test.rebalance_manager, &instructions.clone());
+  defindex_contract.rebalance(&test.rebalance_manager, &instructions.clone(), &None);
 
   let events = test.env.events().all();
   let rebalance_events: std::vec::Vec<(Address, Vec<Val>, Val)> = 
@@ -191,15 +187,14 @@ fn check_rebalance_events(){
   let instructions = sorobanvec![
     &test.env,
     Instruction::SwapExactIn(
-        test.token_0.address.clone(),
-        test.token_1.address.clone(),
+        sorobanvec![&test.env, test.token_0.address.clone(), test.token_1.address.clone()],
         amount_in,
         amount_in,
         test.env.ledger().timestamp() + 3600u64
     ),
 ];
 
-  defindex_contract.rebalance(&test.rebalance_manager, &instructions);
+  defindex_contract.rebalance(&test.rebalance_manager, &instructions, &None);
 
   let events = test.env.events().all();
   let rebalance_events: std::vec::Vec<(Address, Vec<Val>, Val)> = 
@@ -233,14 +228,13 @@ fn check_rebalance_events(){
   let swap_exact_out_instructions = sorobanvec![
     &test.env,
     Instruction::SwapExactOut(
-      test.token_0.address.clone(),
-      test.token_1.address.clone(),
+      sorobanvec![&test.env, test.token_0.address.clone(), test.token_1.address.clone()],
         amount_out,
         amount_out,
         test.env.ledger().timestamp() + 3600u64
     )];
 
-  defindex_contract.rebalance(&test.rebalance_manager, &swap_exact_out_instructions);
+  defindex_contract.rebalance(&test.rebalance_manager, &swap_exact_out_instructions, &None);
 
   let events = test.env.events().all();
   let rebalance_events: std::vec::Vec<(Address, Vec<Val>, Val)> = 
@@ -315,7 +309,9 @@ fn set_new_manager_by_manager() {
     let users = DeFindexVaultTest::generate_random_users(&test.env, 2);
     test.env.mock_all_auths();
 
-    defindex_contract.set_manager(&users[0]);
+    defindex_contract.queue_manager(&users[0]);
+    test.env.ledger().set_timestamp(test.env.ledger().timestamp() + defindex_contract.get_manager_timelock());
+    defindex_contract.accept_manager();
 
     // Verify the event was emitted correctly
     let events = test.env.events().all().last().unwrap();
@@ -377,14 +373,14 @@ fn deposit_withdraw_event_emitted(){
   assert_eq!(deposit_event.amounts, sorobanvec![&test.env, amount]);
   assert_eq!(deposit_event.df_tokens_minted, amount);
   assert_eq!(deposit_event.total_supply_before, 0);
-  assert_eq!(deposit_event.total_managed_funds_before, sorobanvec![&test.env, CurrentAssetInvestmentAllocation {
+  assert_eq!(deposit_event.total_managed_funds_before, sorobanvec![&test.env, models::CurrentAssetInvestmentAllocation {
     asset: test.token_0.address.clone(),
     idle_amount: 0,
     invested_amount: 0,
     total_amount: 0,
     strategy_allocations: sorobanvec![
       &test.env,
-      StrategyAllocation {
+      models::StrategyAllocation {
         strategy_address: test.strategy_client_token_0.address.clone(),
         amount: 0,
         paused: false,
@@ -407,14 +403,14 @@ fn deposit_withdraw_event_emitted(){
   assert_eq!(deposit_event.amounts, sorobanvec![&test.env, amount]);
   assert_eq!(deposit_event.df_tokens_minted, amount);
   assert_eq!(deposit_event.total_supply_before, amount);
-  assert_eq!(deposit_event.total_managed_funds_before, sorobanvec![&test.env, CurrentAssetInvestmentAllocation {
+  assert_eq!(deposit_event.total_managed_funds_before, sorobanvec![&test.env, models::CurrentAssetInvestmentAllocation {
     asset: test.token_0.address.clone(),
     idle_amount: amount,
     invested_amount: 0,
     total_amount: amount,
     strategy_allocations: sorobanvec![
       &test.env,
-      StrategyAllocation {
+      models::StrategyAllocation {
         strategy_address: test.strategy_client_token_0.address.clone(),
         amount: 0,
         paused: false,
@@ -436,14 +432,14 @@ fn deposit_withdraw_event_emitted(){
   assert_eq!(withdraw_event.df_tokens_burned, amount);
   // it has been deposited amount twice
   assert_eq!(withdraw_event.total_supply_before, 2*amount);
-  assert_eq!(withdraw_event.total_managed_funds_before, sorobanvec![&test.env, CurrentAssetInvestmentAllocation {
+  assert_eq!(withdraw_event.total_managed_funds_before, sorobanvec![&test.env, models::CurrentAssetInvestmentAllocation {
     asset: test.token_0.address.clone(),
     idle_amount: 2*amount,
     invested_amount: 0,
     total_amount: 2*amount,
     strategy_allocations: sorobanvec![
       &test.env,
-      StrategyAllocation {
+      models::StrategyAllocation {
         strategy_address: test.strategy_client_token_0.address.clone(),
         amount: 0,
         paused: false,
@@ -4,7 +4,7 @@ use std::println;
 use soroban_sdk::{ vec as sorobanvec, Address, Map, String, Vec, IntoVal,
 testutils::{MockAuth, MockAuthInvoke, Address as _}, Bytes};
 
-use crate::test::{create_defindex_vault, create_fixed_strategy_params_token_0, create_strategy_params_token_0, defindex_vault::{ AssetStrategySet, ContractError, Instruction, Report, RolesDataKey}, DeFindexVaultTest, EnvTestUtils};
+use crate::test::{create_defindex_vault, create_fixed_strategy_params_token_0, create_strategy_params_token_0, create_strategy_params_token_1, defindex_vault::{ AssetStrategySet, ContractError, FeeDistributionMode, FeeReceiver, Instruction, ProposalAction, Report, RolesDataKey, VaultError}, DeFindexVaultTest, EnvTestUtils};
 use crate::storage;
 
 
@@ -79,7 +79,7 @@ fn rebalance_invest(){
       ),
   ];
 
-  defindex_contract.rebalance(&test.rebalance_manager, &instructions);
+  defindex_contract.rebalance(&test.rebalance_manager, &instructions, &None);
 
   let vault_balance = test.token_0.balance(&defindex_contract.address);
   assert_eq!(vault_balance, instruction_amount_0); 
@@ -100,7 +100,7 @@ fn rebalance_invest(){
       ),
   ];
 
-  defindex_contract.rebalance(&test.rebalance_manager, &instructions);
+  defindex_contract.rebalance(&test.rebalance_manager, &instructions, &None);
   let report = defindex_contract.report().get(0).unwrap().gains_or_losses;
 
   let expected_reward = instruction_amount_0 / 10;
@@ -177,7 +177,7 @@ fn rebalance_unwind(){
       ),
   ];
 
-  defindex_contract.rebalance(&test.rebalance_manager, &instructions);
+  defindex_contract.rebalance(&test.rebalance_manager, &instructions, &None);
 
   let vault_balance = test.token_0.balance(&defindex_contract.address);
   assert_eq!(vault_balance, instruction_amount_0); 
@@ -218,7 +218,7 @@ fn rebalance_unwind(){
   ];
 
   // This rebalance should update report, lock fees and distribute fees
-  defindex_contract.rebalance(&test.rebalance_manager, &instructions);
+  defindex_contract.rebalance(&test.rebalance_manager, &instructions, &None);
   // Get report from storage after rebalance
   let report = test.env.as_contract(&defindex_contract.address, || {
       storage::get_report(&test.env, &test.fixed_strategy_client_token_0.address)
@@ -414,7 +414,7 @@ fn release_negative_or_zero_fees (){
       &test.env,
       Instruction::Invest(test.strategy_client_token_0.address.clone(), amount),
   ];
-  defindex_contract.rebalance(&test.rebalance_manager, &invest_instructions);
+  defindex_contract.rebalance(&test.rebalance_manager, &invest_instructions, &None);
 
 
   // Simulate earning on the strategy
@@ -423,7 +423,7 @@ fn release_negative_or_zero_fees (){
 
   defindex_contract.report();
   // Locking fees
-  let report = defindex_contract.lock_fees(&None).get(0).unwrap();
+  let report = defindex_contract.lock_fees().get(0).unwrap();
   let fees = report.locked_fee;
   assert_eq!(fees, 2_0_000_000i128);
 
@@ -442,4 +442,458 @@ fn release_negative_or_zero_fees (){
     prev_balance: 20_0_000_000i128,
   };
   assert_eq!(release_fees_result, expected_report);
-}
\ No newline at end of file
+}
+
+#[test]
+fn fees_only_on_gains_above_high_water_mark(){
+  let test = DeFindexVaultTest::setup();
+  test.env.mock_all_auths();
+  let strategy_params_token_0 = create_fixed_strategy_params_token_0(&test);
+  let assets: Vec<AssetStrategySet> = sorobanvec![
+      &test.env,
+      AssetStrategySet {
+          address: test.token_0.address.clone(),
+          strategies: strategy_params_token_0.clone()
+      }
+  ];
+
+  let mut roles: Map<u32, Address> = Map::new(&test.env);
+  roles.set(RolesDataKey::Manager as u32, test.manager.clone());
+  roles.set(RolesDataKey::EmergencyManager as u32, test.emergency_manager.clone());
+  roles.set(RolesDataKey::VaultFeeReceiver as u32, test.vault_fee_receiver.clone());
+  roles.set(RolesDataKey::RebalanceManager as u32, test.rebalance_manager.clone());
+
+  let mut name_symbol: Map<String, String> = Map::new(&test.env);
+  name_symbol.set(String::from_str(&test.env, "name"), String::from_str(&test.env, "dfToken"));
+  name_symbol.set(String::from_str(&test.env, "symbol"), String::from_str(&test.env, "DFT"));
+
+  let defindex_contract = create_defindex_vault(
+      &test.env,
+      assets,
+      roles,
+      2000u32,
+      test.defindex_protocol_receiver.clone(),
+      2500u32,
+      test.soroswap_router.address.clone(),
+      name_symbol,
+      true
+  );
+
+  let amount = 10_000_000_000i128;
+  let users = DeFindexVaultTest::generate_random_users(&test.env, 1);
+  test.token_0_admin_client.mint(&users[0], &amount);
+  defindex_contract.deposit(
+      &sorobanvec![&test.env, amount],
+      &sorobanvec![&test.env, amount],
+      &users[0],
+      &false,
+  );
+
+  let strategy_address = test.fixed_strategy_client_token_0.address.clone();
+  let instruction_amount = 5_000_000_000i128;
+  defindex_contract.rebalance(
+      &test.rebalance_manager,
+      &sorobanvec![&test.env, Instruction::Invest(strategy_address.clone(), instruction_amount)],
+      &None,
+  );
+
+  // Simulate a loss: the strategy was worth 50 more at its last report
+  let loss = 500_000_000i128;
+  test.env.as_contract(&defindex_contract.address, || {
+      let mut report = storage::get_report(&test.env, &strategy_address);
+      report.prev_balance = instruction_amount + loss;
+      storage::set_report(&test.env, &strategy_address, &report);
+  });
+  let report = defindex_contract.report().get(0).unwrap();
+  assert_eq!(report.gains_or_losses, -loss);
+
+  // Investing more keeps the unrecovered loss
+  defindex_contract.rebalance(
+      &test.rebalance_manager,
+      &sorobanvec![&test.env, Instruction::Invest(strategy_address.clone(), instruction_amount)],
+      &None,
+  );
+  let report = defindex_contract.report().get(0).unwrap();
+  assert_eq!(report.gains_or_losses, -loss);
+  let report = defindex_contract.lock_fees().get(0).unwrap();
+  assert_eq!(report.locked_fee, 0);
+
+  // Only the profit above the previous peak is charged
+  let balance_before_harvest = test.fixed_strategy_client_token_0.balance(&defindex_contract.address);
+  test.env.jump_time(ONE_DAY_IN_SECONDS*365);
+  test.fixed_strategy_client_token_0.harvest(&defindex_contract.address, &None::<Bytes>);
+  let gains = test.fixed_strategy_client_token_0.balance(&defindex_contract.address) - balance_before_harvest;
+  assert!(gains > loss);
+
+  let report = defindex_contract.report().get(0).unwrap();
+  assert_eq!(report.gains_or_losses, gains - loss);
+  let report = defindex_contract.lock_fees().get(0).unwrap();
+  assert_eq!(report.locked_fee, (gains - loss) * 2000 / 10000);
+  assert_eq!(report.gains_or_losses, 0);
+}
+
+#[test]
+fn rescue_keeps_unrecovered_losses(){
+  let test = DeFindexVaultTest::setup();
+  test.env.mock_all_auths();
+  let strategy_params_token_0 = create_fixed_strategy_params_token_0(&test);
+  let assets: Vec<AssetStrategySet> = sorobanvec![
+      &test.env,
+      AssetStrategySet {
+          address: test.token_0.address.clone(),
+          strategies: strategy_params_token_0.clone()
+      }
+  ];
+
+  let mut roles: Map<u32, Address> = Map::new(&test.env);
+  roles.set(RolesDataKey::Manager as u32, test.manager.clone());
+  roles.set(RolesDataKey::EmergencyManager as u32, test.emergency_manager.clone());
+  roles.set(RolesDataKey::VaultFeeReceiver as u32, test.vault_fee_receiver.clone());
+  roles.set(RolesDataKey::RebalanceManager as u32, test.rebalance_manager.clone());
+
+  let mut name_symbol: Map<String, String> = Map::new(&test.env);
+  name_symbol.set(String::from_str(&test.env, "name"), String::from_str(&test.env, "dfToken"));
+  name_symbol.set(String::from_str(&test.env, "symbol"), String::from_str(&test.env, "DFT"));
+
+  let defindex_contract = create_defindex_vault(
+      &test.env,
+      assets,
+      roles,
+      2000u32,
+      test.defindex_protocol_receiver.clone(),
+      2500u32,
+      test.soroswap_router.address.clone(),
+      name_symbol,
+      true
+  );
+
+  let amount = 10_000_000_000i128;
+  let users = DeFindexVaultTest::generate_random_users(&test.env, 1);
+  test.token_0_admin_client.mint(&users[0], &amount);
+  defindex_contract.deposit(
+      &sorobanvec![&test.env, amount],
+      &sorobanvec![&test.env, amount],
+      &users[0],
+      &false,
+  );
+
+  let strategy_address = test.fixed_strategy_client_token_0.address.clone();
+  let instruction_amount = 5_000_000_000i128;
+  defindex_contract.rebalance(
+      &test.rebalance_manager,
+      &sorobanvec![&test.env, Instruction::Invest(strategy_address.clone(), instruction_amount)],
+      &None,
+  );
+
+  // Simulate a loss: the strategy was worth 10 more at its last report
+  let loss = 100_000_000i128;
+  test.env.as_contract(&defindex_contract.address, || {
+      let mut report = storage::get_report(&test.env, &strategy_address);
+      report.prev_balance = instruction_amount + loss;
+      storage::set_report(&test.env, &strategy_address, &report);
+  });
+
+  // Rescuing empties the strategy but keeps the unrecovered loss
+  defindex_contract.rescue(&strategy_address, &test.emergency_manager);
+  let report = test.env.as_contract(&defindex_contract.address, || storage::get_report(&test.env, &strategy_address));
+  assert_eq!(report, crate::report::Report { prev_balance: 0, gains_or_losses: -loss, locked_fee: 0 });
+
+  defindex_contract.unpause_strategy(&strategy_address, &test.manager);
+  defindex_contract.rebalance(
+      &test.rebalance_manager,
+      &sorobanvec![&test.env, Instruction::Invest(strategy_address.clone(), instruction_amount)],
+      &None,
+  );
+  let report = defindex_contract.report().get(0).unwrap();
+  assert_eq!(report.gains_or_losses, -loss);
+
+  // Only the profit above the peak before the rescue is charged
+  let balance_before_harvest = test.fixed_strategy_client_token_0.balance(&defindex_contract.address);
+  test.env.jump_time(ONE_DAY_IN_SECONDS*365);
+  test.fixed_strategy_client_token_0.harvest(&defindex_contract.address, &None::<Bytes>);
+  let gains = test.fixed_strategy_client_token_0.balance(&defindex_contract.address) - balance_before_harvest;
+  assert!(gains > loss);
+
+  let report = defindex_contract.report().get(0).unwrap();
+  assert_eq!(report.gains_or_losses, gains - loss);
+  let report = defindex_contract.lock_fees().get(0).unwrap();
+  assert_eq!(report.locked_fee, (gains - loss) * 2000 / 10000);
+  assert_eq!(report.gains_or_losses, 0);
+}
+
+#[test]
+fn distribute_fees_in_shares(){
+  let test = DeFindexVaultTest::setup();
+  test.env.mock_all_auths();
+  let strategy_params_token_0 = create_fixed_strategy_params_token_0(&test);
+  let assets: Vec<AssetStrategySet> = sorobanvec![
+      &test.env,
+      AssetStrategySet {
+          address: test.token_0.address.clone(),
+          strategies: strategy_params_token_0.clone()
+      }
+  ];
+
+  let mut roles: Map<u32, Address> = Map::new(&test.env);
+  roles.set(RolesDataKey::Manager as u32, test.manager.clone());
+  roles.set(RolesDataKey::EmergencyManager as u32, test.emergency_manager.clone());
+  roles.set(RolesDataKey::VaultFeeReceiver as u32, test.vault_fee_receiver.clone());
+  roles.set(RolesDataKey::RebalanceManager as u32, test.rebalance_manager.clone());
+
+  let mut name_symbol: Map<String, String> = Map::new(&test.env);
+  name_symbol.set(String::from_str(&test.env, "name"), String::from_str(&test.env, "dfToken"));
+  name_symbol.set(String::from_str(&test.env, "symbol"), String::from_str(&test.env, "DFT"));
+
+  let defindex_contract = create_defindex_vault(
+      &test.env,
+      assets,
+      roles,
+      2000u32,
+      test.defindex_protocol_receiver.clone(),
+      2500u32,
+      test.soroswap_router.address.clone(),
+      name_symbol,
+      true
+  );
+
+  let amount = 10_000_000_000i128;
+  let users = DeFindexVaultTest::generate_random_users(&test.env, 1);
+  test.token_0_admin_client.mint(&users[0], &amount);
+  defindex_contract.deposit(
+      &sorobanvec![&test.env, amount],
+      &sorobanvec![&test.env, amount],
+      &users[0],
+      &false,
+  );
+
+  let strategy_address = test.fixed_strategy_client_token_0.address.clone();
+  defindex_contract.rebalance(
+      &test.rebalance_manager,
+      &sorobanvec![&test.env, Instruction::Invest(strategy_address.clone(), amount)],
+      &None,
+  );
+
+  assert_eq!(defindex_contract.get_fee_distribution_mode(), FeeDistributionMode::Assets);
+  let proposal_id = defindex_contract.propose(&ProposalAction::SetFeeDistributionMode(FeeDistributionMode::Shares));
+  let result = defindex_contract.try_execute_proposal(&proposal_id);
+  assert_eq!(result, Err(Err(VaultError::ProposalNotReady.into())));
+  assert_eq!(defindex_contract.get_fee_distribution_mode(), FeeDistributionMode::Assets);
+  test.env.jump_time(defindex_contract.get_manager_timelock());
+  defindex_contract.execute_proposal(&proposal_id);
+  assert_eq!(defindex_contract.get_fee_distribution_mode(), FeeDistributionMode::Shares);
+
+  test.env.jump_time(ONE_DAY_IN_SECONDS*365);
+  test.fixed_strategy_client_token_0.harvest(&defindex_contract.address, &None::<Bytes>);
+  let strategy_balance = test.fixed_strategy_client_token_0.balance(&defindex_contract.address);
+  let gains = strategy_balance - amount;
+  let locked_fee = gains * 2000 / 10000;
+  defindex_contract.report();
+  let report = defindex_contract.lock_fees().get(0).unwrap();
+  assert_eq!(report.locked_fee, locked_fee);
+
+  let total_supply = defindex_contract.total_supply();
+  let fee_shares = locked_fee * total_supply / (strategy_balance - locked_fee);
+  let defindex_fee_shares = fee_shares * 2500 / 10000;
+
+  defindex_contract.distribute_fees(&test.manager);
+
+  // The fees stay invested and the receivers get vault shares instead
+  assert_eq!(test.fixed_strategy_client_token_0.balance(&defindex_contract.address), strategy_balance);
+  assert_eq!(test.token_0.balance(&test.vault_fee_receiver), 0);
+  assert_eq!(test.token_0.balance(&test.defindex_protocol_receiver), 0);
+  assert_eq!(defindex_contract.balance(&test.vault_fee_receiver), fee_shares - defindex_fee_shares);
+  assert_eq!(defindex_contract.balance(&test.defindex_protocol_receiver), defindex_fee_shares);
+  assert_eq!(defindex_contract.total_supply(), total_supply + fee_shares);
+
+  let report = defindex_contract.report().get(0).unwrap();
+  assert_eq!(report.locked_fee, 0);
+  assert_eq!(report.prev_balance, strategy_balance);
+
+  // Only managers can propose a new mode
+  let users = DeFindexVaultTest::generate_random_users(&test.env, 1);
+  let action = ProposalAction::SetFeeDistributionMode(FeeDistributionMode::Assets);
+  let result = defindex_contract.mock_auths(&[MockAuth {
+      address: &users[0],
+      invoke: &MockAuthInvoke {
+          contract: &defindex_contract.address.clone(),
+          fn_name: "propose",
+          args: (action.clone(),).into_val(&test.env),
+          sub_invokes: &[],
+      },
+  }]).try_propose(&action);
+  assert!(result.is_err());
+}
+
+#[test]
+fn distribute_fees_in_shares_multi_asset(){
+  let test = DeFindexVaultTest::setup();
+  test.env.mock_all_auths();
+  let assets: Vec<AssetStrategySet> = sorobanvec![
+      &test.env,
+      AssetStrategySet {
+          address: test.token_0.address.clone(),
+          strategies: create_fixed_strategy_params_token_0(&test)
+      },
+      AssetStrategySet {
+          address: test.token_1.address.clone(),
+          strategies: create_strategy_params_token_1(&test)
+      }
+  ];
+
+  let mut roles: Map<u32, Address> = Map::new(&test.env);
+  roles.set(RolesDataKey::Manager as u32, test.manager.clone());
+  roles.set(RolesDataKey::EmergencyManager as u32, test.emergency_manager.clone());
+  roles.set(RolesDataKey::VaultFeeReceiver as u32, test.vault_fee_receiver.clone());
+  roles.set(RolesDataKey::RebalanceManager as u32, test.rebalance_manager.clone());
+
+  let mut name_symbol: Map<String, String> = Map::new(&test.env);
+  name_symbol.set(String::from_str(&test.env, "name"), String::from_str(&test.env, "dfToken"));
+  name_symbol.set(String::from_str(&test.env, "symbol"), String::from_str(&test.env, "DFT"));
+
+  let defindex_contract = create_defindex_vault(
+      &test.env,
+      assets,
+      roles,
+      2000u32,
+      test.defindex_protocol_receiver.clone(),
+      2500u32,
+      test.soroswap_router.address.clone(),
+      name_symbol,
+      true
+  );
+
+  let amount = 10_000_000_000i128;
+  let users = DeFindexVaultTest::generate_random_users(&test.env, 1);
+  test.token_0_admin_client.mint(&users[0], &amount);
+  test.token_1_admin_client.mint(&users[0], &amount);
+  defindex_contract.deposit(
+      &sorobanvec![&test.env, amount, amount],
+      &sorobanvec![&test.env, amount, amount],
+      &users[0],
+      &false,
+  );
+
+  let strategy_address = test.fixed_strategy_client_token_0.address.clone();
+  defindex_contract.rebalance(
+      &test.rebalance_manager,
+      &sorobanvec![&test.env, Instruction::Invest(strategy_address.clone(), amount)],
+      &None,
+  );
+
+  // Fee shares would only be priced against the first asset, so the mode is rejected
+  let result = defindex_contract.try_propose(&ProposalAction::SetFeeDistributionMode(FeeDistributionMode::Shares));
+  assert_eq!(result, Err(Err(VaultError::FeeModeNotSupported.into())));
+  assert_eq!(defindex_contract.get_fee_distribution_mode(), FeeDistributionMode::Assets);
+
+  test.env.jump_time(ONE_DAY_IN_SECONDS*365);
+  test.fixed_strategy_client_token_0.harvest(&defindex_contract.address, &None::<Bytes>);
+  let strategy_balance = test.fixed_strategy_client_token_0.balance(&defindex_contract.address);
+  defindex_contract.report();
+  let locked_fee = defindex_contract.lock_fees().get(0).unwrap().locked_fee;
+  assert_eq!(locked_fee, (strategy_balance - amount) * 2000 / 10000);
+
+  defindex_contract.distribute_fees(&test.manager);
+
+  // The receivers get exactly the locked fees in the underlying asset and no vault shares
+  let defindex_fee = locked_fee * 2500 / 10000;
+  assert_eq!(test.token_0.balance(&test.vault_fee_receiver), locked_fee - defindex_fee);
+  assert_eq!(test.token_0.balance(&test.defindex_protocol_receiver), defindex_fee);
+  assert_eq!(test.token_1.balance(&test.vault_fee_receiver), 0);
+  assert_eq!(test.token_1.balance(&test.defindex_protocol_receiver), 0);
+  assert_eq!(defindex_contract.balance(&test.vault_fee_receiver), 0);
+  assert_eq!(defindex_contract.balance(&test.defindex_protocol_receiver), 0);
+
+  // The depositor can still redeem everything but the fees
+  let total_managed_funds = defindex_contract.fetch_total_managed_funds();
+  assert_eq!(total_managed_funds.get(0).unwrap().total_amount, strategy_balance - locked_fee);
+  assert_eq!(total_managed_funds.get(1).unwrap().total_amount, amount);
+}
+
+#[test]
+fn distribute_fees_to_multiple_receivers(){
+  let test = DeFindexVaultTest::setup();
+  test.env.mock_all_auths();
+  let strategy_params_token_0 = create_fixed_strategy_params_token_0(&test);
+  let assets: Vec<AssetStrategySet> = sorobanvec![
+      &test.env,
+      AssetStrategySet {
+          address: test.token_0.address.clone(),
+          strategies: strategy_params_token_0.clone()
+      }
+  ];
+
+  let mut roles: Map<u32, Address> = Map::new(&test.env);
+  roles.set(RolesDataKey::Manager as u32, test.manager.clone());
+  roles.set(RolesDataKey::EmergencyManager as u32, test.emergency_manager.clone());
+  roles.set(RolesDataKey::VaultFeeReceiver as u32, test.vault_fee_receiver.clone());
+  roles.set(RolesDataKey::RebalanceManager as u32, test.rebalance_manager.clone());
+
+  let mut name_symbol: Map<String, String> = Map::new(&test.env);
+  name_symbol.set(String::from_str(&test.env, "name"), String::from_str(&test.env, "dfToken"));
+  name_symbol.set(String::from_str(&test.env, "symbol"), String::from_str(&test.env, "DFT"));
+
+  let defindex_contract = create_defindex_vault(
+      &test.env,
+      assets,
+      roles,
+      2000u32,
+      test.defindex_protocol_receiver.clone(),
+      2500u32,
+      test.soroswap_router.address.clone(),
+      name_symbol,
+      true
+  );
+
+  let amount = 10_000_000_000i128;
+  let users = DeFindexVaultTest::generate_random_users(&test.env, 1);
+  test.token_0_admin_client.mint(&users[0], &amount);
+  defindex_contract.deposit(
+      &sorobanvec![&test.env, amount],
+      &sorobanvec![&test.env, amount],
+      &users[0],
+      &false,
+  );
+
+  let strategy_address = test.fixed_strategy_client_token_0.address.clone();
+  defindex_contract.rebalance(
+      &test.rebalance_manager,
+      &sorobanvec![&test.env, Instruction::Invest(strategy_address.clone(), amount)],
+      &None,
+  );
+
+  let fee_receivers = DeFindexVaultTest::generate_random_users(&test.env, 2);
+
+  // The weights must add up to 100%
+  let result = defindex_contract.try_propose(&ProposalAction::SetFeeReceivers(sorobanvec![
+      &test.env,
+      FeeReceiver { address: fee_receivers[0].clone(), bps: 6000 },
+      FeeReceiver { address: fee_receivers[1].clone(), bps: 3000 },
+  ]));
+  assert_eq!(result, Err(Ok(ContractError::InvalidFeeBps)));
+
+  let receivers = sorobanvec![
+      &test.env,
+      FeeReceiver { address: fee_receivers[0].clone(), bps: 6000 },
+      FeeReceiver { address: fee_receivers[1].clone(), bps: 4000 },
+  ];
+  let proposal_id = defindex_contract.propose(&ProposalAction::SetFeeReceivers(receivers.clone()));
+  test.env.jump_time(defindex_contract.get_manager_timelock());
+  defindex_contract.execute_proposal(&proposal_id);
+  assert_eq!(defindex_contract.get_fee_receivers(), receivers);
+
+  test.env.jump_time(ONE_DAY_IN_SECONDS*365);
+  test.fixed_strategy_client_token_0.harvest(&defindex_contract.address, &None::<Bytes>);
+  defindex_contract.report();
+  let locked_fee = defindex_contract.lock_fees().get(0).unwrap().locked_fee;
+  assert!(locked_fee > 0);
+
+  defindex_contract.distribute_fees(&test.manager);
+
+  let defindex_fee = locked_fee * 2500 / 10000;
+  let vault_fee = locked_fee - defindex_fee;
+  let first_receiver_fee = vault_fee * 6000 / 10000;
+  assert_eq!(test.token_0.balance(&test.defindex_protocol_receiver), defindex_fee);
+  assert_eq!(test.token_0.balance(&fee_receivers[0]), first_receiver_fee);
+  assert_eq!(test.token_0.balance(&fee_receivers[1]), vault_fee - first_receiver_fee);
+  assert_eq!(test.token_0.balance(&test.vault_fee_receiver), 0);
+}
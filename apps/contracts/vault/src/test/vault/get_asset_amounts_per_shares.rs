@@ -256,7 +256,7 @@ fn deposit_and_invest_several_assets_get_asset_amounts_per_shares() {
             amount_to_invest
         )
     ];
-    defindex_contract.rebalance(&test.rebalance_manager, &rebalance_instructions);
+    defindex_contract.rebalance(&test.rebalance_manager, &rebalance_instructions, &None);
     let token_0_invested_funds = defindex_contract.fetch_total_managed_funds().get(0).unwrap().invested_amount;
     let token_1_invested_funds = defindex_contract.fetch_total_managed_funds().get(1).unwrap().invested_amount;
     let token_0_idle_funds = test.token_0.balance(&defindex_contract.address);
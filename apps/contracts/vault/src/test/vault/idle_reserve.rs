@@ -0,0 +1,116 @@
+use soroban_sdk::{vec as sorobanvec, Address, Map, String, Vec};
+
+use crate::test::defindex_vault::{AssetStrategySet, ContractError, Instruction, RolesDataKey};
+use crate::test::{create_defindex_vault, create_strategy_params_token_0, DeFindexVaultTest};
+
+#[test]
+fn deposit_keeps_reserve_and_withdraw_tops_it_up() {
+    let test = DeFindexVaultTest::setup();
+    test.env.mock_all_auths();
+    let strategy_params_token_0 = create_strategy_params_token_0(&test);
+
+    let assets: Vec<AssetStrategySet> = sorobanvec![
+        &test.env,
+        AssetStrategySet {
+            address: test.token_0.address.clone(),
+            strategies: strategy_params_token_0.clone()
+        }
+    ];
+
+    let mut roles: Map<u32, Address> = Map::new(&test.env);
+    roles.set(RolesDataKey::Manager as u32, test.manager.clone());
+    roles.set(RolesDataKey::EmergencyManager as u32, test.emergency_manager.clone());
+    roles.set(RolesDataKey::VaultFeeReceiver as u32, test.vault_fee_receiver.clone());
+    roles.set(RolesDataKey::RebalanceManager as u32, test.rebalance_manager.clone());
+
+    let mut name_symbol: Map<String, String> = Map::new(&test.env);
+    name_symbol.set(String::from_str(&test.env, "name"), String::from_str(&test.env, "dfToken"));
+    name_symbol.set(String::from_str(&test.env, "symbol"), String::from_str(&test.env, "DFT"));
+
+    let defindex_contract = create_defindex_vault(
+        &test.env,
+        assets,
+        roles,
+        2000u32,
+        test.defindex_protocol_receiver.clone(),
+        2500u32,
+        test.soroswap_router.address.clone(),
+        name_symbol,
+        true
+    );
+
+    let amount = 1_000_000_000i128;
+    let users = DeFindexVaultTest::generate_random_users(&test.env, 1);
+    test.token_0_admin_client.mint(&users[0], &(amount * 2));
+
+    defindex_contract.deposit(&sorobanvec![&test.env, amount], &sorobanvec![&test.env, amount], &users[0], &false);
+    defindex_contract.rebalance(
+        &test.rebalance_manager,
+        &sorobanvec![&test.env, Instruction::Invest(test.strategy_client_token_0.address.clone(), amount)],
+        &None,
+    );
+
+    // 10% of the asset is kept idle
+    assert_eq!(defindex_contract.get_idle_reserve(&test.token_0.address), 0u32);
+    defindex_contract.set_idle_reserve(&test.token_0.address, &1000u32);
+    assert_eq!(defindex_contract.get_idle_reserve(&test.token_0.address), 1000u32);
+
+    defindex_contract.deposit(&sorobanvec![&test.env, amount], &sorobanvec![&test.env, amount], &users[0], &true);
+    assert_eq!(test.token_0.balance(&defindex_contract.address), 200_000_000i128);
+    assert_eq!(test.token_0.balance(&test.strategy_client_token_0.address), 1_800_000_000i128);
+
+    // Small withdrawals are served from the reserve and never touch the strategy
+    defindex_contract.withdraw_exact_amounts(&sorobanvec![&test.env, 100_000_000i128], &i128::MAX, &users[0]);
+    assert_eq!(test.token_0.balance(&users[0]), 100_000_000i128);
+    assert_eq!(test.token_0.balance(&defindex_contract.address), 100_000_000i128);
+    assert_eq!(test.token_0.balance(&test.strategy_client_token_0.address), 1_800_000_000i128);
+
+    // Larger withdrawals unwind enough to also refill the reserve
+    defindex_contract.withdraw_exact_amounts(&sorobanvec![&test.env, 500_000_000i128], &i128::MAX, &users[0]);
+    assert_eq!(test.token_0.balance(&users[0]), 600_000_000i128);
+    assert_eq!(test.token_0.balance(&defindex_contract.address), 140_000_000i128);
+    assert_eq!(test.token_0.balance(&test.strategy_client_token_0.address), 1_260_000_000i128);
+}
+
+#[test]
+fn invalid_idle_reserve() {
+    let test = DeFindexVaultTest::setup();
+    test.env.mock_all_auths();
+    let strategy_params_token_0 = create_strategy_params_token_0(&test);
+
+    let assets: Vec<AssetStrategySet> = sorobanvec![
+        &test.env,
+        AssetStrategySet {
+            address: test.token_0.address.clone(),
+            strategies: strategy_params_token_0.clone()
+        }
+    ];
+
+    let mut roles: Map<u32, Address> = Map::new(&test.env);
+    roles.set(RolesDataKey::Manager as u32, test.manager.clone());
+    roles.set(RolesDataKey::EmergencyManager as u32, test.emergency_manager.clone());
+    roles.set(RolesDataKey::VaultFeeReceiver as u32, test.vault_fee_receiver.clone());
+    roles.set(RolesDataKey::RebalanceManager as u32, test.rebalance_manager.clone());
+
+    let mut name_symbol: Map<String, String> = Map::new(&test.env);
+    name_symbol.set(String::from_str(&test.env, "name"), String::from_str(&test.env, "dfToken"));
+    name_symbol.set(String::from_str(&test.env, "symbol"), String::from_str(&test.env, "DFT"));
+
+    let defindex_contract = create_defindex_vault(
+        &test.env,
+        assets,
+        roles,
+        2000u32,
+        test.defindex_protocol_receiver.clone(),
+        2500u32,
+        test.soroswap_router.address.clone(),
+        name_symbol,
+        true
+    );
+
+    let result = defindex_contract.try_set_idle_reserve(&test.token_1.address, &1000u32);
+    assert_eq!(result, Err(Ok(ContractError::AssetNotFound)));
+
+    let result = defindex_contract.try_set_idle_reserve(&test.token_0.address, &10_001u32);
+    assert_eq!(result, Err(Ok(ContractError::InvalidRatio)));
+}
@@ -0,0 +1,152 @@
+use soroban_sdk::{testutils::Ledger, vec as sorobanvec, Address, Map, String, Vec};
+
+use crate::test::defindex_vault::{AssetStrategySet, ContractError, ProposalAction, RolesDataKey};
+use crate::test::{create_defindex_vault, create_strategy_params_token_0, DeFindexVaultTest};
+
+const SECONDS_PER_YEAR: u64 = 31_536_000;
+
+#[test]
+fn management_fee_accrues_over_time() {
+    let test = DeFindexVaultTest::setup();
+    test.env.mock_all_auths();
+    let strategy_params_token_0 = create_strategy_params_token_0(&test);
+
+    let assets: Vec<AssetStrategySet> = sorobanvec![
+        &test.env,
+        AssetStrategySet {
+            address: test.token_0.address.clone(),
+            strategies: strategy_params_token_0.clone()
+        }
+    ];
+
+    let mut roles: Map<u32, Address> = Map::new(&test.env);
+    roles.set(RolesDataKey::Manager as u32, test.manager.clone());
+    roles.set(RolesDataKey::EmergencyManager as u32, test.emergency_manager.clone());
+    roles.set(RolesDataKey::VaultFeeReceiver as u32, test.vault_fee_receiver.clone());
+    roles.set(RolesDataKey::RebalanceManager as u32, test.rebalance_manager.clone());
+
+    let mut name_symbol: Map<String, String> = Map::new(&test.env);
+    name_symbol.set(String::from_str(&test.env, "name"), String::from_str(&test.env, "dfToken"));
+    name_symbol.set(String::from_str(&test.env, "symbol"), String::from_str(&test.env, "DFT"));
+
+    let defindex_contract = create_defindex_vault(
+        &test.env,
+        assets,
+        roles,
+        2000u32,
+        test.defindex_protocol_receiver.clone(),
+        2500u32,
+        test.soroswap_router.address.clone(),
+        name_symbol,
+        true
+    );
+
+    let amount = 1_000_000_000i128;
+    let users = DeFindexVaultTest::generate_random_users(&test.env, 1);
+    test.token_0_admin_client.mint(&users[0], &(amount * 2));
+    defindex_contract.deposit(&sorobanvec![&test.env, amount], &sorobanvec![&test.env, amount], &users[0], &false);
+
+    assert_eq!(defindex_contract.get_management_fee(), 0u32);
+    let result = defindex_contract.try_propose(&ProposalAction::SetManagementFee(1001u32));
+    assert_eq!(result, Err(Ok(ContractError::MaximumFeeExceeded)));
+
+    // 1% per year
+    let proposal_id = defindex_contract.propose(&ProposalAction::SetManagementFee(100u32));
+    let executed_at = defindex_contract.get_proposal(&proposal_id).executable_at;
+    test.env.ledger().set_timestamp(executed_at);
+    defindex_contract.execute_proposal(&proposal_id);
+    assert_eq!(defindex_contract.get_management_fee(), 100u32);
+
+    // Nothing accrued for the time before the fee was set
+    assert_eq!(defindex_contract.balance(&test.vault_fee_receiver), 0i128);
+    assert_eq!(defindex_contract.balance(&test.defindex_protocol_receiver), 0i128);
+
+    let total_supply = defindex_contract.total_supply();
+    test.env.ledger().set_timestamp(executed_at + SECONDS_PER_YEAR);
+    defindex_contract.withdraw(&1000i128, &sorobanvec![&test.env, 0i128], &users[0]);
+
+    // The receivers own 1% of the vault after one year, split at the 25% protocol fee rate
+    let minted_shares = total_supply / 99;
+    let defindex_fee_shares = minted_shares * 2500 / 10000;
+    assert_eq!(defindex_contract.balance(&test.defindex_protocol_receiver), defindex_fee_shares);
+    assert_eq!(defindex_contract.balance(&test.vault_fee_receiver), minted_shares - defindex_fee_shares);
+    assert_eq!(defindex_contract.total_supply(), total_supply + minted_shares - 1000i128);
+
+    // No double accrual within the same ledger time
+    defindex_contract.withdraw(&1000i128, &sorobanvec![&test.env, 0i128], &users[0]);
+    assert_eq!(defindex_contract.total_supply(), total_supply + minted_shares - 2000i128);
+}
+
+#[test]
+fn management_fee_accrues_over_several_years() {
+    let test = DeFindexVaultTest::setup();
+    test.env.mock_all_auths();
+    let strategy_params_token_0 = create_strategy_params_token_0(&test);
+
+    let assets: Vec<AssetStrategySet> = sorobanvec![
+        &test.env,
+        AssetStrategySet {
+            address: test.token_0.address.clone(),
+            strategies: strategy_params_token_0.clone()
+        }
+    ];
+
+    let mut roles: Map<u32, Address> = Map::new(&test.env);
+    roles.set(RolesDataKey::Manager as u32, test.manager.clone());
+    roles.set(RolesDataKey::EmergencyManager as u32, test.emergency_manager.clone());
+    roles.set(RolesDataKey::VaultFeeReceiver as u32, test.vault_fee_receiver.clone());
+    roles.set(RolesDataKey::RebalanceManager as u32, test.rebalance_manager.clone());
+
+    let mut name_symbol: Map<String, String> = Map::new(&test.env);
+    name_symbol.set(String::from_str(&test.env, "name"), String::from_str(&test.env, "dfToken"));
+    name_symbol.set(String::from_str(&test.env, "symbol"), String::from_str(&test.env, "DFT"));
+
+    let defindex_contract = create_defindex_vault(
+        &test.env,
+        assets,
+        roles,
+        2000u32,
+        test.defindex_protocol_receiver.clone(),
+        2500u32,
+        test.soroswap_router.address.clone(),
+        name_symbol,
+        true
+    );
+
+    let amount = 1_000_000_000i128;
+    let users = DeFindexVaultTest::generate_random_users(&test.env, 2);
+    test.token_0_admin_client.mint(&users[0], &amount);
+    test.token_0_admin_client.mint(&users[1], &amount);
+    defindex_contract.deposit(&sorobanvec![&test.env, amount], &sorobanvec![&test.env, amount], &users[0], &false);
+
+    // 1% per year
+    let proposal_id = defindex_contract.propose(&ProposalAction::SetManagementFee(100u32));
+    let executed_at = defindex_contract.get_proposal(&proposal_id).executable_at;
+    test.env.ledger().set_timestamp(executed_at);
+    defindex_contract.execute_proposal(&proposal_id);
+
+    // Two years without any deposit, withdrawal or report
+    let total_supply = defindex_contract.total_supply();
+    test.env.ledger().set_timestamp(executed_at + 2 * SECONDS_PER_YEAR);
+
+    // The preview accounts for the fee shares minted by the deposit itself
+    let (_, preview_shares) = defindex_contract.preview_deposit(&sorobanvec![&test.env, amount]);
+    defindex_contract.deposit(&sorobanvec![&test.env, amount], &sorobanvec![&test.env, amount], &users[1], &false);
+    assert_eq!(defindex_contract.balance(&users[1]), preview_shares);
+
+    // Both years are charged, the second one on the shares minted for the first
+    let first_year_shares = total_supply / 99;
+    let second_year_shares = (total_supply + first_year_shares) / 99;
+    let minted_shares = first_year_shares + second_year_shares;
+    let fee_receivers_shares = defindex_contract.balance(&test.defindex_protocol_receiver)
+        + defindex_contract.balance(&test.vault_fee_receiver);
+    assert_eq!(fee_receivers_shares, minted_shares);
+    assert_eq!(defindex_contract.total_supply(), total_supply + minted_shares + preview_shares);
+
+    // Same for withdrawals
+    test.env.ledger().set_timestamp(executed_at + 3 * SECONDS_PER_YEAR);
+    let amounts_out = sorobanvec![&test.env, amount / 2];
+    let preview_shares = defindex_contract.preview_withdraw(&amounts_out);
+    let burned_shares = defindex_contract.withdraw_exact_amounts(&amounts_out, &i128::MAX, &users[1]);
+    assert_eq!(burned_shares, preview_shares);
+}
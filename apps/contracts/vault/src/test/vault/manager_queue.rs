@@ -0,0 +1,133 @@
+use soroban_sdk::{testutils::Ledger, vec as sorobanvec, Address, Map, String, Vec};
+
+use crate::test::defindex_vault::{AssetStrategySet, ContractError, QueuedManager, RolesDataKey};
+use crate::test::{create_defindex_vault, create_strategy_params_token_0, DeFindexVaultTest};
+
+#[test]
+fn manager_handover_after_timelock() {
+    let test = DeFindexVaultTest::setup();
+    test.env.mock_all_auths();
+    let strategy_params_token_0 = create_strategy_params_token_0(&test);
+
+    let assets: Vec<AssetStrategySet> = sorobanvec![
+        &test.env,
+        AssetStrategySet {
+            address: test.token_0.address.clone(),
+            strategies: strategy_params_token_0.clone()
+        }
+    ];
+
+    let mut roles: Map<u32, Address> = Map::new(&test.env);
+    roles.set(RolesDataKey::Manager as u32, test.manager.clone());
+    roles.set(RolesDataKey::EmergencyManager as u32, test.emergency_manager.clone());
+    roles.set(RolesDataKey::VaultFeeReceiver as u32, test.vault_fee_receiver.clone());
+    roles.set(RolesDataKey::RebalanceManager as u32, test.rebalance_manager.clone());
+
+    let mut name_symbol: Map<String, String> = Map::new(&test.env);
+    name_symbol.set(String::from_str(&test.env, "name"), String::from_str(&test.env, "dfToken"));
+    name_symbol.set(String::from_str(&test.env, "symbol"), String::from_str(&test.env, "DFT"));
+
+    let defindex_contract = create_defindex_vault(
+        &test.env,
+        assets,
+        roles,
+        2000u32,
+        test.defindex_protocol_receiver.clone(),
+        2500u32,
+        test.soroswap_router.address.clone(),
+        name_symbol,
+        true
+    );
+
+    let users = DeFindexVaultTest::generate_random_users(&test.env, 1);
+    let new_manager = users[0].clone();
+
+    // 7 days by default, and at least 1 day
+    assert_eq!(defindex_contract.get_manager_timelock(), 604_800u64);
+    let result = defindex_contract.try_set_manager_timelock(&86_399u64);
+    assert_eq!(result, Err(Ok(ContractError::AmountNotAllowed)));
+
+    // A new delay only applies once the current one has elapsed
+    let changed_at = test.env.ledger().timestamp();
+    defindex_contract.set_manager_timelock(&86_400u64);
+    assert_eq!(defindex_contract.get_manager_timelock(), 604_800u64);
+    test.env.ledger().set_timestamp(changed_at + 604_799);
+    assert_eq!(defindex_contract.get_manager_timelock(), 604_800u64);
+    test.env.ledger().set_timestamp(changed_at + 604_800);
+    assert_eq!(defindex_contract.get_manager_timelock(), 86_400u64);
+
+    let result = defindex_contract.try_accept_manager();
+    assert_eq!(result, Err(Ok(ContractError::QueueEmpty)));
+
+    let now = test.env.ledger().timestamp();
+    defindex_contract.queue_manager(&new_manager);
+    assert_eq!(
+        defindex_contract.get_queued_manager(),
+        QueuedManager {
+            new_manager: new_manager.clone(),
+            available_at: now + 86_400,
+        }
+    );
+
+    test.env.ledger().set_timestamp(now + 86_399);
+    let result = defindex_contract.try_accept_manager();
+    assert_eq!(result, Err(Ok(ContractError::SetManagerBeforeTime)));
+    assert_eq!(defindex_contract.get_manager(), test.manager);
+
+    test.env.ledger().set_timestamp(now + 86_400);
+    defindex_contract.accept_manager();
+    assert_eq!(defindex_contract.get_manager(), new_manager);
+
+    let result = defindex_contract.try_get_queued_manager();
+    assert_eq!(result, Err(Ok(ContractError::QueueEmpty)));
+}
+
+#[test]
+fn cancel_manager_queue() {
+    let test = DeFindexVaultTest::setup();
+    test.env.mock_all_auths();
+    let strategy_params_token_0 = create_strategy_params_token_0(&test);
+
+    let assets: Vec<AssetStrategySet> = sorobanvec![
+        &test.env,
+        AssetStrategySet {
+            address: test.token_0.address.clone(),
+            strategies: strategy_params_token_0.clone()
+        }
+    ];
+
+    let mut roles: Map<u32, Address> = Map::new(&test.env);
+    roles.set(RolesDataKey::Manager as u32, test.manager.clone());
+    roles.set(RolesDataKey::EmergencyManager as u32, test.emergency_manager.clone());
+    roles.set(RolesDataKey::VaultFeeReceiver as u32, test.vault_fee_receiver.clone());
+    roles.set(RolesDataKey::RebalanceManager as u32, test.rebalance_manager.clone());
+
+    let mut name_symbol: Map<String, String> = Map::new(&test.env);
+    name_symbol.set(String::from_str(&test.env, "name"), String::from_str(&test.env, "dfToken"));
+    name_symbol.set(String::from_str(&test.env, "symbol"), String::from_str(&test.env, "DFT"));
+
+    let defindex_contract = create_defindex_vault(
+        &test.env,
+        assets,
+        roles,
+        2000u32,
+        test.defindex_protocol_receiver.clone(),
+        2500u32,
+        test.soroswap_router.address.clone(),
+        name_symbol,
+        true
+    );
+
+    let users = DeFindexVaultTest::generate_random_users(&test.env, 1);
+
+    let result = defindex_contract.try_cancel_manager_queue();
+    assert_eq!(result, Err(Ok(ContractError::QueueEmpty)));
+
+    defindex_contract.queue_manager(&users[0]);
+    defindex_contract.cancel_manager_queue();
+
+    test.env.ledger().set_timestamp(test.env.ledger().timestamp() + defindex_contract.get_manager_timelock());
+    let result = defindex_contract.try_accept_manager();
+    assert_eq!(result, Err(Ok(ContractError::QueueEmpty)));
+    assert_eq!(defindex_contract.get_manager(), test.manager);
+}
@@ -1,14 +1,30 @@
 mod admin;
+mod allowlist;
 mod budget;
 mod deposit;
 mod deposit_and_invest;
+mod deposit_caps;
+mod deposit_single_asset;
+mod entry_exit_fees;
 mod events;
 mod fees;
 mod funds;
 mod get_asset_amounts_per_shares;
+mod idle_reserve;
 mod initialize;
+mod management_fee;
+mod manager_queue;
+mod pause;
+mod preview;
 mod rebalance;
+mod rebalance_to_targets;
 mod rescue;
+mod target_allocation;
 mod upgrade;
 mod withdraw;
-mod router;
\ No newline at end of file
+mod withdraw_exact_amounts;
+mod withdraw_order;
+mod withdraw_queue;
+mod withdraw_to_asset;
+mod router;
+mod strategy_management;
\ No newline at end of file
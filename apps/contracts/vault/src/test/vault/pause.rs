@@ -0,0 +1,146 @@
+use soroban_sdk::{
+    testutils::{MockAuth, MockAuthInvoke},
+    vec as sorobanvec, Address, IntoVal, Map, String, Vec,
+};
+
+use crate::test::defindex_vault::{AssetStrategySet, Instruction, PauseFlags, RolesDataKey, VaultError};
+use crate::test::{create_defindex_vault, create_strategy_params_token_0, DeFindexVaultTest};
+
+#[test]
+fn pause_deposits_withdrawals_and_rebalances() {
+    let test = DeFindexVaultTest::setup();
+    test.env.mock_all_auths();
+    let strategy_params_token_0 = create_strategy_params_token_0(&test);
+
+    let assets: Vec<AssetStrategySet> = sorobanvec![
+        &test.env,
+        AssetStrategySet {
+            address: test.token_0.address.clone(),
+            strategies: strategy_params_token_0.clone()
+        }
+    ];
+
+    let mut roles: Map<u32, Address> = Map::new(&test.env);
+    roles.set(RolesDataKey::Manager as u32, test.manager.clone());
+    roles.set(RolesDataKey::EmergencyManager as u32, test.emergency_manager.clone());
+    roles.set(RolesDataKey::VaultFeeReceiver as u32, test.vault_fee_receiver.clone());
+    roles.set(RolesDataKey::RebalanceManager as u32, test.rebalance_manager.clone());
+
+    let mut name_symbol: Map<String, String> = Map::new(&test.env);
+    name_symbol.set(String::from_str(&test.env, "name"), String::from_str(&test.env, "dfToken"));
+    name_symbol.set(String::from_str(&test.env, "symbol"), String::from_str(&test.env, "DFT"));
+
+    let defindex_contract = create_defindex_vault(
+        &test.env,
+        assets,
+        roles,
+        2000u32,
+        test.defindex_protocol_receiver.clone(),
+        2500u32,
+        test.soroswap_router.address.clone(),
+        name_symbol,
+        true
+    );
+
+    let amount = 1_000_000_000i128;
+    let users = DeFindexVaultTest::generate_random_users(&test.env, 1);
+    test.token_0_admin_client.mint(&users[0], &(amount * 2));
+    defindex_contract.deposit(&sorobanvec![&test.env, amount], &sorobanvec![&test.env, amount], &users[0], &false);
+
+    assert_eq!(
+        defindex_contract.get_pause_flags(),
+        PauseFlags { deposits: false, withdrawals: false, rebalances: false }
+    );
+
+    // Deposits only
+    defindex_contract.set_pause_flags(&PauseFlags { deposits: true, withdrawals: false, rebalances: false });
+    let result = defindex_contract.try_deposit(
+        &sorobanvec![&test.env, amount],
+        &sorobanvec![&test.env, amount],
+        &users[0],
+        &false,
+    );
+    assert_eq!(result, Err(Err(VaultError::DepositsPaused.into())));
+    let result = defindex_contract.try_deposit_single_asset(&test.token_0.address, &amount, &0i128, &users[0], &false);
+    assert_eq!(result, Err(Err(VaultError::DepositsPaused.into())));
+    defindex_contract.withdraw(&1000i128, &sorobanvec![&test.env, 0i128], &users[0]);
+
+    // Withdrawals and rebalances
+    defindex_contract.set_pause_flags(&PauseFlags { deposits: false, withdrawals: true, rebalances: true });
+    let result = defindex_contract.try_withdraw(&1000i128, &sorobanvec![&test.env, 0i128], &users[0]);
+    assert_eq!(result, Err(Err(VaultError::WithdrawalsPaused.into())));
+    let result = defindex_contract.try_withdraw_exact_amounts(&sorobanvec![&test.env, 1000i128], &i128::MAX, &users[0]);
+    assert_eq!(result, Err(Err(VaultError::WithdrawalsPaused.into())));
+    let result = defindex_contract.try_request_withdraw(&1000i128, &users[0]);
+    assert_eq!(result, Err(Err(VaultError::WithdrawalsPaused.into())));
+    let result = defindex_contract.try_rebalance(
+        &test.rebalance_manager,
+        &sorobanvec![&test.env, Instruction::Invest(test.strategy_client_token_0.address.clone(), amount / 2)],
+        &None,
+    );
+    assert_eq!(result, Err(Err(VaultError::RebalancesPaused.into())));
+    let result = defindex_contract.try_rebalance_to_targets(&10u32);
+    assert_eq!(result, Err(Err(VaultError::RebalancesPaused.into())));
+    defindex_contract.deposit(&sorobanvec![&test.env, amount], &sorobanvec![&test.env, amount], &users[0], &false);
+
+    // Unpause everything
+    defindex_contract.set_pause_flags(&PauseFlags { deposits: false, withdrawals: false, rebalances: false });
+    defindex_contract.withdraw(&1000i128, &sorobanvec![&test.env, 0i128], &users[0]);
+    defindex_contract.rebalance(
+        &test.rebalance_manager,
+        &sorobanvec![&test.env, Instruction::Invest(test.strategy_client_token_0.address.clone(), amount / 2)],
+        &None,
+    );
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Auth, InvalidAction)")] // Unauthorized
+fn set_pause_flags_by_manager() {
+    let test = DeFindexVaultTest::setup();
+    let strategy_params_token_0 = create_strategy_params_token_0(&test);
+
+    let assets: Vec<AssetStrategySet> = sorobanvec![
+        &test.env,
+        AssetStrategySet {
+            address: test.token_0.address.clone(),
+            strategies: strategy_params_token_0.clone()
+        }
+    ];
+
+    let mut roles: Map<u32, Address> = Map::new(&test.env);
+    roles.set(RolesDataKey::Manager as u32, test.manager.clone());
+    roles.set(RolesDataKey::EmergencyManager as u32, test.emergency_manager.clone());
+    roles.set(RolesDataKey::VaultFeeReceiver as u32, test.vault_fee_receiver.clone());
+    roles.set(RolesDataKey::RebalanceManager as u32, test.rebalance_manager.clone());
+
+    let mut name_symbol: Map<String, String> = Map::new(&test.env);
+    name_symbol.set(String::from_str(&test.env, "name"), String::from_str(&test.env, "dfToken"));
+    name_symbol.set(String::from_str(&test.env, "symbol"), String::from_str(&test.env, "DFT"));
+
+    let defindex_contract = create_defindex_vault(
+        &test.env,
+        assets,
+        roles,
+        2000u32,
+        test.defindex_protocol_receiver.clone(),
+        2500u32,
+        test.soroswap_router.address.clone(),
+        name_symbol,
+        true
+    );
+
+    let flags = PauseFlags { deposits: true, withdrawals: true, rebalances: true };
+
+    // Only the Emergency Manager can pause the vault
+    defindex_contract
+        .mock_auths(&[MockAuth {
+            address: &test.manager,
+            invoke: &MockAuthInvoke {
+                contract: &defindex_contract.address.clone(),
+                fn_name: "set_pause_flags",
+                args: (flags.clone(),).into_val(&test.env),
+                sub_invokes: &[],
+            },
+        }])
+        .set_pause_flags(&flags);
+}
@@ -0,0 +1,167 @@
+use soroban_sdk::{vec as sorobanvec, Address, Map, String, Vec};
+
+use crate::test::defindex_vault::{AssetStrategySet, ContractError, RolesDataKey};
+use crate::test::{
+    create_defindex_vault, create_strategy_params_token_0, create_strategy_params_token_1,
+    DeFindexVaultTest,
+};
+
+#[test]
+fn preview_deposit_matches_deposit() {
+    let test = DeFindexVaultTest::setup();
+    test.env.mock_all_auths();
+    let strategy_params_token_0 = create_strategy_params_token_0(&test);
+    let strategy_params_token_1 = create_strategy_params_token_1(&test);
+
+    let assets: Vec<AssetStrategySet> = sorobanvec![
+        &test.env,
+        AssetStrategySet {
+            address: test.token_0.address.clone(),
+            strategies: strategy_params_token_0.clone()
+        },
+        AssetStrategySet {
+            address: test.token_1.address.clone(),
+            strategies: strategy_params_token_1.clone()
+        }
+    ];
+
+    let mut roles: Map<u32, Address> = Map::new(&test.env);
+    roles.set(RolesDataKey::Manager as u32, test.manager.clone());
+    roles.set(RolesDataKey::EmergencyManager as u32, test.emergency_manager.clone());
+    roles.set(RolesDataKey::VaultFeeReceiver as u32, test.vault_fee_receiver.clone());
+    roles.set(RolesDataKey::RebalanceManager as u32, test.rebalance_manager.clone());
+
+    let mut name_symbol: Map<String, String> = Map::new(&test.env);
+    name_symbol.set(String::from_str(&test.env, "name"), String::from_str(&test.env, "dfToken"));
+    name_symbol.set(String::from_str(&test.env, "symbol"), String::from_str(&test.env, "DFT"));
+
+    let defindex_contract = create_defindex_vault(
+        &test.env,
+        assets,
+        roles,
+        2000u32,
+        test.defindex_protocol_receiver.clone(),
+        2500u32,
+        test.soroswap_router.address.clone(),
+        name_symbol,
+        true
+    );
+
+    let amount0 = 123_456_789i128;
+    let amount1 = 987_654_321i128;
+
+    let users = DeFindexVaultTest::generate_random_users(&test.env, 2);
+    test.token_0_admin_client.mint(&users[0], &amount0);
+    test.token_1_admin_client.mint(&users[0], &amount1);
+
+    // First deposit, MINIMUM_LIQUIDITY is kept by the vault
+    let amounts_desired = sorobanvec![&test.env, amount0, amount1];
+    let (preview_amounts, preview_shares) = defindex_contract.preview_deposit(&amounts_desired);
+    assert_eq!(preview_amounts, amounts_desired);
+    assert_eq!(preview_shares, amount0 + amount1 - 1000);
+    assert_eq!(defindex_contract.convert_to_shares(&amounts_desired), amount0 + amount1);
+
+    // Previews do not move any funds
+    assert_eq!(test.token_0.balance(&users[0]), amount0);
+    assert_eq!(defindex_contract.total_supply(), 0);
+
+    defindex_contract.deposit(&amounts_desired, &amounts_desired, &users[0], &false);
+    assert_eq!(defindex_contract.balance(&users[0]), preview_shares);
+
+    // Second deposit, not in the vault ratio
+    let amounts_desired = sorobanvec![&test.env, 50_000_000i128, 1_000_000_000i128];
+    test.token_0_admin_client.mint(&users[1], &50_000_000i128);
+    test.token_1_admin_client.mint(&users[1], &1_000_000_000i128);
+
+    let (preview_amounts, preview_shares) = defindex_contract.preview_deposit(&amounts_desired);
+    assert_eq!(defindex_contract.convert_to_shares(&amounts_desired), preview_shares);
+
+    let (deposited_amounts, minted_shares, _) = defindex_contract.deposit(
+        &amounts_desired,
+        &sorobanvec![&test.env, 0i128, 0i128],
+        &users[1],
+        &false,
+    );
+    assert_eq!(preview_amounts, deposited_amounts);
+    assert_eq!(preview_shares, minted_shares);
+    assert_eq!(defindex_contract.balance(&users[1]), minted_shares);
+}
+
+#[test]
+fn preview_withdraw_and_convert_to_assets() {
+    let test = DeFindexVaultTest::setup();
+    test.env.mock_all_auths();
+    let strategy_params_token_0 = create_strategy_params_token_0(&test);
+    let strategy_params_token_1 = create_strategy_params_token_1(&test);
+
+    let assets: Vec<AssetStrategySet> = sorobanvec![
+        &test.env,
+        AssetStrategySet {
+            address: test.token_0.address.clone(),
+            strategies: strategy_params_token_0.clone()
+        },
+        AssetStrategySet {
+            address: test.token_1.address.clone(),
+            strategies: strategy_params_token_1.clone()
+        }
+    ];
+
+    let mut roles: Map<u32, Address> = Map::new(&test.env);
+    roles.set(RolesDataKey::Manager as u32, test.manager.clone());
+    roles.set(RolesDataKey::EmergencyManager as u32, test.emergency_manager.clone());
+    roles.set(RolesDataKey::VaultFeeReceiver as u32, test.vault_fee_receiver.clone());
+    roles.set(RolesDataKey::RebalanceManager as u32, test.rebalance_manager.clone());
+
+    let mut name_symbol: Map<String, String> = Map::new(&test.env);
+    name_symbol.set(String::from_str(&test.env, "name"), String::from_str(&test.env, "dfToken"));
+    name_symbol.set(String::from_str(&test.env, "symbol"), String::from_str(&test.env, "DFT"));
+
+    let defindex_contract = create_defindex_vault(
+        &test.env,
+        assets,
+        roles,
+        2000u32,
+        test.defindex_protocol_receiver.clone(),
+        2500u32,
+        test.soroswap_router.address.clone(),
+        name_symbol,
+        true
+    );
+
+    let amount0 = 123_456_789i128;
+    let amount1 = 987_654_321i128;
+
+    let users = DeFindexVaultTest::generate_random_users(&test.env, 1);
+    test.token_0_admin_client.mint(&users[0], &amount0);
+    test.token_1_admin_client.mint(&users[0], &amount1);
+
+    defindex_contract.deposit(
+        &sorobanvec![&test.env, amount0, amount1],
+        &sorobanvec![&test.env, amount0, amount1],
+        &users[0],
+        &false,
+    );
+
+    let amounts_out = sorobanvec![&test.env, 10_000_000i128, 3_000_000i128];
+    let shares = defindex_contract.preview_withdraw(&amounts_out);
+
+    // Burning one share less would not be enough for token_0
+    let amounts_for_fewer_shares = defindex_contract.convert_to_assets(&(shares - 1));
+    assert!(amounts_for_fewer_shares.get(0).unwrap() < 10_000_000i128);
+
+    let expected_amounts = defindex_contract.convert_to_assets(&shares);
+    assert_eq!(expected_amounts, defindex_contract.get_asset_amounts_per_shares(&shares));
+
+    let withdrawn_amounts = defindex_contract.withdraw(&shares, &amounts_out, &users[0]);
+    assert_eq!(withdrawn_amounts, expected_amounts);
+    assert!(withdrawn_amounts.get(0).unwrap() >= 10_000_000i128);
+    assert!(withdrawn_amounts.get(1).unwrap() >= 3_000_000i128);
+
+    // Requesting more than the vault holds
+    let result = defindex_contract.try_preview_withdraw(&sorobanvec![&test.env, amount0 + 1, 0i128]);
+    assert_eq!(result, Err(Ok(ContractError::InsufficientManagedFunds)));
+
+    // Wrong amounts length
+    let result = defindex_contract.try_preview_withdraw(&sorobanvec![&test.env, 1i128]);
+    assert_eq!(result, Err(Ok(ContractError::WrongAmountsLength)));
+}
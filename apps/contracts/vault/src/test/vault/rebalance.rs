@@ -3,13 +3,13 @@ use soroban_sdk::{vec as sorobanvec, Address, InvokeError, Map, String, Vec, Int
 
 use crate::storage;
 use crate::test::create_unsafe_strategy_params_token_0;
-use crate::test::defindex_vault::{ContractError, RolesDataKey, Strategy};
+use crate::test::defindex_vault::{ContractError, RolesDataKey, Strategy, VaultError};
 use crate::test::{
     std,
     create_defindex_vault, create_strategy_params_token_0, create_strategy_params_token_1,
     defindex_vault::{
-        AssetStrategySet, CurrentAssetInvestmentAllocation, Instruction,
-        StrategyAllocation,
+        AssetStrategySet, CurrentAssetInvestmentAllocation, Instruction, PauseFlags,
+        StrategyAllocation, ZapperInstruction,
     },
     DeFindexVaultTest,
 };
@@ -87,12 +87,13 @@ fn multi_instructions() {
             args: (
                 test.rebalance_manager.clone(),
                 invest_instructions.clone(),
+                None::<u32>,
             )
                 .into_val(&test.env),
             sub_invokes: &[],
         },
     }])
-    .rebalance(&test.rebalance_manager, &invest_instructions);
+    .rebalance(&test.rebalance_manager, &invest_instructions, &None);
 
     let vault_balance = test.token_0.balance(&defindex_contract.address);
     assert_eq!(vault_balance, 0);
@@ -123,12 +124,13 @@ fn multi_instructions() {
             args: (
                 test.rebalance_manager.clone(),
                 instructions.clone(),
+                None::<u32>,
             )
                 .into_val(&test.env),
             sub_invokes: &[],
         },
     }])
-    .rebalance(&test.rebalance_manager, &instructions);
+    .rebalance(&test.rebalance_manager, &instructions, &None);
 
     let vault_balance = test.token_0.balance(&defindex_contract.address);
     assert_eq!(vault_balance, instruction_amount_1);
@@ -207,12 +209,13 @@ fn one_instruction() {
             args: (
                 test.rebalance_manager.clone(),
                 invest_instructions.clone(),
+                None::<u32>,
             )
                 .into_val(&test.env),
             sub_invokes: &[],
         },
     }])
-    .rebalance(&test.rebalance_manager, &invest_instructions);
+    .rebalance(&test.rebalance_manager, &invest_instructions, &None);
 
     let vault_balance = test.token_0.balance(&defindex_contract.address);
     assert_eq!(vault_balance, 0);
@@ -238,12 +241,13 @@ fn one_instruction() {
             args: (
                 test.rebalance_manager.clone(),
                 instructions.clone(),
+                None::<u32>,
             )
                 .into_val(&test.env),
             sub_invokes: &[],
         },
     }])
-    .rebalance(&test.rebalance_manager, &instructions);
+    .rebalance(&test.rebalance_manager, &instructions, &None);
 
     let vault_balance = test.token_0.balance(&defindex_contract.address);
     assert_eq!(vault_balance, instruction_amount_0);
@@ -309,12 +313,13 @@ fn no_instructions() {
             args: (
                 test.rebalance_manager.clone(),
                 empty_intructions.clone(),
+                None::<u32>,
             )
                 .into_val(&test.env),
             sub_invokes: &[],
         },
     }])
-    .try_rebalance(&test.rebalance_manager, &empty_intructions);
+    .try_rebalance(&test.rebalance_manager, &empty_intructions, &None);
     assert_eq!(rebalance, Err(Ok(ContractError::NoInstructions)));
 }
 
@@ -375,12 +380,13 @@ fn insufficient_balance() {
             args: (
                 test.rebalance_manager.clone(),
                 withdraw_no_funds_instructions.clone(),
+                None::<u32>,
             )
                 .into_val(&test.env),
             sub_invokes: &[],
         },
     }])
-    .try_rebalance(&test.rebalance_manager, &withdraw_no_funds_instructions);
+    .try_rebalance(&test.rebalance_manager, &withdraw_no_funds_instructions, &None);
     assert_eq!(
         withdraw_no_funds,
         Err(Ok(ContractError::UnwindMoreThanAvailable))
@@ -400,12 +406,13 @@ fn insufficient_balance() {
             args: (
                 test.rebalance_manager.clone(),
                 invest_no_funds_instructions.clone(),
+                None::<u32>,
             )
                 .into_val(&test.env),
             sub_invokes: &[],
         },
     }])
-    .try_rebalance(&test.rebalance_manager, &invest_no_funds_instructions);
+    .try_rebalance(&test.rebalance_manager, &invest_no_funds_instructions, &None);
 
     //Contract should fail with error #10 no balance or panic the test
     if invest_no_funds != Err(Err(InvokeError::Contract(10))) {
@@ -437,12 +444,13 @@ fn insufficient_balance() {
             args: (
                 test.rebalance_manager.clone(),
                 withdraw_instructions.clone(),
+                None::<u32>,
             )
                 .into_val(&test.env),
             sub_invokes: &[],
         },
     }])
-    .try_rebalance(&test.rebalance_manager, &withdraw_instructions);
+    .try_rebalance(&test.rebalance_manager, &withdraw_instructions, &None);
     assert_eq!(rebalance, Err(Ok(ContractError::UnwindMoreThanAvailable)));
 
     let invest_instructions = sorobanvec![
@@ -460,12 +468,13 @@ fn insufficient_balance() {
             args: (
                 test.rebalance_manager.clone(),
                 invest_instructions.clone(),
+                None::<u32>,
             )
                 .into_val(&test.env),
             sub_invokes: &[],
         },
     }])
-    .try_rebalance(&test.rebalance_manager, &invest_instructions);
+    .try_rebalance(&test.rebalance_manager, &invest_instructions, &None);
     if rebalance == Err(Err(InvokeError::Contract(10))) {
         return;
     } else {
@@ -582,8 +591,7 @@ fn swap_exact_in() {
     let instructions = sorobanvec![
         &test.env,
         Instruction::SwapExactIn(
-            test.token_0.address.clone(),
-            test.token_1.address.clone(),
+            path.clone(),
             amount_in,
             0, // amount_out_min
             test.env.ledger().timestamp() + 3600u64
@@ -599,12 +607,13 @@ fn swap_exact_in() {
             args: (
                 test.rebalance_manager.clone(),
                 instructions.clone(),
+                None::<u32>,
             )
                 .into_val(&test.env),
             sub_invokes: &[],
         },
     }])
-    .rebalance(&test.rebalance_manager, &instructions);
+    .rebalance(&test.rebalance_manager, &instructions, &None);
 
     // check total managed funds
     let mut total_managed_funds_expected = Vec::new(&test.env);
@@ -754,8 +763,7 @@ fn swap_exact_out() {
     let insufficient_instructions = sorobanvec![
         &test.env,
         Instruction::SwapExactOut(
-            test.token_0.address.clone(),
-            test.token_1.address.clone(),
+            sorobanvec![&test.env, test.token_0.address.clone(), test.token_1.address.clone()],
             expected_amount_out,
             insufficient_amount_in_max,
             test.env.ledger().timestamp() + 3600u64
@@ -771,12 +779,13 @@ fn swap_exact_out() {
                 args: (
                     test.rebalance_manager.clone(),
                     insufficient_instructions.clone(),
+                    None::<u32>,
                 )
                     .into_val(&test.env),
                 sub_invokes: &[],
             },
         }])
-        .try_rebalance(&test.rebalance_manager, &insufficient_instructions);
+        .try_rebalance(&test.rebalance_manager, &insufficient_instructions, &None);
     
     assert_eq!(rebalance_result, Err(Ok(ContractError::ExcessiveInputAmount)));
 
@@ -789,8 +798,7 @@ fn swap_exact_out() {
     let instructions = sorobanvec![
         &test.env,
         Instruction::SwapExactOut(
-            test.token_0.address.clone(),
-            test.token_1.address.clone(),
+            path.clone(),
             expected_amount_out, //amount_out
             amount_in_should,    // amount_in_max
             test.env.ledger().timestamp() + 3600u64
@@ -806,12 +814,13 @@ fn swap_exact_out() {
             args: (
                 test.rebalance_manager.clone(),
                 instructions.clone(),
+                None::<u32>,
             )
                 .into_val(&test.env),
             sub_invokes: &[],
         },
     }])
-    .rebalance(&test.rebalance_manager, &instructions);
+    .rebalance(&test.rebalance_manager, &instructions, &None);
 
     // check total managed funds
     let mut total_managed_funds_expected = Vec::new(&test.env);
@@ -915,8 +924,7 @@ fn swap_from_unauthorized(){
     let instructions = sorobanvec![
         &test.env,
         Instruction::SwapExactIn(
-            test.token_0.address.clone(),
-            test.token_1.address.clone(),
+            sorobanvec![&test.env, test.token_0.address.clone(), test.token_1.address.clone()],
             amount_in,
             0, // amount_out_min
             test.env.ledger().timestamp() + 3600u64
@@ -932,12 +940,13 @@ fn swap_from_unauthorized(){
             args: (
                 test.rebalance_manager.clone(),
                 instructions.clone(),
+                None::<u32>,
             )
                 .into_val(&test.env),
             sub_invokes: &[],
         },
     }])
-    .rebalance(&test.rebalance_manager, &instructions);
+    .rebalance(&test.rebalance_manager, &instructions, &None);
 }
 #[test]
 fn swap_wrong_asset_in(){
@@ -1003,15 +1012,14 @@ fn swap_wrong_asset_in(){
     let instructions = sorobanvec![
         &test.env,
         Instruction::SwapExactIn(
-            test.token_2.address.clone(),
-            test.token_1.address.clone(),
+            sorobanvec![&test.env, test.token_2.address.clone(), test.token_1.address.clone()],
             amount_in,
             0, // amount_out_min
             test.env.ledger().timestamp() + 3600u64
         ),
     ];
 
-    let result = defindex_contract.try_rebalance(&test.rebalance_manager, &instructions);
+    let result = defindex_contract.try_rebalance(&test.rebalance_manager, &instructions, &None);
     assert_eq!(result, Err(Ok(ContractError::UnsupportedAsset)));
 
 }
@@ -1079,15 +1087,14 @@ fn swap_wrong_asset_out(){
     let instructions = sorobanvec![
         &test.env,
         Instruction::SwapExactOut(
-            test.token_0.address.clone(),
-            test.token_2.address.clone(),
+            sorobanvec![&test.env, test.token_0.address.clone(), test.token_2.address.clone()],
             amount_in,
             0, // amount_out_min
             test.env.ledger().timestamp() + 3600u64
         ),
     ];
 
-    let result = defindex_contract.try_rebalance(&test.rebalance_manager, &instructions);
+    let result = defindex_contract.try_rebalance(&test.rebalance_manager, &instructions, &None);
     assert_eq!(result, Err(Ok(ContractError::UnsupportedAsset)));
 }
 #[test]
@@ -1157,7 +1164,7 @@ fn invest_negative_or_zero_amount(){
             amount_in,
         ),
     ];
-    let result = defindex_contract.try_rebalance(&test.rebalance_manager, &instructions);
+    let result = defindex_contract.try_rebalance(&test.rebalance_manager, &instructions, &None);
     assert_eq!(result, Err(Ok(ContractError::AmountNotAllowed)));
 
     let amount_in = -(1_000_000i128);
@@ -1171,7 +1178,7 @@ fn invest_negative_or_zero_amount(){
         ),
     ];
 
-    let result = defindex_contract.try_rebalance(&test.rebalance_manager, &instructions);
+    let result = defindex_contract.try_rebalance(&test.rebalance_manager, &instructions, &None);
     assert_eq!(result, Err(Ok(ContractError::AmountNotAllowed)));
 }
 #[test]
@@ -1243,7 +1250,7 @@ fn invest_wrong_address(){
         ),
     ];
 
-    let result = defindex_contract.try_rebalance(&test.rebalance_manager, &instructions);
+    let result = defindex_contract.try_rebalance(&test.rebalance_manager, &instructions, &None);
     assert_eq!(result, Err(Ok(ContractError::StrategyNotFound)));
 }
 #[test]
@@ -1332,7 +1339,7 @@ fn invest_paused_strategy(){
             amount_to_invest,
         ),
     ];
-    let result = defindex_contract.try_rebalance(&test.rebalance_manager, &instructions);
+    let result = defindex_contract.try_rebalance(&test.rebalance_manager, &instructions, &None);
     
     // Check if invested funds are 0
     let invested_funds = defindex_contract.fetch_total_managed_funds().get(0).unwrap().invested_amount;
@@ -1405,7 +1412,7 @@ fn invest_more_than_idle_funds(){
             amount_to_invest,
         ),
     ];
-    defindex_contract.rebalance(&test.rebalance_manager, &instructions);
+    defindex_contract.rebalance(&test.rebalance_manager, &instructions, &None);
 }
 
 #[test]
@@ -1485,12 +1492,13 @@ fn invest_unauthorized(){
             args: (
                 test.rebalance_manager.clone(),
                 instructions.clone(),
+                None::<u32>,
             )
                 .into_val(&test.env),
             sub_invokes: &[],
         },
     }])
-    .rebalance(&test.rebalance_manager, &instructions);
+    .rebalance(&test.rebalance_manager, &instructions, &None);
 }
 
 #[test]
@@ -1554,7 +1562,7 @@ fn unwind_paused_strategy(){
             amount_to_invest,
         ),
     ];
-    defindex_contract.rebalance(&test.rebalance_manager, &instructions);
+    defindex_contract.rebalance(&test.rebalance_manager, &instructions, &None);
 
     let invested_funds = defindex_contract.fetch_total_managed_funds().get(0).unwrap().invested_amount;
     assert_eq!(invested_funds, amount_to_invest);
@@ -1591,7 +1599,7 @@ fn unwind_paused_strategy(){
             amount_to_unwind,
         ),
     ];
-    let unwind_result = defindex_contract.try_rebalance(&test.rebalance_manager, &instructions);
+    let unwind_result = defindex_contract.try_rebalance(&test.rebalance_manager, &instructions, &None);
     
     // Check if invested funds are 0
     let invested_funds = defindex_contract.fetch_total_managed_funds().get(0).unwrap().invested_amount;
@@ -1662,7 +1670,7 @@ fn unwind_wrong_address(){
             amount_to_invest,
         ),
     ];
-    defindex_contract.rebalance(&test.rebalance_manager, &instructions);
+    defindex_contract.rebalance(&test.rebalance_manager, &instructions, &None);
 
     let invested_funds = defindex_contract.fetch_total_managed_funds().get(0).unwrap().invested_amount;
     assert_eq!(invested_funds, amount_to_invest);
@@ -1699,7 +1707,7 @@ fn unwind_wrong_address(){
             amount_to_unwind,
         ),
     ];
-    let unwind_result = defindex_contract.try_rebalance(&test.rebalance_manager, &instructions);
+    let unwind_result = defindex_contract.try_rebalance(&test.rebalance_manager, &instructions, &None);
     
     // Check if invested funds are 0
     let invested_funds = defindex_contract.fetch_total_managed_funds().get(0).unwrap().invested_amount;
@@ -1768,7 +1776,7 @@ fn unwind_negative_or_zero_amount(){
             amount_to_invest,
         ),
     ];
-    defindex_contract.rebalance(&test.rebalance_manager, &instructions);
+    defindex_contract.rebalance(&test.rebalance_manager, &instructions, &None);
 
     let invested_funds = defindex_contract.fetch_total_managed_funds().get(0).unwrap().invested_amount;
     assert_eq!(invested_funds, amount_to_invest);
@@ -1805,7 +1813,7 @@ fn unwind_negative_or_zero_amount(){
             -amount_to_unwind,
         ),
     ];
-    let unwind_result = defindex_contract.try_rebalance(&test.rebalance_manager, &instructions);
+    let unwind_result = defindex_contract.try_rebalance(&test.rebalance_manager, &instructions, &None);
     assert_eq!(unwind_result, Err(Ok(ContractError::AmountNotAllowed)));
    
     // Rebalance with zero amount
@@ -1817,7 +1825,7 @@ fn unwind_negative_or_zero_amount(){
             amount_to_unwind,
         ),
     ];
-    let unwind_result = defindex_contract.try_rebalance(&test.rebalance_manager, &instructions);
+    let unwind_result = defindex_contract.try_rebalance(&test.rebalance_manager, &instructions, &None);
     
     // Check if invested funds are 0
     let invested_funds = defindex_contract.fetch_total_managed_funds().get(0).unwrap().invested_amount;
@@ -1903,12 +1911,13 @@ fn unwind_unauthorized(){
             args: (
                 test.rebalance_manager.clone(),
                 instructions.clone(),
+                None::<u32>,
             )
                 .into_val(&test.env),
             sub_invokes: &[],
         },
     }])
-    .rebalance(&test.rebalance_manager, &instructions);
+    .rebalance(&test.rebalance_manager, &instructions, &None);
 }
 
 #[test]
@@ -1977,7 +1986,7 @@ fn unwind_over_max(){
             amount0,
         ),
     ];
-    defindex_contract.rebalance(&test.rebalance_manager, &instructions);
+    defindex_contract.rebalance(&test.rebalance_manager, &instructions, &None);
 
     let invested_funds = defindex_contract.fetch_total_managed_funds().get(0).unwrap().invested_amount;
     assert_eq!(invested_funds, amount0);
@@ -1992,7 +2001,7 @@ fn unwind_over_max(){
             withdraw_amount,
         ),
     ];
-    let unwind_result = defindex_contract.try_rebalance(&test.rebalance_manager, &instructions);
+    let unwind_result = defindex_contract.try_rebalance(&test.rebalance_manager, &instructions, &None);
 
     // Check that the unwind has no effects
     let invested_funds = defindex_contract.fetch_total_managed_funds().get(0).unwrap().invested_amount;
@@ -2066,7 +2075,7 @@ fn should_report(){
             amount0,
         ),
     ];
-    defindex_contract.rebalance(&test.rebalance_manager, &instructions);
+    defindex_contract.rebalance(&test.rebalance_manager, &instructions, &None);
 
     // Check investment effects
     let invested_funds = defindex_contract.fetch_total_managed_funds().get(0).unwrap().invested_amount;
@@ -2095,7 +2104,7 @@ fn should_report(){
     ];
     let total_managed_funds = defindex_contract.fetch_total_managed_funds();
     std::println!("Total managed funds: {:?}", total_managed_funds);
-    defindex_contract.rebalance(&test.rebalance_manager, &instructions);
+    defindex_contract.rebalance(&test.rebalance_manager, &instructions, &None);
     let total_managed_funds_after_unwind = defindex_contract.fetch_total_managed_funds();
     std::println!("Total managed funds after unwind: {:?}", total_managed_funds_after_unwind);
     // Get report after unwind
@@ -2107,4 +2116,472 @@ fn should_report(){
     };
     assert_eq!(report_after_unwind, expected_report);
     std::println!("Report after unwind: {:?}", report_after_unwind);
-}
\ No newline at end of file
+}
+#[test]
+fn swap_multi_hop_path(){
+    let test = DeFindexVaultTest::setup();
+    test.env.mock_all_auths();
+    let users = DeFindexVaultTest::generate_random_users(&test.env, 1);
+
+    let strategy_params_token_0 = create_strategy_params_token_0(&test);
+    let strategy_params_token_1 = create_strategy_params_token_1(&test);
+
+    let assets: Vec<AssetStrategySet> = sorobanvec![
+        &test.env,
+        AssetStrategySet {
+            address: test.token_0.address.clone(),
+            strategies: strategy_params_token_0.clone()
+        },
+        AssetStrategySet {
+            address: test.token_1.address.clone(),
+            strategies: strategy_params_token_1.clone()
+        },
+    ];
+
+    let mut roles: Map<u32, Address> = Map::new(&test.env);
+    roles.set(RolesDataKey::Manager as u32, test.manager.clone());
+    roles.set(RolesDataKey::EmergencyManager as u32, test.emergency_manager.clone());
+    roles.set(RolesDataKey::VaultFeeReceiver as u32, test.vault_fee_receiver.clone());
+    roles.set(RolesDataKey::RebalanceManager as u32, test.rebalance_manager.clone());
+
+    let mut name_symbol: Map<String, String> = Map::new(&test.env);
+    name_symbol.set(String::from_str(&test.env, "name"), String::from_str(&test.env, "dfToken"));
+    name_symbol.set(String::from_str(&test.env, "symbol"), String::from_str(&test.env, "DFT"));
+
+    let defindex_contract = create_defindex_vault(
+        &test.env,
+        assets,
+        roles,
+        2000u32,
+        test.defindex_protocol_receiver.clone(),
+        2500u32,
+        test.soroswap_router.address.clone(),
+        name_symbol,
+        true
+    );
+
+    let amount0 = 123456789i128;
+    let amount1 = 987654321i128;
+
+    test.token_0_admin_client.mint(&users[0], &amount0);
+    test.token_1_admin_client.mint(&users[0], &amount1);
+
+    defindex_contract.deposit(
+        &sorobanvec![&test.env, amount0, amount1],
+        &sorobanvec![&test.env, amount0, amount1],
+        &users[0],
+        &false,
+    );
+
+    // token_2 is not a vault asset, it is only used as an intermediate hop
+    let path = sorobanvec![
+        &test.env,
+        test.token_0.address.clone(),
+        test.token_2.address.clone(),
+        test.token_1.address.clone(),
+    ];
+
+    let amount_in = 1_000_000i128;
+    let expected_amount_out = test.soroswap_router.router_get_amounts_out(&amount_in, &path).last().unwrap();
+    let instructions = sorobanvec![
+        &test.env,
+        Instruction::SwapExactIn(
+            path.clone(),
+            amount_in,
+            0, // amount_out_min
+            test.env.ledger().timestamp() + 3600u64
+        ),
+    ];
+    defindex_contract.rebalance(&test.rebalance_manager, &instructions, &None);
+
+    assert_eq!(test.token_0.balance(&defindex_contract.address), amount0 - amount_in);
+    assert_eq!(test.token_1.balance(&defindex_contract.address), amount1 + expected_amount_out);
+    assert_eq!(test.token_2.balance(&defindex_contract.address), 0);
+
+    let amount_out = 1_000_000i128;
+    let expected_amount_in = test.soroswap_router.router_get_amounts_in(&amount_out, &path).first().unwrap();
+    let instructions = sorobanvec![
+        &test.env,
+        Instruction::SwapExactOut(
+            path.clone(),
+            amount_out,
+            expected_amount_in, // amount_in_max
+            test.env.ledger().timestamp() + 3600u64
+        ),
+    ];
+    defindex_contract.rebalance(&test.rebalance_manager, &instructions, &None);
+
+    assert_eq!(test.token_0.balance(&defindex_contract.address), amount0 - amount_in - expected_amount_in);
+    assert_eq!(test.token_1.balance(&defindex_contract.address), amount1 + expected_amount_out + amount_out);
+
+    // A path needs at least one hop
+    let instructions = sorobanvec![
+        &test.env,
+        Instruction::SwapExactIn(
+            sorobanvec![&test.env, test.token_0.address.clone()],
+            amount_in,
+            0, // amount_out_min
+            test.env.ledger().timestamp() + 3600u64
+        ),
+    ];
+    let result = defindex_contract.try_rebalance(&test.rebalance_manager, &instructions, &None);
+    assert_eq!(result, Err(Ok(ContractError::SwapExactInError)));
+}
+
+#[test]
+fn zapper(){
+    let test = DeFindexVaultTest::setup();
+    test.env.mock_all_auths();
+    let users = DeFindexVaultTest::generate_random_users(&test.env, 1);
+
+    let strategy_params_token_0 = create_strategy_params_token_0(&test);
+    let strategy_params_token_1 = create_strategy_params_token_1(&test);
+
+    let assets: Vec<AssetStrategySet> = sorobanvec![
+        &test.env,
+        AssetStrategySet {
+            address: test.token_0.address.clone(),
+            strategies: strategy_params_token_0.clone()
+        },
+        AssetStrategySet {
+            address: test.token_1.address.clone(),
+            strategies: strategy_params_token_1.clone()
+        },
+    ];
+
+    let mut roles: Map<u32, Address> = Map::new(&test.env);
+    roles.set(RolesDataKey::Manager as u32, test.manager.clone());
+    roles.set(RolesDataKey::EmergencyManager as u32, test.emergency_manager.clone());
+    roles.set(RolesDataKey::VaultFeeReceiver as u32, test.vault_fee_receiver.clone());
+    roles.set(RolesDataKey::RebalanceManager as u32, test.rebalance_manager.clone());
+
+    let mut name_symbol: Map<String, String> = Map::new(&test.env);
+    name_symbol.set(String::from_str(&test.env, "name"), String::from_str(&test.env, "dfToken"));
+    name_symbol.set(String::from_str(&test.env, "symbol"), String::from_str(&test.env, "DFT"));
+
+    let defindex_contract = create_defindex_vault(
+        &test.env,
+        assets,
+        roles,
+        2000u32,
+        test.defindex_protocol_receiver.clone(),
+        2500u32,
+        test.soroswap_router.address.clone(),
+        name_symbol,
+        true
+    );
+
+    let amount0 = 123456789i128;
+    let amount1 = 987654321i128;
+
+    test.token_0_admin_client.mint(&users[0], &amount0);
+    test.token_1_admin_client.mint(&users[0], &amount1);
+
+    defindex_contract.deposit(
+        &sorobanvec![&test.env, amount0, amount1],
+        &sorobanvec![&test.env, amount0, amount1],
+        &users[0],
+        &false,
+    );
+
+    let strategy_from = test.strategy_client_token_0.address.clone();
+    let strategy_to = test.strategy_client_token_1.address.clone();
+    defindex_contract.rebalance(
+        &test.rebalance_manager,
+        &sorobanvec![
+            &test.env,
+            Instruction::Invest(strategy_from.clone(), amount0),
+        ],
+        &None,
+    );
+
+    let amount = 1_000_000i128;
+    let path = sorobanvec![&test.env, test.token_0.address.clone(), test.token_1.address.clone()];
+    let expected_amount_out = test.soroswap_router.router_get_amounts_out(&amount, &path).last().unwrap();
+    let zap = ZapperInstruction {
+        strategy_from: strategy_from.clone(),
+        strategy_to: strategy_to.clone(),
+        amount,
+        path: path.clone(),
+        amount_out_min: expected_amount_out + 1,
+        deadline: test.env.ledger().timestamp() + 3600u64,
+    };
+
+    // The swap output must meet the slippage bound
+    let result = defindex_contract.try_rebalance(
+        &test.rebalance_manager,
+        &sorobanvec![&test.env, Instruction::Zapper(sorobanvec![&test.env, zap.clone()])],
+        &None,
+    );
+    assert_eq!(result, Err(Ok(ContractError::SwapExactInError)));
+
+    // The path must go from the asset of `strategy_from` to the asset of `strategy_to`
+    let wrong_path_zap = ZapperInstruction {
+        path: sorobanvec![&test.env, test.token_1.address.clone(), test.token_0.address.clone()],
+        ..zap.clone()
+    };
+    let result = defindex_contract.try_rebalance(
+        &test.rebalance_manager,
+        &sorobanvec![&test.env, Instruction::Zapper(sorobanvec![&test.env, wrong_path_zap])],
+        &None,
+    );
+    assert_eq!(result, Err(Ok(ContractError::UnsupportedAsset)));
+
+    let zap = ZapperInstruction {
+        amount_out_min: expected_amount_out,
+        ..zap
+    };
+    defindex_contract.rebalance(
+        &test.rebalance_manager,
+        &sorobanvec![&test.env, Instruction::Zapper(sorobanvec![&test.env, zap])],
+        &None,
+    );
+
+    // Nothing is left idle
+    assert_eq!(test.token_0.balance(&defindex_contract.address), 0);
+    assert_eq!(test.token_1.balance(&defindex_contract.address), amount1);
+    assert_eq!(test.strategy_client_token_0.balance(&defindex_contract.address), amount0 - amount);
+    assert_eq!(test.strategy_client_token_1.balance(&defindex_contract.address), expected_amount_out);
+
+    let report = test.env.as_contract(&defindex_contract.address, || storage::get_report(&test.env, &strategy_to));
+    assert_eq!(report.prev_balance, expected_amount_out);
+}
+
+#[test]
+fn max_loss(){
+    let test = DeFindexVaultTest::setup();
+    test.env.mock_all_auths();
+    let users = DeFindexVaultTest::generate_random_users(&test.env, 1);
+
+    let strategy_params_token_0 = create_strategy_params_token_0(&test);
+    let strategy_params_token_1 = create_strategy_params_token_1(&test);
+
+    let assets: Vec<AssetStrategySet> = sorobanvec![
+        &test.env,
+        AssetStrategySet {
+            address: test.token_0.address.clone(),
+            strategies: strategy_params_token_0.clone()
+        },
+        AssetStrategySet {
+            address: test.token_1.address.clone(),
+            strategies: strategy_params_token_1.clone()
+        },
+    ];
+
+    let mut roles: Map<u32, Address> = Map::new(&test.env);
+    roles.set(RolesDataKey::Manager as u32, test.manager.clone());
+    roles.set(RolesDataKey::EmergencyManager as u32, test.emergency_manager.clone());
+    roles.set(RolesDataKey::VaultFeeReceiver as u32, test.vault_fee_receiver.clone());
+    roles.set(RolesDataKey::RebalanceManager as u32, test.rebalance_manager.clone());
+
+    let mut name_symbol: Map<String, String> = Map::new(&test.env);
+    name_symbol.set(String::from_str(&test.env, "name"), String::from_str(&test.env, "dfToken"));
+    name_symbol.set(String::from_str(&test.env, "symbol"), String::from_str(&test.env, "DFT"));
+
+    let defindex_contract = create_defindex_vault(
+        &test.env,
+        assets,
+        roles,
+        2000u32,
+        test.defindex_protocol_receiver.clone(),
+        2500u32,
+        test.soroswap_router.address.clone(),
+        name_symbol,
+        true
+    );
+
+    let amount0 = 123456789i128;
+    let amount1 = 987654321i128;
+
+    test.token_0_admin_client.mint(&users[0], &amount0);
+    test.token_1_admin_client.mint(&users[0], &amount1);
+
+    defindex_contract.deposit(
+        &sorobanvec![&test.env, amount0, amount1],
+        &sorobanvec![&test.env, amount0, amount1],
+        &users[0],
+        &false,
+    );
+
+    // Swapping every token_0 pays the 0.3% pool fee on a third of the vault's value, a loss of about 10 bps
+    let instructions = sorobanvec![
+        &test.env,
+        Instruction::SwapExactIn(
+            sorobanvec![&test.env, test.token_0.address.clone(), test.token_1.address.clone()],
+            amount0,
+            0, // amount_out_min
+            test.env.ledger().timestamp() + 3600u64
+        ),
+    ];
+
+    let result = defindex_contract.try_rebalance(&test.rebalance_manager, &instructions, &Some(5u32));
+    assert_eq!(result, Err(Ok(ContractError::MaxLossExceeded)));
+    assert_eq!(test.token_0.balance(&defindex_contract.address), amount0);
+    assert_eq!(test.token_1.balance(&defindex_contract.address), amount1);
+
+    defindex_contract.rebalance(&test.rebalance_manager, &instructions, &Some(20u32));
+    assert_eq!(test.token_0.balance(&defindex_contract.address), 0);
+    assert!(test.token_1.balance(&defindex_contract.address) > amount1);
+
+    // Instructions that keep the vault's value pass even with no tolerance
+    let amount_to_invest = 100_000_000i128;
+    let instructions = sorobanvec![
+        &test.env,
+        Instruction::Invest(strategy_params_token_1.first().unwrap().address.clone(), amount_to_invest),
+    ];
+    defindex_contract.rebalance(&test.rebalance_manager, &instructions, &Some(0u32));
+    assert_eq!(test.strategy_client_token_1.balance(&defindex_contract.address), amount_to_invest);
+}
+
+#[test]
+fn simulate_rebalance(){
+    let test = DeFindexVaultTest::setup();
+    test.env.mock_all_auths();
+    let users = DeFindexVaultTest::generate_random_users(&test.env, 1);
+
+    let strategy_params_token_0 = create_strategy_params_token_0(&test);
+    let strategy_params_token_1 = create_strategy_params_token_1(&test);
+
+    let assets: Vec<AssetStrategySet> = sorobanvec![
+        &test.env,
+        AssetStrategySet {
+            address: test.token_0.address.clone(),
+            strategies: strategy_params_token_0.clone()
+        },
+        AssetStrategySet {
+            address: test.token_1.address.clone(),
+            strategies: strategy_params_token_1.clone()
+        },
+    ];
+
+    let mut roles: Map<u32, Address> = Map::new(&test.env);
+    roles.set(RolesDataKey::Manager as u32, test.manager.clone());
+    roles.set(RolesDataKey::EmergencyManager as u32, test.emergency_manager.clone());
+    roles.set(RolesDataKey::VaultFeeReceiver as u32, test.vault_fee_receiver.clone());
+    roles.set(RolesDataKey::RebalanceManager as u32, test.rebalance_manager.clone());
+
+    let mut name_symbol: Map<String, String> = Map::new(&test.env);
+    name_symbol.set(String::from_str(&test.env, "name"), String::from_str(&test.env, "dfToken"));
+    name_symbol.set(String::from_str(&test.env, "symbol"), String::from_str(&test.env, "DFT"));
+
+    let defindex_contract = create_defindex_vault(
+        &test.env,
+        assets,
+        roles,
+        2000u32,
+        test.defindex_protocol_receiver.clone(),
+        2500u32,
+        test.soroswap_router.address.clone(),
+        name_symbol,
+        true
+    );
+
+    let amount0 = 123456789i128;
+    let amount1 = 987654321i128;
+
+    test.token_0_admin_client.mint(&users[0], &amount0);
+    test.token_1_admin_client.mint(&users[0], &amount1);
+
+    defindex_contract.deposit(
+        &sorobanvec![&test.env, amount0, amount1],
+        &sorobanvec![&test.env, amount0, amount1],
+        &users[0],
+        &false,
+    );
+
+    let strategy_0 = strategy_params_token_0.first().unwrap().address.clone();
+    let strategy_1 = strategy_params_token_1.first().unwrap().address.clone();
+    let path = sorobanvec![&test.env, test.token_0.address.clone(), test.token_1.address.clone()];
+    let amount_in = 20_000_000i128;
+    let expected_amount_out = test.soroswap_router.router_get_amounts_out(&amount_in, &path).last().unwrap();
+
+    let instructions = sorobanvec![
+        &test.env,
+        Instruction::Invest(strategy_0.clone(), 100_000_000),
+        Instruction::SwapExactIn(
+            path.clone(),
+            amount_in,
+            0, // amount_out_min
+            test.env.ledger().timestamp() + 3600u64
+        ),
+        Instruction::Invest(strategy_1.clone(), 500_000_000),
+        Instruction::Unwind(strategy_0.clone(), 30_000_000),
+    ];
+
+    let simulation = defindex_contract.simulate_rebalance(&instructions);
+
+    // Nothing is executed
+    assert_eq!(test.token_0.balance(&defindex_contract.address), amount0);
+    assert_eq!(test.token_1.balance(&defindex_contract.address), amount1);
+
+    assert_eq!(simulation.swaps.len(), 1);
+    let swap = simulation.swaps.get(0).unwrap();
+    assert_eq!(swap.token_in, test.token_0.address);
+    assert_eq!(swap.token_out, test.token_1.address);
+    assert_eq!(swap.amount_in, amount_in);
+    assert_eq!(swap.amount_out, expected_amount_out);
+
+    let expected_allocations: Vec<CurrentAssetInvestmentAllocation> = sorobanvec![
+        &test.env,
+        CurrentAssetInvestmentAllocation {
+            asset: test.token_0.address.clone(),
+            total_amount: amount0 - amount_in,
+            idle_amount: amount0 - amount_in - 70_000_000,
+            invested_amount: 70_000_000,
+            strategy_allocations: sorobanvec![
+                &test.env,
+                StrategyAllocation {
+                    strategy_address: strategy_0.clone(),
+                    amount: 70_000_000,
+                    paused: false,
+                },
+            ],
+        },
+        CurrentAssetInvestmentAllocation {
+            asset: test.token_1.address.clone(),
+            total_amount: amount1 + expected_amount_out,
+            idle_amount: amount1 + expected_amount_out - 500_000_000,
+            invested_amount: 500_000_000,
+            strategy_allocations: sorobanvec![
+                &test.env,
+                StrategyAllocation {
+                    strategy_address: strategy_1.clone(),
+                    amount: 500_000_000,
+                    paused: false,
+                },
+            ],
+        },
+    ];
+    assert_eq!(simulation.allocations, expected_allocations);
+
+    // A rebalance can still be planned while rebalances are paused
+    defindex_contract.set_pause_flags(&PauseFlags { deposits: false, withdrawals: false, rebalances: true });
+    assert_eq!(defindex_contract.simulate_rebalance(&instructions), simulation);
+    let result = defindex_contract.try_rebalance(&test.rebalance_manager, &instructions, &None);
+    assert_eq!(result, Err(Err(VaultError::RebalancesPaused.into())));
+    defindex_contract.set_pause_flags(&PauseFlags { deposits: false, withdrawals: false, rebalances: false });
+
+    // The simulation matches the executed rebalance
+    defindex_contract.rebalance(&test.rebalance_manager, &instructions, &None);
+    assert_eq!(defindex_contract.fetch_total_managed_funds(), expected_allocations);
+
+    // Failing instructions return the error the rebalance would return
+    let instructions = sorobanvec![
+        &test.env,
+        Instruction::Invest(strategy_0.clone(), amount0),
+    ];
+    let result = defindex_contract.try_simulate_rebalance(&instructions);
+    assert_eq!(result, Err(Ok(ContractError::InsufficientBalance)));
+
+    let instructions = sorobanvec![
+        &test.env,
+        Instruction::SwapExactIn(
+            path.clone(),
+            amount_in,
+            expected_amount_out * 2, // amount_out_min
+            test.env.ledger().timestamp() + 3600u64
+        ),
+    ];
+    let result = defindex_contract.try_simulate_rebalance(&instructions);
+    assert_eq!(result, Err(Ok(ContractError::InsufficientOutputAmount)));
+}
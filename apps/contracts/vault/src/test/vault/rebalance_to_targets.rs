@@ -0,0 +1,332 @@
+use soroban_sdk::{vec as sorobanvec, Address, Map, String, Vec};
+
+use crate::test::defindex_vault::{
+    AssetStrategySet, AssetTargetAllocation, ContractError, Instruction, RolesDataKey, StrategyTarget,
+};
+use crate::test::{
+    create_defindex_vault, create_strategy_params_token_0, create_strategy_params_token_1,
+    create_unsafe_strategy_params_token_0, DeFindexVaultTest,
+};
+
+#[test]
+fn invest_idle_funds_to_targets() {
+    let test = DeFindexVaultTest::setup();
+    test.env.mock_all_auths();
+    let mut strategy_params_token_0 = create_strategy_params_token_0(&test);
+    strategy_params_token_0.append(&create_unsafe_strategy_params_token_0(&test));
+    let strategy_params_token_1 = create_strategy_params_token_1(&test);
+
+    let assets: Vec<AssetStrategySet> = sorobanvec![
+        &test.env,
+        AssetStrategySet {
+            address: test.token_0.address.clone(),
+            strategies: strategy_params_token_0.clone()
+        },
+        AssetStrategySet {
+            address: test.token_1.address.clone(),
+            strategies: strategy_params_token_1.clone()
+        }
+    ];
+
+    let mut roles: Map<u32, Address> = Map::new(&test.env);
+    roles.set(RolesDataKey::Manager as u32, test.manager.clone());
+    roles.set(RolesDataKey::EmergencyManager as u32, test.emergency_manager.clone());
+    roles.set(RolesDataKey::VaultFeeReceiver as u32, test.vault_fee_receiver.clone());
+    roles.set(RolesDataKey::RebalanceManager as u32, test.rebalance_manager.clone());
+
+    let mut name_symbol: Map<String, String> = Map::new(&test.env);
+    name_symbol.set(String::from_str(&test.env, "name"), String::from_str(&test.env, "dfToken"));
+    name_symbol.set(String::from_str(&test.env, "symbol"), String::from_str(&test.env, "DFT"));
+
+    let defindex_contract = create_defindex_vault(
+        &test.env,
+        assets,
+        roles,
+        2000u32,
+        test.defindex_protocol_receiver.clone(),
+        2500u32,
+        test.soroswap_router.address.clone(),
+        name_symbol,
+        true
+    );
+
+    let amount0 = 1_000_000_000i128;
+    let amount1 = 4_000_000_000i128;
+
+    let users = DeFindexVaultTest::generate_random_users(&test.env, 1);
+    test.token_0_admin_client.mint(&users[0], &amount0);
+    test.token_1_admin_client.mint(&users[0], &amount1);
+
+    defindex_contract.deposit(
+        &sorobanvec![&test.env, amount0, amount1],
+        &sorobanvec![&test.env, amount0, amount1],
+        &users[0],
+        &false,
+    );
+
+    defindex_contract.set_target_allocation(&AssetTargetAllocation {
+        asset: test.token_0.address.clone(),
+        idle_bps: 1000,
+        strategy_targets: sorobanvec![
+            &test.env,
+            StrategyTarget {
+                strategy_address: test.strategy_client_token_0.address.clone(),
+                weight_bps: 6000,
+            },
+            StrategyTarget {
+                strategy_address: test.unsafe_strategy_client_token_0.address.clone(),
+                weight_bps: 3000,
+            },
+        ],
+    });
+
+    // Anyone can trigger the rebalance
+    test.env.set_auths(&[]);
+    let executed = defindex_contract.rebalance_to_targets(&10u32);
+    assert_eq!(
+        executed,
+        sorobanvec![
+            &test.env,
+            Instruction::Invest(test.strategy_client_token_0.address.clone(), 600_000_000i128),
+            Instruction::Invest(test.unsafe_strategy_client_token_0.address.clone(), 300_000_000i128),
+        ]
+    );
+
+    assert_eq!(test.token_0.balance(&test.strategy_client_token_0.address), 600_000_000i128);
+    assert_eq!(test.token_0.balance(&test.unsafe_strategy_client_token_0.address), 300_000_000i128);
+    assert_eq!(test.token_0.balance(&defindex_contract.address), 100_000_000i128);
+
+    // token_1 has no target allocation and is left untouched
+    assert_eq!(test.token_1.balance(&defindex_contract.address), amount1);
+
+    // Nothing left to do
+    let executed = defindex_contract.rebalance_to_targets(&10u32);
+    assert_eq!(executed.len(), 0);
+}
+
+#[test]
+fn move_funds_between_strategies_bounded() {
+    let test = DeFindexVaultTest::setup();
+    test.env.mock_all_auths();
+    let mut strategy_params_token_0 = create_strategy_params_token_0(&test);
+    strategy_params_token_0.append(&create_unsafe_strategy_params_token_0(&test));
+    let strategy_params_token_1 = create_strategy_params_token_1(&test);
+
+    let assets: Vec<AssetStrategySet> = sorobanvec![
+        &test.env,
+        AssetStrategySet {
+            address: test.token_0.address.clone(),
+            strategies: strategy_params_token_0.clone()
+        },
+        AssetStrategySet {
+            address: test.token_1.address.clone(),
+            strategies: strategy_params_token_1.clone()
+        }
+    ];
+
+    let mut roles: Map<u32, Address> = Map::new(&test.env);
+    roles.set(RolesDataKey::Manager as u32, test.manager.clone());
+    roles.set(RolesDataKey::EmergencyManager as u32, test.emergency_manager.clone());
+    roles.set(RolesDataKey::VaultFeeReceiver as u32, test.vault_fee_receiver.clone());
+    roles.set(RolesDataKey::RebalanceManager as u32, test.rebalance_manager.clone());
+
+    let mut name_symbol: Map<String, String> = Map::new(&test.env);
+    name_symbol.set(String::from_str(&test.env, "name"), String::from_str(&test.env, "dfToken"));
+    name_symbol.set(String::from_str(&test.env, "symbol"), String::from_str(&test.env, "DFT"));
+
+    let defindex_contract = create_defindex_vault(
+        &test.env,
+        assets,
+        roles,
+        2000u32,
+        test.defindex_protocol_receiver.clone(),
+        2500u32,
+        test.soroswap_router.address.clone(),
+        name_symbol,
+        true
+    );
+
+    let amount0 = 1_000_000_000i128;
+    let amount1 = 4_000_000_000i128;
+
+    let users = DeFindexVaultTest::generate_random_users(&test.env, 1);
+    test.token_0_admin_client.mint(&users[0], &amount0);
+    test.token_1_admin_client.mint(&users[0], &amount1);
+
+    defindex_contract.deposit(
+        &sorobanvec![&test.env, amount0, amount1],
+        &sorobanvec![&test.env, amount0, amount1],
+        &users[0],
+        &false,
+    );
+
+    defindex_contract.set_target_allocation(&AssetTargetAllocation {
+        asset: test.token_0.address.clone(),
+        idle_bps: 1000,
+        strategy_targets: sorobanvec![
+            &test.env,
+            StrategyTarget {
+                strategy_address: test.strategy_client_token_0.address.clone(),
+                weight_bps: 6000,
+            },
+            StrategyTarget {
+                strategy_address: test.unsafe_strategy_client_token_0.address.clone(),
+                weight_bps: 3000,
+            },
+        ],
+    });
+    defindex_contract.rebalance_to_targets(&10u32);
+
+    defindex_contract.set_target_allocation(&AssetTargetAllocation {
+        asset: test.token_0.address.clone(),
+        idle_bps: 1000,
+        strategy_targets: sorobanvec![
+            &test.env,
+            StrategyTarget {
+                strategy_address: test.strategy_client_token_0.address.clone(),
+                weight_bps: 2000,
+            },
+            StrategyTarget {
+                strategy_address: test.unsafe_strategy_client_token_0.address.clone(),
+                weight_bps: 7000,
+            },
+        ],
+    });
+
+    // Only the unwind fits in this call
+    let executed = defindex_contract.rebalance_to_targets(&1u32);
+    assert_eq!(
+        executed,
+        sorobanvec![
+            &test.env,
+            Instruction::Unwind(test.strategy_client_token_0.address.clone(), 400_000_000i128),
+        ]
+    );
+    assert_eq!(test.token_0.balance(&test.strategy_client_token_0.address), 200_000_000i128);
+    assert_eq!(test.token_0.balance(&defindex_contract.address), 500_000_000i128);
+
+    // The next call continues with the investment
+    let executed = defindex_contract.rebalance_to_targets(&1u32);
+    assert_eq!(
+        executed,
+        sorobanvec![
+            &test.env,
+            Instruction::Invest(test.unsafe_strategy_client_token_0.address.clone(), 400_000_000i128),
+        ]
+    );
+    assert_eq!(test.token_0.balance(&test.strategy_client_token_0.address), 200_000_000i128);
+    assert_eq!(test.token_0.balance(&test.unsafe_strategy_client_token_0.address), 700_000_000i128);
+    assert_eq!(test.token_0.balance(&defindex_contract.address), 100_000_000i128);
+}
+
+#[test]
+fn within_tolerance_band() {
+    let test = DeFindexVaultTest::setup();
+    test.env.mock_all_auths();
+    let mut strategy_params_token_0 = create_strategy_params_token_0(&test);
+    strategy_params_token_0.append(&create_unsafe_strategy_params_token_0(&test));
+    let strategy_params_token_1 = create_strategy_params_token_1(&test);
+
+    let assets: Vec<AssetStrategySet> = sorobanvec![
+        &test.env,
+        AssetStrategySet {
+            address: test.token_0.address.clone(),
+            strategies: strategy_params_token_0.clone()
+        },
+        AssetStrategySet {
+            address: test.token_1.address.clone(),
+            strategies: strategy_params_token_1.clone()
+        }
+    ];
+
+    let mut roles: Map<u32, Address> = Map::new(&test.env);
+    roles.set(RolesDataKey::Manager as u32, test.manager.clone());
+    roles.set(RolesDataKey::EmergencyManager as u32, test.emergency_manager.clone());
+    roles.set(RolesDataKey::VaultFeeReceiver as u32, test.vault_fee_receiver.clone());
+    roles.set(RolesDataKey::RebalanceManager as u32, test.rebalance_manager.clone());
+
+    let mut name_symbol: Map<String, String> = Map::new(&test.env);
+    name_symbol.set(String::from_str(&test.env, "name"), String::from_str(&test.env, "dfToken"));
+    name_symbol.set(String::from_str(&test.env, "symbol"), String::from_str(&test.env, "DFT"));
+
+    let defindex_contract = create_defindex_vault(
+        &test.env,
+        assets,
+        roles,
+        2000u32,
+        test.defindex_protocol_receiver.clone(),
+        2500u32,
+        test.soroswap_router.address.clone(),
+        name_symbol,
+        true
+    );
+
+    let amount0 = 1_000_000_000i128;
+    let amount1 = 4_000_000_000i128;
+
+    let users = DeFindexVaultTest::generate_random_users(&test.env, 1);
+    test.token_0_admin_client.mint(&users[0], &amount0);
+    test.token_1_admin_client.mint(&users[0], &amount1);
+
+    defindex_contract.deposit(
+        &sorobanvec![&test.env, amount0, amount1],
+        &sorobanvec![&test.env, amount0, amount1],
+        &users[0],
+        &false,
+    );
+
+    assert_eq!(defindex_contract.get_rebalance_tolerance(), 100u32);
+
+    defindex_contract.set_target_allocation(&AssetTargetAllocation {
+        asset: test.token_0.address.clone(),
+        idle_bps: 1000,
+        strategy_targets: sorobanvec![
+            &test.env,
+            StrategyTarget {
+                strategy_address: test.strategy_client_token_0.address.clone(),
+                weight_bps: 6000,
+            },
+            StrategyTarget {
+                strategy_address: test.unsafe_strategy_client_token_0.address.clone(),
+                weight_bps: 3000,
+            },
+        ],
+    });
+    defindex_contract.rebalance_to_targets(&10u32);
+
+    // Strategies deviate by 3% of the asset total managed funds
+    defindex_contract.set_target_allocation(&AssetTargetAllocation {
+        asset: test.token_0.address.clone(),
+        idle_bps: 1000,
+        strategy_targets: sorobanvec![
+            &test.env,
+            StrategyTarget {
+                strategy_address: test.strategy_client_token_0.address.clone(),
+                weight_bps: 6300,
+            },
+            StrategyTarget {
+                strategy_address: test.unsafe_strategy_client_token_0.address.clone(),
+                weight_bps: 2700,
+            },
+        ],
+    });
+
+    defindex_contract.set_rebalance_tolerance(&500u32);
+    assert_eq!(defindex_contract.get_rebalance_tolerance(), 500u32);
+    let executed = defindex_contract.rebalance_to_targets(&10u32);
+    assert_eq!(executed.len(), 0);
+
+    defindex_contract.set_rebalance_tolerance(&200u32);
+    let executed = defindex_contract.rebalance_to_targets(&10u32);
+    assert_eq!(
+        executed,
+        sorobanvec![
+            &test.env,
+            Instruction::Unwind(test.unsafe_strategy_client_token_0.address.clone(), 30_000_000i128),
+            Instruction::Invest(test.strategy_client_token_0.address.clone(), 30_000_000i128),
+        ]
+    );
+
+    let result = defindex_contract.try_set_rebalance_tolerance(&10_001u32);
+    assert_eq!(result, Err(Ok(ContractError::InvalidRatio)));
+}
@@ -73,7 +73,7 @@ fn rescue_success() {
         &test.env,
         Instruction::Invest(test.strategy_client_token_0.address.clone(), amount),
     ];
-    defindex_contract.rebalance(&test.rebalance_manager, &invest_instructions);
+    defindex_contract.rebalance(&test.rebalance_manager, &invest_instructions, &None);
 
     // Balance of the token_0 on the vault should be 0
     let vault_balance_of_token = test.token_0.balance(&defindex_contract.address);
@@ -157,7 +157,7 @@ fn rescue_errors() {
         &test.env,
         Instruction::Invest(test.strategy_client_token_0.address.clone(), amount),
     ];
-    defindex_contract.rebalance(&test.rebalance_manager, &invest_instructions);
+    defindex_contract.rebalance(&test.rebalance_manager, &invest_instructions, &None);
 
     // rescue with wrong strategy address
     let result = defindex_contract.try_rescue(
@@ -244,7 +244,7 @@ fn pause_then_rescue() {
         &test.env,
         Instruction::Invest(test.strategy_client_token_0.address.clone(), amount),
     ];
-    defindex_contract.rebalance(&test.rebalance_manager, &invest_instructions);
+    defindex_contract.rebalance(&test.rebalance_manager, &invest_instructions, &None);
 
     // Balance of the token_0 on the vault should be 0
     let vault_balance_of_token = test.token_0.balance(&defindex_contract.address);
@@ -344,7 +344,7 @@ fn distribute_fees_on_rescue() {
         &test.env,
         Instruction::Invest(test.strategy_client_token_0.address.clone(), amount),
     ];
-    defindex_contract.rebalance(&test.rebalance_manager, &invest_instructions);
+    defindex_contract.rebalance(&test.rebalance_manager, &invest_instructions, &None);
 
     // Balance of the token_0 on the vault should be 0
     let vault_balance_of_token = test.token_0.balance(&defindex_contract.address);
@@ -361,7 +361,7 @@ fn distribute_fees_on_rescue() {
     test.strategy_client_token_0.deposit(&10_0_000_000i128, &defindex_contract.address);
     defindex_contract.report();
 
-    defindex_contract.lock_fees(&None);
+    defindex_contract.lock_fees();
 
     let balance_defindex_protocol = test.token_0.balance(&test.defindex_protocol_receiver);
     assert_eq!(balance_defindex_protocol, 0i128);
@@ -448,7 +448,7 @@ fn should_report() {
         &test.env,
         Instruction::Invest(test.strategy_client_token_0.address.clone(), amount),
         ];
-        defindex_contract.rebalance(&test.rebalance_manager, &invest_instructions);
+        defindex_contract.rebalance(&test.rebalance_manager, &invest_instructions, &None);
         
     
     let initial_report = defindex_contract.env.as_contract(&defindex_contract.address, || storage::get_report(&test.env, &test.strategy_client_token_0.address.clone()));
@@ -75,7 +75,7 @@ fn swap_exact_in_error(){
   let mut path: Vec<Address> = Vec::new(&test.env);
   path.push_back(test.token_0.address.clone());
   path.push_back(test.token_1.address.clone());
-  let _result = test.env.as_contract(&defindex_contract.address, || router::internal_swap_exact_tokens_for_tokens(&test.env, &test.token_0.address.clone(), &test.token_1.address.clone(), &amount_in, &amount_out_min, &deadline));
+  let _result = test.env.as_contract(&defindex_contract.address, || router::internal_swap_exact_tokens_for_tokens(&test.env, &path, &amount_in, &amount_out_min, &deadline));
 }
 
 #[test]
@@ -144,7 +144,7 @@ fn swap_exact_out_error(){
   let mut path: Vec<Address> = Vec::new(&test.env);
   path.push_back(test.token_0.address.clone());
   path.push_back(test.token_1.address.clone());
-  let _result = test.env.as_contract(&defindex_contract.address, || router::internal_swap_tokens_for_exact_tokens(&test.env, &test.token_0.address.clone(), &test.token_1.address.clone(), &amount_out, &amount_in_max, &deadline));
+  let _result = test.env.as_contract(&defindex_contract.address, || router::internal_swap_tokens_for_exact_tokens(&test.env, &path, &amount_out, &amount_in_max, &deadline));
 }
 #[test]
 #[should_panic(expected = "HostError: Error(Contract, #200)")]
@@ -212,5 +212,5 @@ fn same_asset_swap(){
   let mut path: Vec<Address> = Vec::new(&test.env);
   path.push_back(test.token_0.address.clone());
   path.push_back(test.token_1.address.clone());
-  let _result = test.env.as_contract(&defindex_contract.address, || router::internal_swap_tokens_for_exact_tokens(&test.env, &test.token_0.address.clone(), &test.token_0.address.clone(), &amount_out, &amount_in_max, &deadline));
+  let _result = test.env.as_contract(&defindex_contract.address, || router::internal_swap_tokens_for_exact_tokens(&test.env, &sorobanvec![&test.env, test.token_0.address.clone(), test.token_0.address.clone()], &amount_out, &amount_in_max, &deadline));
 }
\ No newline at end of file
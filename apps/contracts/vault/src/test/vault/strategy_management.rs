@@ -0,0 +1,192 @@
+use soroban_sdk::{testutils::Ledger, vec as sorobanvec, Address, Map, String, Vec};
+
+use crate::test::defindex_vault::{
+    AssetStrategySet, AssetTargetAllocation, ContractError, Instruction, ProposalAction, RolesDataKey,
+    Strategy, StrategyTarget, VaultError,
+};
+use crate::test::{
+    create_defindex_vault, create_strategy_params_token_0, create_unsafe_strategy_params_token_0,
+    DeFindexVaultTest,
+};
+
+#[test]
+fn add_and_remove_strategy() {
+    let test = DeFindexVaultTest::setup();
+    test.env.mock_all_auths();
+    let strategy_params_token_0 = create_strategy_params_token_0(&test);
+
+    let assets: Vec<AssetStrategySet> = sorobanvec![
+        &test.env,
+        AssetStrategySet {
+            address: test.token_0.address.clone(),
+            strategies: strategy_params_token_0.clone()
+        }
+    ];
+
+    let mut roles: Map<u32, Address> = Map::new(&test.env);
+    roles.set(RolesDataKey::Manager as u32, test.manager.clone());
+    roles.set(RolesDataKey::EmergencyManager as u32, test.emergency_manager.clone());
+    roles.set(RolesDataKey::VaultFeeReceiver as u32, test.vault_fee_receiver.clone());
+    roles.set(RolesDataKey::RebalanceManager as u32, test.rebalance_manager.clone());
+
+    let mut name_symbol: Map<String, String> = Map::new(&test.env);
+    name_symbol.set(String::from_str(&test.env, "name"), String::from_str(&test.env, "dfToken"));
+    name_symbol.set(String::from_str(&test.env, "symbol"), String::from_str(&test.env, "DFT"));
+
+    let defindex_contract = create_defindex_vault(
+        &test.env,
+        assets,
+        roles,
+        2000u32,
+        test.defindex_protocol_receiver.clone(),
+        2500u32,
+        test.soroswap_router.address.clone(),
+        name_symbol,
+        true
+    );
+
+    let amount = 1_000_000_000i128;
+    let users = DeFindexVaultTest::generate_random_users(&test.env, 1);
+    test.token_0_admin_client.mint(&users[0], &amount);
+    defindex_contract.deposit(&sorobanvec![&test.env, amount], &sorobanvec![&test.env, amount], &users[0], &false);
+
+    let new_strategy = test.unsafe_strategy_client_token_0.address.clone();
+    let add_strategy = ProposalAction::AddStrategy(
+        test.token_0.address.clone(),
+        create_unsafe_strategy_params_token_0(&test).get(0).unwrap(),
+    );
+
+    // The emergency manager can veto a new strategy
+    let proposal_id = defindex_contract.propose(&add_strategy);
+    defindex_contract.veto_proposal(&proposal_id);
+    test.env.ledger().set_timestamp(test.env.ledger().timestamp() + defindex_contract.get_manager_timelock());
+    let result = defindex_contract.try_execute_proposal(&proposal_id);
+    assert_eq!(result, Err(Err(VaultError::ProposalNotFound.into())));
+    assert_eq!(defindex_contract.get_assets().get(0).unwrap().strategies.len(), 1);
+
+    // Otherwise it is only added once the timelock has elapsed
+    let proposal_id = defindex_contract.add_strategy(
+        &test.token_0.address,
+        &create_unsafe_strategy_params_token_0(&test).get(0).unwrap(),
+    );
+    assert_eq!(defindex_contract.get_proposal(&proposal_id).action, add_strategy);
+    let result = defindex_contract.try_execute_proposal(&proposal_id);
+    assert_eq!(result, Err(Err(VaultError::ProposalNotReady.into())));
+    assert_eq!(defindex_contract.get_assets().get(0).unwrap().strategies.len(), 1);
+
+    test.env.ledger().set_timestamp(defindex_contract.get_proposal(&proposal_id).executable_at);
+    defindex_contract.execute_proposal(&proposal_id);
+    let assets = defindex_contract.get_assets();
+    assert_eq!(assets.get(0).unwrap().strategies.len(), 2);
+    assert_eq!(assets.get(0).unwrap().strategies.get(1).unwrap().address, new_strategy);
+
+    // The new strategy can be used once added
+    defindex_contract.rebalance(
+        &test.rebalance_manager,
+        &sorobanvec![&test.env, Instruction::Invest(new_strategy.clone(), 400_000_000i128)],
+        &None,
+    );
+    assert_eq!(test.token_0.balance(&new_strategy), 400_000_000i128);
+
+    defindex_contract.set_target_allocation(&AssetTargetAllocation {
+        asset: test.token_0.address.clone(),
+        idle_bps: 1000,
+        strategy_targets: sorobanvec![
+            &test.env,
+            StrategyTarget {
+                strategy_address: test.strategy_client_token_0.address.clone(),
+                weight_bps: 5000,
+            },
+            StrategyTarget {
+                strategy_address: new_strategy.clone(),
+                weight_bps: 4000,
+            }
+        ],
+    });
+    defindex_contract.set_withdraw_order(&test.token_0.address, &sorobanvec![&test.env, new_strategy.clone()]);
+
+    // A strategy holding funds cannot be removed
+    let result = defindex_contract.try_remove_strategy(&new_strategy);
+    assert_eq!(result, Err(Ok(ContractError::StrategyWithdrawError)));
+
+    defindex_contract.rebalance(
+        &test.rebalance_manager,
+        &sorobanvec![&test.env, Instruction::Unwind(new_strategy.clone(), 400_000_000i128)],
+        &None,
+    );
+    defindex_contract.remove_strategy(&new_strategy);
+
+    let assets = defindex_contract.get_assets();
+    assert_eq!(assets.get(0).unwrap().strategies.len(), 1);
+    assert_eq!(defindex_contract.report().len(), 1);
+    assert_eq!(defindex_contract.get_withdraw_order(&test.token_0.address), sorobanvec![&test.env]);
+
+    let target_allocation = defindex_contract.get_target_allocation(&test.token_0.address).unwrap();
+    assert_eq!(target_allocation.idle_bps, 5000);
+    assert_eq!(target_allocation.strategy_targets.len(), 1);
+
+    let total_managed_funds = defindex_contract.fetch_total_managed_funds();
+    assert_eq!(total_managed_funds.get(0).unwrap().total_amount, amount);
+}
+
+#[test]
+fn invalid_add_and_remove_strategy() {
+    let test = DeFindexVaultTest::setup();
+    test.env.mock_all_auths();
+    let strategy_params_token_0 = create_strategy_params_token_0(&test);
+
+    let assets: Vec<AssetStrategySet> = sorobanvec![
+        &test.env,
+        AssetStrategySet {
+            address: test.token_0.address.clone(),
+            strategies: strategy_params_token_0.clone()
+        }
+    ];
+
+    let mut roles: Map<u32, Address> = Map::new(&test.env);
+    roles.set(RolesDataKey::Manager as u32, test.manager.clone());
+    roles.set(RolesDataKey::EmergencyManager as u32, test.emergency_manager.clone());
+    roles.set(RolesDataKey::VaultFeeReceiver as u32, test.vault_fee_receiver.clone());
+    roles.set(RolesDataKey::RebalanceManager as u32, test.rebalance_manager.clone());
+
+    let mut name_symbol: Map<String, String> = Map::new(&test.env);
+    name_symbol.set(String::from_str(&test.env, "name"), String::from_str(&test.env, "dfToken"));
+    name_symbol.set(String::from_str(&test.env, "symbol"), String::from_str(&test.env, "DFT"));
+
+    let defindex_contract = create_defindex_vault(
+        &test.env,
+        assets,
+        roles,
+        2000u32,
+        test.defindex_protocol_receiver.clone(),
+        2500u32,
+        test.soroswap_router.address.clone(),
+        name_symbol,
+        true
+    );
+
+    // Already used by the vault
+    let result = defindex_contract.try_add_strategy(&test.token_0.address, &strategy_params_token_0.get(0).unwrap());
+    assert_eq!(result, Err(Ok(ContractError::DuplicatedStrategy)));
+    let result = defindex_contract.try_propose(&ProposalAction::AddStrategy(
+        test.token_0.address.clone(),
+        strategy_params_token_0.get(0).unwrap(),
+    ));
+    assert_eq!(result, Err(Ok(ContractError::DuplicatedStrategy)));
+
+    // The strategy does not support the asset
+    let token_1_strategy = Strategy {
+        name: String::from_str(&test.env, "Strategy 1"),
+        address: test.strategy_client_token_1.address.clone(),
+        paused: false,
+    };
+    let result = defindex_contract.try_propose(&ProposalAction::AddStrategy(test.token_0.address.clone(), token_1_strategy.clone()));
+    assert_eq!(result, Err(Ok(ContractError::StrategyDoesNotSupportAsset)));
+
+    // The asset is not managed by the vault
+    let result = defindex_contract.try_propose(&ProposalAction::AddStrategy(test.token_1.address.clone(), token_1_strategy));
+    assert_eq!(result, Err(Ok(ContractError::AssetNotFound)));
+
+    let result = defindex_contract.try_remove_strategy(&test.strategy_client_token_1.address);
+    assert_eq!(result, Err(Ok(ContractError::StrategyNotFound)));
+}
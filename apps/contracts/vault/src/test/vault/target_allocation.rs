@@ -0,0 +1,269 @@
+use soroban_sdk::{vec as sorobanvec, Address, Map, String, Vec};
+
+use crate::test::defindex_vault::{
+    AssetStrategySet, AssetTargetAllocation, ContractError, RolesDataKey, StrategyTarget,
+};
+use crate::test::{
+    create_defindex_vault, create_strategy_params_token_0, create_strategy_params_token_1,
+    create_unsafe_strategy_params_token_0, DeFindexVaultTest,
+};
+
+#[test]
+fn deposit_and_invest_follows_targets() {
+    let test = DeFindexVaultTest::setup();
+    test.env.mock_all_auths();
+    let mut strategy_params_token_0 = create_strategy_params_token_0(&test);
+    strategy_params_token_0.append(&create_unsafe_strategy_params_token_0(&test));
+    let strategy_params_token_1 = create_strategy_params_token_1(&test);
+
+    let assets: Vec<AssetStrategySet> = sorobanvec![
+        &test.env,
+        AssetStrategySet {
+            address: test.token_0.address.clone(),
+            strategies: strategy_params_token_0.clone()
+        },
+        AssetStrategySet {
+            address: test.token_1.address.clone(),
+            strategies: strategy_params_token_1.clone()
+        }
+    ];
+
+    let mut roles: Map<u32, Address> = Map::new(&test.env);
+    roles.set(RolesDataKey::Manager as u32, test.manager.clone());
+    roles.set(RolesDataKey::EmergencyManager as u32, test.emergency_manager.clone());
+    roles.set(RolesDataKey::VaultFeeReceiver as u32, test.vault_fee_receiver.clone());
+    roles.set(RolesDataKey::RebalanceManager as u32, test.rebalance_manager.clone());
+
+    let mut name_symbol: Map<String, String> = Map::new(&test.env);
+    name_symbol.set(String::from_str(&test.env, "name"), String::from_str(&test.env, "dfToken"));
+    name_symbol.set(String::from_str(&test.env, "symbol"), String::from_str(&test.env, "DFT"));
+
+    let defindex_contract = create_defindex_vault(
+        &test.env,
+        assets,
+        roles,
+        2000u32,
+        test.defindex_protocol_receiver.clone(),
+        2500u32,
+        test.soroswap_router.address.clone(),
+        name_symbol,
+        true
+    );
+
+    let target_allocation = AssetTargetAllocation {
+        asset: test.token_0.address.clone(),
+        idle_bps: 1000,
+        strategy_targets: sorobanvec![
+            &test.env,
+            StrategyTarget {
+                strategy_address: test.strategy_client_token_0.address.clone(),
+                weight_bps: 6000,
+            },
+            StrategyTarget {
+                strategy_address: test.unsafe_strategy_client_token_0.address.clone(),
+                weight_bps: 3000,
+            },
+        ],
+    };
+    defindex_contract.set_target_allocation(&target_allocation);
+    assert_eq!(defindex_contract.get_target_allocation(&test.token_0.address), Some(target_allocation));
+    assert_eq!(defindex_contract.get_target_allocation(&test.token_1.address), None);
+
+    let amount0 = 1_000_000_001i128;
+    let amount1 = 4_000_000_000i128;
+
+    let users = DeFindexVaultTest::generate_random_users(&test.env, 1);
+    test.token_0_admin_client.mint(&users[0], &amount0);
+    test.token_1_admin_client.mint(&users[0], &amount1);
+
+    // The very first deposit is invested for token_0, token_1 has no target and no investments yet
+    defindex_contract.deposit(
+        &sorobanvec![&test.env, amount0, amount1],
+        &sorobanvec![&test.env, amount0, amount1],
+        &users[0],
+        &true,
+    );
+
+    assert_eq!(test.token_0.balance(&test.strategy_client_token_0.address), 600_000_000i128);
+    assert_eq!(test.token_0.balance(&test.unsafe_strategy_client_token_0.address), 300_000_000i128);
+    // The idle buffer and the rounding dust stay in the vault
+    assert_eq!(test.token_0.balance(&defindex_contract.address), 100_000_001i128);
+    assert_eq!(test.token_1.balance(&defindex_contract.address), amount1);
+    assert_eq!(test.token_1.balance(&test.strategy_client_token_1.address), 0);
+
+    // Strategies without a target weight receive nothing
+    let target_allocation = AssetTargetAllocation {
+        asset: test.token_0.address.clone(),
+        idle_bps: 0,
+        strategy_targets: sorobanvec![
+            &test.env,
+            StrategyTarget {
+                strategy_address: test.unsafe_strategy_client_token_0.address.clone(),
+                weight_bps: 10_000,
+            },
+        ],
+    };
+    defindex_contract.set_target_allocation(&target_allocation);
+
+    let amount0 = 100_000_000i128;
+    let amount1 = 400_000_000i128;
+    test.token_0_admin_client.mint(&users[0], &amount0);
+    test.token_1_admin_client.mint(&users[0], &amount1);
+    defindex_contract.deposit(
+        &sorobanvec![&test.env, amount0, amount1],
+        &sorobanvec![&test.env, 0i128, 0i128],
+        &users[0],
+        &true,
+    );
+
+    assert_eq!(test.token_0.balance(&test.strategy_client_token_0.address), 600_000_000i128);
+    assert_eq!(test.token_0.balance(&test.unsafe_strategy_client_token_0.address), 400_000_000i128);
+    assert_eq!(test.token_0.balance(&defindex_contract.address), 100_000_001i128);
+}
+
+#[test]
+fn invalid_target_allocation() {
+    let test = DeFindexVaultTest::setup();
+    test.env.mock_all_auths();
+    let mut strategy_params_token_0 = create_strategy_params_token_0(&test);
+    strategy_params_token_0.append(&create_unsafe_strategy_params_token_0(&test));
+    let strategy_params_token_1 = create_strategy_params_token_1(&test);
+
+    let assets: Vec<AssetStrategySet> = sorobanvec![
+        &test.env,
+        AssetStrategySet {
+            address: test.token_0.address.clone(),
+            strategies: strategy_params_token_0.clone()
+        },
+        AssetStrategySet {
+            address: test.token_1.address.clone(),
+            strategies: strategy_params_token_1.clone()
+        }
+    ];
+
+    let mut roles: Map<u32, Address> = Map::new(&test.env);
+    roles.set(RolesDataKey::Manager as u32, test.manager.clone());
+    roles.set(RolesDataKey::EmergencyManager as u32, test.emergency_manager.clone());
+    roles.set(RolesDataKey::VaultFeeReceiver as u32, test.vault_fee_receiver.clone());
+    roles.set(RolesDataKey::RebalanceManager as u32, test.rebalance_manager.clone());
+
+    let mut name_symbol: Map<String, String> = Map::new(&test.env);
+    name_symbol.set(String::from_str(&test.env, "name"), String::from_str(&test.env, "dfToken"));
+    name_symbol.set(String::from_str(&test.env, "symbol"), String::from_str(&test.env, "DFT"));
+
+    let defindex_contract = create_defindex_vault(
+        &test.env,
+        assets,
+        roles,
+        2000u32,
+        test.defindex_protocol_receiver.clone(),
+        2500u32,
+        test.soroswap_router.address.clone(),
+        name_symbol,
+        true
+    );
+
+    // Weights do not add up to 10_000
+    let result = defindex_contract.try_set_target_allocation(&AssetTargetAllocation {
+        asset: test.token_0.address.clone(),
+        idle_bps: 1000,
+        strategy_targets: sorobanvec![
+            &test.env,
+            StrategyTarget {
+                strategy_address: test.strategy_client_token_0.address.clone(),
+                weight_bps: 8000,
+            },
+        ],
+    });
+    assert_eq!(result, Err(Ok(ContractError::InvalidRatio)));
+
+    // Strategy of another asset
+    let result = defindex_contract.try_set_target_allocation(&AssetTargetAllocation {
+        asset: test.token_0.address.clone(),
+        idle_bps: 0,
+        strategy_targets: sorobanvec![
+            &test.env,
+            StrategyTarget {
+                strategy_address: test.strategy_client_token_1.address.clone(),
+                weight_bps: 10_000,
+            },
+        ],
+    });
+    assert_eq!(result, Err(Ok(ContractError::StrategyNotFound)));
+
+    // Duplicated strategy
+    let result = defindex_contract.try_set_target_allocation(&AssetTargetAllocation {
+        asset: test.token_0.address.clone(),
+        idle_bps: 0,
+        strategy_targets: sorobanvec![
+            &test.env,
+            StrategyTarget {
+                strategy_address: test.strategy_client_token_0.address.clone(),
+                weight_bps: 5000,
+            },
+            StrategyTarget {
+                strategy_address: test.strategy_client_token_0.address.clone(),
+                weight_bps: 5000,
+            },
+        ],
+    });
+    assert_eq!(result, Err(Ok(ContractError::DuplicatedStrategy)));
+
+    // Asset not in the vault
+    let result = defindex_contract.try_set_target_allocation(&AssetTargetAllocation {
+        asset: test.token_2.address.clone(),
+        idle_bps: 10_000,
+        strategy_targets: sorobanvec![&test.env],
+    });
+    assert_eq!(result, Err(Ok(ContractError::AssetNotFound)));
+}
+
+#[test]
+fn set_target_allocation_requires_manager() {
+    let test = DeFindexVaultTest::setup();
+    let mut strategy_params_token_0 = create_strategy_params_token_0(&test);
+    strategy_params_token_0.append(&create_unsafe_strategy_params_token_0(&test));
+    let strategy_params_token_1 = create_strategy_params_token_1(&test);
+
+    let assets: Vec<AssetStrategySet> = sorobanvec![
+        &test.env,
+        AssetStrategySet {
+            address: test.token_0.address.clone(),
+            strategies: strategy_params_token_0.clone()
+        },
+        AssetStrategySet {
+            address: test.token_1.address.clone(),
+            strategies: strategy_params_token_1.clone()
+        }
+    ];
+
+    let mut roles: Map<u32, Address> = Map::new(&test.env);
+    roles.set(RolesDataKey::Manager as u32, test.manager.clone());
+    roles.set(RolesDataKey::EmergencyManager as u32, test.emergency_manager.clone());
+    roles.set(RolesDataKey::VaultFeeReceiver as u32, test.vault_fee_receiver.clone());
+    roles.set(RolesDataKey::RebalanceManager as u32, test.rebalance_manager.clone());
+
+    let mut name_symbol: Map<String, String> = Map::new(&test.env);
+    name_symbol.set(String::from_str(&test.env, "name"), String::from_str(&test.env, "dfToken"));
+    name_symbol.set(String::from_str(&test.env, "symbol"), String::from_str(&test.env, "DFT"));
+
+    let defindex_contract = create_defindex_vault(
+        &test.env,
+        assets,
+        roles,
+        2000u32,
+        test.defindex_protocol_receiver.clone(),
+        2500u32,
+        test.soroswap_router.address.clone(),
+        name_symbol,
+        true
+    );
+
+    let result = defindex_contract.try_set_target_allocation(&AssetTargetAllocation {
+        asset: test.token_0.address.clone(),
+        idle_bps: 10_000,
+        strategy_targets: sorobanvec![&test.env],
+    });
+    assert!(result.is_err());
+    assert_eq!(defindex_contract.get_target_allocation(&test.token_0.address), None);
+}
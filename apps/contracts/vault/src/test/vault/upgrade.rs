@@ -1,8 +1,8 @@
-use soroban_sdk::testutils::{MockAuth, MockAuthInvoke};
-use soroban_sdk::{vec as sorobanvec, Address, Map, String, Vec};
+use soroban_sdk::testutils::{Ledger, MockAuth, MockAuthInvoke};
+use soroban_sdk::{vec as sorobanvec, Address, IntoVal, Map, String, Vec};
 
 use crate::test::defindex_vault::{
-    self, AssetStrategySet, ContractError, RolesDataKey
+    self, AssetStrategySet, ContractError, ProposalAction, RolesDataKey
 };
 use crate::test::{
     create_defindex_vault, create_strategy_params_token_0,
@@ -47,16 +47,31 @@ fn upgrade_success() {
 
     let installed_new_wasm = test.env.deployer().upload_contract_wasm(defindex_vault::WASM);
     
+    let action = ProposalAction::Upgrade(installed_new_wasm.clone());
+
+    let proposal_id = defindex_contract.mock_auths(&[MockAuth {
+        address: &test.manager.clone(),
+        invoke: &MockAuthInvoke {
+            contract: &defindex_contract.address.clone(),
+            fn_name: "propose",
+            args: (action.clone(),).into_val(&test.env),
+            sub_invokes: &[],
+        },
+    }
+    ]).propose(&action);
+
+    test.env.ledger().set_timestamp(defindex_contract.get_proposal(&proposal_id).executable_at);
+
     defindex_contract.mock_auths(&[MockAuth {
         address: &test.manager.clone(),
         invoke: &MockAuthInvoke {
             contract: &defindex_contract.address.clone(),
-            fn_name: "upgrade",
-            args: sorobanvec!(&test.env, installed_new_wasm.clone().to_val()),
+            fn_name: "execute_proposal",
+            args: (proposal_id,).into_val(&test.env),
             sub_invokes: &[],
         },
     }
-    ]).upgrade(&installed_new_wasm);
+    ]).execute_proposal(&proposal_id);
     
 }
 
@@ -99,16 +114,18 @@ fn upgrade_not_manager() {
 
     let installed_new_wasm = test.env.deployer().upload_contract_wasm(defindex_vault::WASM);
     
+    let action = ProposalAction::Upgrade(installed_new_wasm.clone());
+
     defindex_contract.mock_auths(&[MockAuth {
         address: &test.emergency_manager.clone(),
         invoke: &MockAuthInvoke {
             contract: &defindex_contract.address.clone(),
-            fn_name: "upgrade",
-            args: sorobanvec!(&test.env, installed_new_wasm.clone().to_val()),
+            fn_name: "propose",
+            args: (action.clone(),).into_val(&test.env),
             sub_invokes: &[],
         },
     }
-    ]).upgrade(&installed_new_wasm);
+    ]).propose(&action);
     
 }
 
@@ -150,16 +167,18 @@ fn upgrade_not_upgradable() {
 
     let installed_new_wasm = test.env.deployer().upload_contract_wasm(defindex_vault::WASM);
     
+    let action = ProposalAction::Upgrade(installed_new_wasm.clone());
+
     let result = defindex_contract.mock_auths(&[MockAuth {
         address: &test.manager.clone(),
         invoke: &MockAuthInvoke {
             contract: &defindex_contract.address.clone(),
-            fn_name: "upgrade",
-            args: sorobanvec!(&test.env, installed_new_wasm.clone().to_val()),
+            fn_name: "propose",
+            args: (action.clone(),).into_val(&test.env),
             sub_invokes: &[],
         },
     }
-    ]).try_upgrade(&installed_new_wasm);
+    ]).try_propose(&action);
 
     assert_eq!(result, Err(Ok(ContractError::NotUpgradable)));
 }
\ No newline at end of file
@@ -752,7 +752,7 @@ fn from_strategy_one_asset_one_strategy_success() {
         ),
     ];
 
-    defindex_contract.rebalance(&test.rebalance_manager, &rebalance_instructions);
+    defindex_contract.rebalance(&test.rebalance_manager, &rebalance_instructions, &None);
 
     let vault_balance = test.token_0.balance(&defindex_contract.address);
     assert_eq!(vault_balance, 0);
@@ -869,7 +869,7 @@ fn from_strategies_one_asset_two_strategies_success() {
             amount_to_invest_1
         ),
     ];
-    defindex_contract.rebalance(&test.rebalance_manager, &instructions);
+    defindex_contract.rebalance(&test.rebalance_manager, &instructions, &None);
 
     let strategy_1_balance_before_withdraw = test.token_0.balance(&test.strategy_client_token_0.address);
     let strategy_2_balance_before_withdraw = test.token_0.balance(&strategy_client_1.address);
@@ -1011,7 +1011,7 @@ fn from_strategies_two_asset_each_one_strategy_success() {
         ),
     ];
 
-    defindex_contract.rebalance(&test.rebalance_manager, &rebalance_instructions);
+    defindex_contract.rebalance(&test.rebalance_manager, &rebalance_instructions, &None);
 
     // check vault balances
     assert_eq!(test.token_0.balance(&defindex_contract.address), 0);
@@ -2165,5 +2165,5 @@ fn invest(test: &DeFindexVaultTest, defindex_contract: &defindex_vault::Client,
             investment_amount
         ),
     ];
-    defindex_contract.rebalance(&test.rebalance_manager, &instructions);
+    defindex_contract.rebalance(&test.rebalance_manager, &instructions, &None);
 }
\ No newline at end of file
@@ -0,0 +1,379 @@
+use soroban_sdk::{vec as sorobanvec, Address, Map, String, Vec};
+
+use crate::test::defindex_vault::{AssetStrategySet, ContractError, Instruction, RolesDataKey};
+use crate::test::{
+    create_defindex_vault, create_strategy_params_token_0, create_strategy_params_token_1,
+    DeFindexVaultTest,
+};
+
+#[test]
+fn idle_funds_success() {
+    let test = DeFindexVaultTest::setup();
+    test.env.mock_all_auths();
+    let strategy_params_token_0 = create_strategy_params_token_0(&test);
+    let strategy_params_token_1 = create_strategy_params_token_1(&test);
+
+    let assets: Vec<AssetStrategySet> = sorobanvec![
+        &test.env,
+        AssetStrategySet {
+            address: test.token_0.address.clone(),
+            strategies: strategy_params_token_0.clone()
+        },
+        AssetStrategySet {
+            address: test.token_1.address.clone(),
+            strategies: strategy_params_token_1.clone()
+        }
+    ];
+
+    let mut roles: Map<u32, Address> = Map::new(&test.env);
+    roles.set(RolesDataKey::Manager as u32, test.manager.clone());
+    roles.set(RolesDataKey::EmergencyManager as u32, test.emergency_manager.clone());
+    roles.set(RolesDataKey::VaultFeeReceiver as u32, test.vault_fee_receiver.clone());
+    roles.set(RolesDataKey::RebalanceManager as u32, test.rebalance_manager.clone());
+
+    let mut name_symbol: Map<String, String> = Map::new(&test.env);
+    name_symbol.set(String::from_str(&test.env, "name"), String::from_str(&test.env, "dfToken"));
+    name_symbol.set(String::from_str(&test.env, "symbol"), String::from_str(&test.env, "DFT"));
+
+    let defindex_contract = create_defindex_vault(
+        &test.env,
+        assets,
+        roles,
+        2000u32,
+        test.defindex_protocol_receiver.clone(),
+        2500u32,
+        test.soroswap_router.address.clone(),
+        name_symbol,
+        true
+    );
+
+    let amount0 = 123_456_789i128;
+    let amount1 = 987_654_321i128;
+
+    let users = DeFindexVaultTest::generate_random_users(&test.env, 1);
+    test.token_0_admin_client.mint(&users[0], &amount0);
+    test.token_1_admin_client.mint(&users[0], &amount1);
+
+    defindex_contract.deposit(
+        &sorobanvec![&test.env, amount0, amount1],
+        &sorobanvec![&test.env, amount0, amount1],
+        &users[0],
+        &false,
+    );
+
+    let user_shares = defindex_contract.balance(&users[0]);
+    let amounts_out = sorobanvec![&test.env, 10_000_000i128, 3_000_000i128];
+    let expected_shares = defindex_contract.preview_withdraw(&amounts_out);
+
+    let burned_shares = defindex_contract.withdraw_exact_amounts(&amounts_out, &expected_shares, &users[0]);
+    assert_eq!(burned_shares, expected_shares);
+
+    // The user receives exactly the requested amounts
+    assert_eq!(test.token_0.balance(&users[0]), 10_000_000i128);
+    assert_eq!(test.token_1.balance(&users[0]), 3_000_000i128);
+    assert_eq!(defindex_contract.balance(&users[0]), user_shares - burned_shares);
+    assert_eq!(test.token_0.balance(&defindex_contract.address), amount0 - 10_000_000i128);
+    assert_eq!(test.token_1.balance(&defindex_contract.address), amount1 - 3_000_000i128);
+
+    // Shares are rounded up, so the burned shares are worth at least the withdrawn amounts
+    let total_supply = defindex_contract.total_supply() + burned_shares;
+    assert!(amount0 * burned_shares / total_supply >= 10_000_000i128);
+    assert!(amount0 * (burned_shares - 1) / total_supply < 10_000_000i128);
+}
+
+#[test]
+fn invested_funds_success() {
+    let test = DeFindexVaultTest::setup();
+    test.env.mock_all_auths();
+    let strategy_params_token_0 = create_strategy_params_token_0(&test);
+    let strategy_params_token_1 = create_strategy_params_token_1(&test);
+
+    let assets: Vec<AssetStrategySet> = sorobanvec![
+        &test.env,
+        AssetStrategySet {
+            address: test.token_0.address.clone(),
+            strategies: strategy_params_token_0.clone()
+        },
+        AssetStrategySet {
+            address: test.token_1.address.clone(),
+            strategies: strategy_params_token_1.clone()
+        }
+    ];
+
+    let mut roles: Map<u32, Address> = Map::new(&test.env);
+    roles.set(RolesDataKey::Manager as u32, test.manager.clone());
+    roles.set(RolesDataKey::EmergencyManager as u32, test.emergency_manager.clone());
+    roles.set(RolesDataKey::VaultFeeReceiver as u32, test.vault_fee_receiver.clone());
+    roles.set(RolesDataKey::RebalanceManager as u32, test.rebalance_manager.clone());
+
+    let mut name_symbol: Map<String, String> = Map::new(&test.env);
+    name_symbol.set(String::from_str(&test.env, "name"), String::from_str(&test.env, "dfToken"));
+    name_symbol.set(String::from_str(&test.env, "symbol"), String::from_str(&test.env, "DFT"));
+
+    let defindex_contract = create_defindex_vault(
+        &test.env,
+        assets,
+        roles,
+        2000u32,
+        test.defindex_protocol_receiver.clone(),
+        2500u32,
+        test.soroswap_router.address.clone(),
+        name_symbol,
+        true
+    );
+
+    let amount0 = 123_456_789i128;
+    let amount1 = 987_654_321i128;
+
+    let users = DeFindexVaultTest::generate_random_users(&test.env, 1);
+    test.token_0_admin_client.mint(&users[0], &amount0);
+    test.token_1_admin_client.mint(&users[0], &amount1);
+
+    defindex_contract.deposit(
+        &sorobanvec![&test.env, amount0, amount1],
+        &sorobanvec![&test.env, amount0, amount1],
+        &users[0],
+        &false,
+    );
+
+    let invest_instructions = sorobanvec![
+        &test.env,
+        Instruction::Invest(test.strategy_client_token_0.address.clone(), amount0 / 2),
+        Instruction::Invest(test.strategy_client_token_1.address.clone(), amount1),
+    ];
+    defindex_contract.rebalance(&test.rebalance_manager, &invest_instructions, &None);
+
+    // token_0 is taken from idle funds and from the strategy, token_1 only from the strategy
+    let amounts_out = sorobanvec![&test.env, amount0 - amount0 / 4, amount1 / 3];
+    let burned_shares = defindex_contract.withdraw_exact_amounts(&amounts_out, &i128::MAX, &users[0]);
+    assert!(burned_shares > 0);
+
+    assert_eq!(test.token_0.balance(&users[0]), amount0 - amount0 / 4);
+    assert_eq!(test.token_1.balance(&users[0]), amount1 / 3);
+    assert_eq!(test.token_0.balance(&defindex_contract.address), 0);
+    assert_eq!(test.token_0.balance(&test.strategy_client_token_0.address), amount0 / 4);
+    assert_eq!(test.token_1.balance(&test.strategy_client_token_1.address), amount1 - amount1 / 3);
+}
+
+#[test]
+fn max_shares_exceeded() {
+    let test = DeFindexVaultTest::setup();
+    test.env.mock_all_auths();
+    let strategy_params_token_0 = create_strategy_params_token_0(&test);
+    let strategy_params_token_1 = create_strategy_params_token_1(&test);
+
+    let assets: Vec<AssetStrategySet> = sorobanvec![
+        &test.env,
+        AssetStrategySet {
+            address: test.token_0.address.clone(),
+            strategies: strategy_params_token_0.clone()
+        },
+        AssetStrategySet {
+            address: test.token_1.address.clone(),
+            strategies: strategy_params_token_1.clone()
+        }
+    ];
+
+    let mut roles: Map<u32, Address> = Map::new(&test.env);
+    roles.set(RolesDataKey::Manager as u32, test.manager.clone());
+    roles.set(RolesDataKey::EmergencyManager as u32, test.emergency_manager.clone());
+    roles.set(RolesDataKey::VaultFeeReceiver as u32, test.vault_fee_receiver.clone());
+    roles.set(RolesDataKey::RebalanceManager as u32, test.rebalance_manager.clone());
+
+    let mut name_symbol: Map<String, String> = Map::new(&test.env);
+    name_symbol.set(String::from_str(&test.env, "name"), String::from_str(&test.env, "dfToken"));
+    name_symbol.set(String::from_str(&test.env, "symbol"), String::from_str(&test.env, "DFT"));
+
+    let defindex_contract = create_defindex_vault(
+        &test.env,
+        assets,
+        roles,
+        2000u32,
+        test.defindex_protocol_receiver.clone(),
+        2500u32,
+        test.soroswap_router.address.clone(),
+        name_symbol,
+        true
+    );
+
+    let amount0 = 123_456_789i128;
+    let amount1 = 987_654_321i128;
+
+    let users = DeFindexVaultTest::generate_random_users(&test.env, 1);
+    test.token_0_admin_client.mint(&users[0], &amount0);
+    test.token_1_admin_client.mint(&users[0], &amount1);
+
+    defindex_contract.deposit(
+        &sorobanvec![&test.env, amount0, amount1],
+        &sorobanvec![&test.env, amount0, amount1],
+        &users[0],
+        &false,
+    );
+
+    let amounts_out = sorobanvec![&test.env, 10_000_000i128, 3_000_000i128];
+    let expected_shares = defindex_contract.preview_withdraw(&amounts_out);
+
+    let result = defindex_contract.try_withdraw_exact_amounts(&amounts_out, &(expected_shares - 1), &users[0]);
+    assert_eq!(result, Err(Ok(ContractError::ExcessiveInputAmount)));
+}
+
+#[test]
+fn invalid_amounts() {
+    let test = DeFindexVaultTest::setup();
+    test.env.mock_all_auths();
+    let strategy_params_token_0 = create_strategy_params_token_0(&test);
+    let strategy_params_token_1 = create_strategy_params_token_1(&test);
+
+    let assets: Vec<AssetStrategySet> = sorobanvec![
+        &test.env,
+        AssetStrategySet {
+            address: test.token_0.address.clone(),
+            strategies: strategy_params_token_0.clone()
+        },
+        AssetStrategySet {
+            address: test.token_1.address.clone(),
+            strategies: strategy_params_token_1.clone()
+        }
+    ];
+
+    let mut roles: Map<u32, Address> = Map::new(&test.env);
+    roles.set(RolesDataKey::Manager as u32, test.manager.clone());
+    roles.set(RolesDataKey::EmergencyManager as u32, test.emergency_manager.clone());
+    roles.set(RolesDataKey::VaultFeeReceiver as u32, test.vault_fee_receiver.clone());
+    roles.set(RolesDataKey::RebalanceManager as u32, test.rebalance_manager.clone());
+
+    let mut name_symbol: Map<String, String> = Map::new(&test.env);
+    name_symbol.set(String::from_str(&test.env, "name"), String::from_str(&test.env, "dfToken"));
+    name_symbol.set(String::from_str(&test.env, "symbol"), String::from_str(&test.env, "DFT"));
+
+    let defindex_contract = create_defindex_vault(
+        &test.env,
+        assets,
+        roles,
+        2000u32,
+        test.defindex_protocol_receiver.clone(),
+        2500u32,
+        test.soroswap_router.address.clone(),
+        name_symbol,
+        true
+    );
+
+    let amount0 = 123_456_789i128;
+    let amount1 = 987_654_321i128;
+
+    let users = DeFindexVaultTest::generate_random_users(&test.env, 1);
+    test.token_0_admin_client.mint(&users[0], &amount0);
+    test.token_1_admin_client.mint(&users[0], &amount1);
+
+    defindex_contract.deposit(
+        &sorobanvec![&test.env, amount0, amount1],
+        &sorobanvec![&test.env, amount0, amount1],
+        &users[0],
+        &false,
+    );
+
+    // More than the vault holds
+    let result = defindex_contract.try_withdraw_exact_amounts(
+        &sorobanvec![&test.env, amount0 + 1, 0i128],
+        &i128::MAX,
+        &users[0],
+    );
+    assert_eq!(result, Err(Ok(ContractError::InsufficientManagedFunds)));
+
+    // Wrong amounts length
+    let result = defindex_contract.try_withdraw_exact_amounts(
+        &sorobanvec![&test.env, 1i128],
+        &i128::MAX,
+        &users[0],
+    );
+    assert_eq!(result, Err(Ok(ContractError::WrongAmountsLength)));
+
+    // Nothing to withdraw
+    let result = defindex_contract.try_withdraw_exact_amounts(
+        &sorobanvec![&test.env, 0i128, 0i128],
+        &i128::MAX,
+        &users[0],
+    );
+    assert_eq!(result, Err(Ok(ContractError::AmountNotAllowed)));
+
+    // Negative amounts
+    let result = defindex_contract.try_withdraw_exact_amounts(
+        &sorobanvec![&test.env, -1i128, 0i128],
+        &i128::MAX,
+        &users[0],
+    );
+    assert_eq!(result, Err(Ok(ContractError::AmountNotAllowed)));
+}
+
+#[test]
+fn unrequested_value_stays_in_the_vault() {
+    let test = DeFindexVaultTest::setup();
+    test.env.mock_all_auths();
+    let strategy_params_token_0 = create_strategy_params_token_0(&test);
+    let strategy_params_token_1 = create_strategy_params_token_1(&test);
+
+    let assets: Vec<AssetStrategySet> = sorobanvec![
+        &test.env,
+        AssetStrategySet {
+            address: test.token_0.address.clone(),
+            strategies: strategy_params_token_0.clone()
+        },
+        AssetStrategySet {
+            address: test.token_1.address.clone(),
+            strategies: strategy_params_token_1.clone()
+        }
+    ];
+
+    let mut roles: Map<u32, Address> = Map::new(&test.env);
+    roles.set(RolesDataKey::Manager as u32, test.manager.clone());
+    roles.set(RolesDataKey::EmergencyManager as u32, test.emergency_manager.clone());
+    roles.set(RolesDataKey::VaultFeeReceiver as u32, test.vault_fee_receiver.clone());
+    roles.set(RolesDataKey::RebalanceManager as u32, test.rebalance_manager.clone());
+
+    let mut name_symbol: Map<String, String> = Map::new(&test.env);
+    name_symbol.set(String::from_str(&test.env, "name"), String::from_str(&test.env, "dfToken"));
+    name_symbol.set(String::from_str(&test.env, "symbol"), String::from_str(&test.env, "DFT"));
+
+    let defindex_contract = create_defindex_vault(
+        &test.env,
+        assets,
+        roles,
+        2000u32,
+        test.defindex_protocol_receiver.clone(),
+        2500u32,
+        test.soroswap_router.address.clone(),
+        name_symbol,
+        true
+    );
+
+    let amount0 = 100_000_000i128;
+    let amount1 = 200_000_000i128;
+    let users = DeFindexVaultTest::generate_random_users(&test.env, 2);
+    for user in users.iter() {
+        test.token_0_admin_client.mint(user, &amount0);
+        test.token_1_admin_client.mint(user, &amount1);
+        defindex_contract.deposit(
+            &sorobanvec![&test.env, amount0, amount1],
+            &sorobanvec![&test.env, amount0, amount1],
+            user,
+            &false,
+        );
+    }
+    let shares_before = defindex_contract.balance(&users[0]);
+
+    // Half of the first user's token_0, but only a tenth of their token_1
+    let amounts_out = sorobanvec![&test.env, amount0 / 2, amount1 / 10];
+    let expected_shares = defindex_contract.preview_withdraw(&amounts_out);
+    let burned_shares = defindex_contract.withdraw_exact_amounts(&amounts_out, &i128::MAX, &users[0]);
+    assert_eq!(burned_shares, expected_shares);
+    assert_eq!(test.token_0.balance(&users[0]), amount0 / 2);
+    assert_eq!(test.token_1.balance(&users[0]), amount1 / 10);
+
+    // The shares burned are worth half of the first user's position in both assets
+    assert!(burned_shares >= shares_before / 2);
+
+    // The token_1 those shares were worth but not withdrawn goes to the remaining holders
+    let second_user_amounts = defindex_contract.convert_to_assets(&defindex_contract.balance(&users[1]));
+    assert!(second_user_amounts.get(0).unwrap() <= amount0);
+    assert!(second_user_amounts.get(1).unwrap() > amount1 + (amount1 / 2 - amount1 / 10) / 2);
+}
@@ -0,0 +1,146 @@
+use soroban_sdk::{vec as sorobanvec, Address, Map, String, Vec};
+
+use crate::test::defindex_vault::{AssetStrategySet, ContractError, Instruction, RolesDataKey};
+use crate::test::{
+    create_defindex_vault, create_strategy_params_token_0, create_unsafe_strategy_params_token_0,
+    DeFindexVaultTest,
+};
+
+#[test]
+fn withdraw_drains_strategies_in_order() {
+    let test = DeFindexVaultTest::setup();
+    test.env.mock_all_auths();
+    let mut strategy_params_token_0 = create_strategy_params_token_0(&test);
+    strategy_params_token_0.append(&create_unsafe_strategy_params_token_0(&test));
+
+    let assets: Vec<AssetStrategySet> = sorobanvec![
+        &test.env,
+        AssetStrategySet {
+            address: test.token_0.address.clone(),
+            strategies: strategy_params_token_0.clone()
+        }
+    ];
+
+    let mut roles: Map<u32, Address> = Map::new(&test.env);
+    roles.set(RolesDataKey::Manager as u32, test.manager.clone());
+    roles.set(RolesDataKey::EmergencyManager as u32, test.emergency_manager.clone());
+    roles.set(RolesDataKey::VaultFeeReceiver as u32, test.vault_fee_receiver.clone());
+    roles.set(RolesDataKey::RebalanceManager as u32, test.rebalance_manager.clone());
+
+    let mut name_symbol: Map<String, String> = Map::new(&test.env);
+    name_symbol.set(String::from_str(&test.env, "name"), String::from_str(&test.env, "dfToken"));
+    name_symbol.set(String::from_str(&test.env, "symbol"), String::from_str(&test.env, "DFT"));
+
+    let defindex_contract = create_defindex_vault(
+        &test.env,
+        assets,
+        roles,
+        2000u32,
+        test.defindex_protocol_receiver.clone(),
+        2500u32,
+        test.soroswap_router.address.clone(),
+        name_symbol,
+        true
+    );
+
+    let amount = 1_000_000_000i128;
+    let users = DeFindexVaultTest::generate_random_users(&test.env, 1);
+    test.token_0_admin_client.mint(&users[0], &amount);
+
+    defindex_contract.deposit(&sorobanvec![&test.env, amount], &sorobanvec![&test.env, amount], &users[0], &false);
+    defindex_contract.rebalance(
+        &test.rebalance_manager,
+        &sorobanvec![
+            &test.env,
+            Instruction::Invest(test.strategy_client_token_0.address.clone(), 600_000_000i128),
+            Instruction::Invest(test.unsafe_strategy_client_token_0.address.clone(), 400_000_000i128)
+        ],
+        &None,
+    );
+
+    // Unsafe strategy first, the hodl strategy is only drained once it is empty
+    assert_eq!(defindex_contract.get_withdraw_order(&test.token_0.address), sorobanvec![&test.env]);
+    let withdraw_order = sorobanvec![&test.env, test.unsafe_strategy_client_token_0.address.clone()];
+    defindex_contract.set_withdraw_order(&test.token_0.address, &withdraw_order);
+    assert_eq!(defindex_contract.get_withdraw_order(&test.token_0.address), withdraw_order);
+
+    defindex_contract.withdraw_exact_amounts(&sorobanvec![&test.env, 300_000_000i128], &i128::MAX, &users[0]);
+    assert_eq!(test.token_0.balance(&users[0]), 300_000_000i128);
+    assert_eq!(test.token_0.balance(&test.unsafe_strategy_client_token_0.address), 100_000_000i128);
+    assert_eq!(test.token_0.balance(&test.strategy_client_token_0.address), 600_000_000i128);
+
+    defindex_contract.withdraw_exact_amounts(&sorobanvec![&test.env, 300_000_000i128], &i128::MAX, &users[0]);
+    assert_eq!(test.token_0.balance(&users[0]), 600_000_000i128);
+    assert_eq!(test.token_0.balance(&test.unsafe_strategy_client_token_0.address), 0i128);
+    assert_eq!(test.token_0.balance(&test.strategy_client_token_0.address), 400_000_000i128);
+
+    // An empty order goes back to proportional unwinding
+    defindex_contract.set_withdraw_order(&test.token_0.address, &sorobanvec![&test.env]);
+    defindex_contract.rebalance(
+        &test.rebalance_manager,
+        &sorobanvec![
+            &test.env,
+            Instruction::Unwind(test.strategy_client_token_0.address.clone(), 200_000_000i128),
+            Instruction::Invest(test.unsafe_strategy_client_token_0.address.clone(), 200_000_000i128)
+        ],
+        &None,
+    );
+    defindex_contract.withdraw_exact_amounts(&sorobanvec![&test.env, 100_000_000i128], &i128::MAX, &users[0]);
+    assert_eq!(test.token_0.balance(&test.unsafe_strategy_client_token_0.address), 150_000_000i128);
+    assert_eq!(test.token_0.balance(&test.strategy_client_token_0.address), 150_000_000i128);
+}
+
+#[test]
+fn invalid_withdraw_order() {
+    let test = DeFindexVaultTest::setup();
+    test.env.mock_all_auths();
+    let mut strategy_params_token_0 = create_strategy_params_token_0(&test);
+    strategy_params_token_0.append(&create_unsafe_strategy_params_token_0(&test));
+
+    let assets: Vec<AssetStrategySet> = sorobanvec![
+        &test.env,
+        AssetStrategySet {
+            address: test.token_0.address.clone(),
+            strategies: strategy_params_token_0.clone()
+        }
+    ];
+
+    let mut roles: Map<u32, Address> = Map::new(&test.env);
+    roles.set(RolesDataKey::Manager as u32, test.manager.clone());
+    roles.set(RolesDataKey::EmergencyManager as u32, test.emergency_manager.clone());
+    roles.set(RolesDataKey::VaultFeeReceiver as u32, test.vault_fee_receiver.clone());
+    roles.set(RolesDataKey::RebalanceManager as u32, test.rebalance_manager.clone());
+
+    let mut name_symbol: Map<String, String> = Map::new(&test.env);
+    name_symbol.set(String::from_str(&test.env, "name"), String::from_str(&test.env, "dfToken"));
+    name_symbol.set(String::from_str(&test.env, "symbol"), String::from_str(&test.env, "DFT"));
+
+    let defindex_contract = create_defindex_vault(
+        &test.env,
+        assets,
+        roles,
+        2000u32,
+        test.defindex_protocol_receiver.clone(),
+        2500u32,
+        test.soroswap_router.address.clone(),
+        name_symbol,
+        true
+    );
+
+    let unsafe_strategy = test.unsafe_strategy_client_token_0.address.clone();
+
+    let result = defindex_contract.try_set_withdraw_order(&test.token_1.address, &sorobanvec![&test.env]);
+    assert_eq!(result, Err(Ok(ContractError::AssetNotFound)));
+
+    let result = defindex_contract.try_set_withdraw_order(
+        &test.token_0.address,
+        &sorobanvec![&test.env, test.strategy_client_token_1.address.clone()],
+    );
+    assert_eq!(result, Err(Ok(ContractError::StrategyNotFound)));
+
+    let result = defindex_contract.try_set_withdraw_order(
+        &test.token_0.address,
+        &sorobanvec![&test.env, unsafe_strategy.clone(), unsafe_strategy],
+    );
+    assert_eq!(result, Err(Ok(ContractError::DuplicatedStrategy)));
+}
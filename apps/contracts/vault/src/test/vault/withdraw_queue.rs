@@ -0,0 +1,393 @@
+use soroban_sdk::{testutils::Address as _, vec as sorobanvec, Address, Map, String, Vec};
+
+use crate::test::defindex_vault::{AssetStrategySet, ContractError, Instruction, RolesDataKey, VaultError, WithdrawRequest};
+use crate::test::{create_defindex_vault, create_strategy_params_token_0, DeFindexVaultTest};
+
+#[test]
+fn requests_are_completed_in_order_once_liquidity_returns() {
+    let test = DeFindexVaultTest::setup();
+    test.env.mock_all_auths();
+    let strategy_params_token_0 = create_strategy_params_token_0(&test);
+
+    let assets: Vec<AssetStrategySet> = sorobanvec![
+        &test.env,
+        AssetStrategySet {
+            address: test.token_0.address.clone(),
+            strategies: strategy_params_token_0.clone()
+        }
+    ];
+
+    let mut roles: Map<u32, Address> = Map::new(&test.env);
+    roles.set(RolesDataKey::Manager as u32, test.manager.clone());
+    roles.set(RolesDataKey::EmergencyManager as u32, test.emergency_manager.clone());
+    roles.set(RolesDataKey::VaultFeeReceiver as u32, test.vault_fee_receiver.clone());
+    roles.set(RolesDataKey::RebalanceManager as u32, test.rebalance_manager.clone());
+
+    let mut name_symbol: Map<String, String> = Map::new(&test.env);
+    name_symbol.set(String::from_str(&test.env, "name"), String::from_str(&test.env, "dfToken"));
+    name_symbol.set(String::from_str(&test.env, "symbol"), String::from_str(&test.env, "DFT"));
+
+    let defindex_contract = create_defindex_vault(
+        &test.env,
+        assets,
+        roles,
+        2000u32,
+        test.defindex_protocol_receiver.clone(),
+        2500u32,
+        test.soroswap_router.address.clone(),
+        name_symbol,
+        true
+    );
+
+    let amount = 1_000_000_000i128;
+    let users = DeFindexVaultTest::generate_random_users(&test.env, 2);
+    test.token_0_admin_client.mint(&users[0], &amount);
+    test.token_0_admin_client.mint(&users[1], &amount);
+
+    defindex_contract.deposit(&sorobanvec![&test.env, amount], &sorobanvec![&test.env, amount], &users[0], &false);
+    defindex_contract.deposit(&sorobanvec![&test.env, amount], &sorobanvec![&test.env, amount], &users[1], &false);
+    defindex_contract.rebalance(
+        &test.rebalance_manager,
+        &sorobanvec![&test.env, Instruction::Invest(test.strategy_client_token_0.address.clone(), amount * 2)],
+        &None,
+    );
+
+    // The strategy becomes illiquid
+    let parking = Address::generate(&test.env);
+    test.token_0.transfer(&test.strategy_client_token_0.address, &parking, &(amount * 2));
+
+    let shares_0 = defindex_contract.balance(&users[0]);
+    let shares_1 = defindex_contract.balance(&users[1]);
+    assert_eq!(shares_0, amount - 1000);
+    assert_eq!(shares_1, amount);
+
+    let result = defindex_contract.try_withdraw(&shares_0, &sorobanvec![&test.env, 0i128], &users[0]);
+    assert_eq!(result, Err(Ok(ContractError::StrategyWithdrawError)));
+
+    assert_eq!(defindex_contract.get_withdraw_queue(), (0u64, 0u64));
+    let request_0 = defindex_contract.request_withdraw(&shares_0, &users[0]);
+    let request_1 = defindex_contract.request_withdraw(&shares_1, &users[1]);
+    assert_eq!((request_0, request_1), (0u64, 1u64));
+    assert_eq!(defindex_contract.get_withdraw_queue(), (0u64, 2u64));
+    assert_eq!(
+        defindex_contract.get_withdraw_request(&request_1),
+        WithdrawRequest { owner: users[1].clone(), shares: shares_1 }
+    );
+
+    // Shares are escrowed by the vault
+    assert_eq!(defindex_contract.balance(&users[0]), 0);
+    assert_eq!(defindex_contract.balance(&users[1]), 0);
+    assert_eq!(defindex_contract.balance(&defindex_contract.address), 1000 + shares_0 + shares_1);
+
+    // Requests are completed in order
+    let result = defindex_contract.try_claim_withdraw(&request_1);
+    assert_eq!(result, Err(Err(VaultError::WithdrawRequestNotFirst.into())));
+
+    // Not enough liquidity yet
+    let result = defindex_contract.try_claim_withdraw(&request_0);
+    assert_eq!(result, Err(Ok(ContractError::StrategyWithdrawError)));
+    let result = defindex_contract.try_process_withdraw_queue(&10u32);
+    assert_eq!(result, Err(Ok(ContractError::StrategyWithdrawError)));
+
+    // Liquidity returns, the first request is claimed
+    test.token_0.transfer(&parking, &test.strategy_client_token_0.address, &(amount * 2));
+    let withdrawn = defindex_contract.claim_withdraw(&request_0);
+    assert_eq!(withdrawn, sorobanvec![&test.env, amount - 1000]);
+    assert_eq!(test.token_0.balance(&users[0]), amount - 1000);
+    assert_eq!(defindex_contract.get_withdraw_queue(), (1u64, 2u64));
+
+    let result = defindex_contract.try_get_withdraw_request(&request_0);
+    assert_eq!(result, Err(Err(VaultError::WithdrawRequestNotFound.into())));
+
+    // A keeper processes the rest of the queue
+    let processed = defindex_contract.process_withdraw_queue(&10u32);
+    assert_eq!(processed, sorobanvec![&test.env, request_1]);
+    assert_eq!(test.token_0.balance(&users[1]), amount);
+    assert_eq!(defindex_contract.get_withdraw_queue(), (2u64, 2u64));
+    assert_eq!(defindex_contract.total_supply(), 1000);
+    assert_eq!(defindex_contract.balance(&defindex_contract.address), 1000);
+
+    // Empty queue
+    let processed = defindex_contract.process_withdraw_queue(&10u32);
+    assert_eq!(processed.len(), 0);
+}
+
+#[test]
+fn process_withdraw_queue_is_bounded() {
+    let test = DeFindexVaultTest::setup();
+    test.env.mock_all_auths();
+    let strategy_params_token_0 = create_strategy_params_token_0(&test);
+
+    let assets: Vec<AssetStrategySet> = sorobanvec![
+        &test.env,
+        AssetStrategySet {
+            address: test.token_0.address.clone(),
+            strategies: strategy_params_token_0.clone()
+        }
+    ];
+
+    let mut roles: Map<u32, Address> = Map::new(&test.env);
+    roles.set(RolesDataKey::Manager as u32, test.manager.clone());
+    roles.set(RolesDataKey::EmergencyManager as u32, test.emergency_manager.clone());
+    roles.set(RolesDataKey::VaultFeeReceiver as u32, test.vault_fee_receiver.clone());
+    roles.set(RolesDataKey::RebalanceManager as u32, test.rebalance_manager.clone());
+
+    let mut name_symbol: Map<String, String> = Map::new(&test.env);
+    name_symbol.set(String::from_str(&test.env, "name"), String::from_str(&test.env, "dfToken"));
+    name_symbol.set(String::from_str(&test.env, "symbol"), String::from_str(&test.env, "DFT"));
+
+    let defindex_contract = create_defindex_vault(
+        &test.env,
+        assets,
+        roles,
+        2000u32,
+        test.defindex_protocol_receiver.clone(),
+        2500u32,
+        test.soroswap_router.address.clone(),
+        name_symbol,
+        true
+    );
+
+    let amount = 1_000_000_000i128;
+    let users = DeFindexVaultTest::generate_random_users(&test.env, 1);
+    test.token_0_admin_client.mint(&users[0], &amount);
+
+    defindex_contract.deposit(&sorobanvec![&test.env, amount], &sorobanvec![&test.env, amount], &users[0], &false);
+
+    for _ in 0..3 {
+        defindex_contract.request_withdraw(&100_000_000i128, &users[0]);
+    }
+
+    let processed = defindex_contract.process_withdraw_queue(&2u32);
+    assert_eq!(processed, sorobanvec![&test.env, 0u64, 1u64]);
+    assert_eq!(test.token_0.balance(&users[0]), 200_000_000i128);
+    assert_eq!(defindex_contract.get_withdraw_queue(), (2u64, 3u64));
+
+    let result = defindex_contract.try_request_withdraw(&0i128, &users[0]);
+    assert_eq!(result, Err(Ok(ContractError::AmountNotAllowed)));
+}
+
+#[test]
+fn direct_withdrawals_cannot_jump_the_queue() {
+    let test = DeFindexVaultTest::setup();
+    test.env.mock_all_auths();
+    let strategy_params_token_0 = create_strategy_params_token_0(&test);
+
+    let assets: Vec<AssetStrategySet> = sorobanvec![
+        &test.env,
+        AssetStrategySet {
+            address: test.token_0.address.clone(),
+            strategies: strategy_params_token_0.clone()
+        }
+    ];
+
+    let mut roles: Map<u32, Address> = Map::new(&test.env);
+    roles.set(RolesDataKey::Manager as u32, test.manager.clone());
+    roles.set(RolesDataKey::EmergencyManager as u32, test.emergency_manager.clone());
+    roles.set(RolesDataKey::VaultFeeReceiver as u32, test.vault_fee_receiver.clone());
+    roles.set(RolesDataKey::RebalanceManager as u32, test.rebalance_manager.clone());
+
+    let mut name_symbol: Map<String, String> = Map::new(&test.env);
+    name_symbol.set(String::from_str(&test.env, "name"), String::from_str(&test.env, "dfToken"));
+    name_symbol.set(String::from_str(&test.env, "symbol"), String::from_str(&test.env, "DFT"));
+
+    let defindex_contract = create_defindex_vault(
+        &test.env,
+        assets,
+        roles,
+        2000u32,
+        test.defindex_protocol_receiver.clone(),
+        2500u32,
+        test.soroswap_router.address.clone(),
+        name_symbol,
+        true
+    );
+
+    let amount = 1_000_000_000i128;
+    let users = DeFindexVaultTest::generate_random_users(&test.env, 2);
+    test.token_0_admin_client.mint(&users[0], &amount);
+    test.token_0_admin_client.mint(&users[1], &amount);
+
+    defindex_contract.deposit(&sorobanvec![&test.env, amount], &sorobanvec![&test.env, amount], &users[0], &false);
+    defindex_contract.deposit(&sorobanvec![&test.env, amount], &sorobanvec![&test.env, amount], &users[1], &false);
+    defindex_contract.rebalance(
+        &test.rebalance_manager,
+        &sorobanvec![&test.env, Instruction::Invest(test.strategy_client_token_0.address.clone(), amount * 2)],
+        &None,
+    );
+
+    // The strategy becomes illiquid and the first user queues a withdrawal
+    let parking = Address::generate(&test.env);
+    test.token_0.transfer(&test.strategy_client_token_0.address, &parking, &(amount * 2));
+    let shares_0 = defindex_contract.balance(&users[0]);
+    let request_0 = defindex_contract.request_withdraw(&shares_0, &users[0]);
+
+    // Once liquidity returns, the second user cannot withdraw before the queued request is served
+    test.token_0.transfer(&parking, &test.strategy_client_token_0.address, &(amount * 2));
+    let shares_1 = defindex_contract.balance(&users[1]);
+    let result = defindex_contract.try_withdraw(&shares_1, &sorobanvec![&test.env, 0i128], &users[1]);
+    assert_eq!(result, Err(Err(VaultError::WithdrawRequestNotFirst.into())));
+    let result = defindex_contract.try_withdraw_to_asset(&shares_1, &test.token_0.address, &0i128, &users[1]);
+    assert_eq!(result, Err(Err(VaultError::WithdrawRequestNotFirst.into())));
+    let result = defindex_contract.try_withdraw_exact_amounts(&sorobanvec![&test.env, amount], &shares_1, &users[1]);
+    assert_eq!(result, Err(Err(VaultError::WithdrawRequestNotFirst.into())));
+
+    defindex_contract.claim_withdraw(&request_0);
+    assert_eq!(test.token_0.balance(&users[0]), amount - 1000);
+
+    let withdrawn = defindex_contract.withdraw(&shares_1, &sorobanvec![&test.env, 0i128], &users[1]);
+    assert_eq!(withdrawn, sorobanvec![&test.env, amount]);
+}
+
+#[test]
+fn cancelled_requests_return_the_shares_and_are_skipped() {
+    let test = DeFindexVaultTest::setup();
+    test.env.mock_all_auths();
+    let strategy_params_token_0 = create_strategy_params_token_0(&test);
+
+    let assets: Vec<AssetStrategySet> = sorobanvec![
+        &test.env,
+        AssetStrategySet {
+            address: test.token_0.address.clone(),
+            strategies: strategy_params_token_0.clone()
+        }
+    ];
+
+    let mut roles: Map<u32, Address> = Map::new(&test.env);
+    roles.set(RolesDataKey::Manager as u32, test.manager.clone());
+    roles.set(RolesDataKey::EmergencyManager as u32, test.emergency_manager.clone());
+    roles.set(RolesDataKey::VaultFeeReceiver as u32, test.vault_fee_receiver.clone());
+    roles.set(RolesDataKey::RebalanceManager as u32, test.rebalance_manager.clone());
+
+    let mut name_symbol: Map<String, String> = Map::new(&test.env);
+    name_symbol.set(String::from_str(&test.env, "name"), String::from_str(&test.env, "dfToken"));
+    name_symbol.set(String::from_str(&test.env, "symbol"), String::from_str(&test.env, "DFT"));
+
+    let defindex_contract = create_defindex_vault(
+        &test.env,
+        assets,
+        roles,
+        2000u32,
+        test.defindex_protocol_receiver.clone(),
+        2500u32,
+        test.soroswap_router.address.clone(),
+        name_symbol,
+        true
+    );
+
+    let amount = 1_000_000_000i128;
+    let users = DeFindexVaultTest::generate_random_users(&test.env, 3);
+    for user in users.iter() {
+        test.token_0_admin_client.mint(user, &amount);
+        defindex_contract.deposit(&sorobanvec![&test.env, amount], &sorobanvec![&test.env, amount], user, &false);
+    }
+    defindex_contract.rebalance(
+        &test.rebalance_manager,
+        &sorobanvec![&test.env, Instruction::Invest(test.strategy_client_token_0.address.clone(), amount * 3)],
+        &None,
+    );
+
+    // The strategy becomes illiquid and every user queues a withdrawal
+    let parking = Address::generate(&test.env);
+    test.token_0.transfer(&test.strategy_client_token_0.address, &parking, &(amount * 3));
+    let mut request_ids: Vec<u64> = Vec::new(&test.env);
+    for user in users.iter() {
+        let shares = defindex_contract.balance(user);
+        request_ids.push_back(defindex_contract.request_withdraw(&shares, user));
+    }
+
+    // A request in the middle of the queue is cancelled and its escrowed shares are returned
+    let escrowed = defindex_contract.get_withdraw_request(&request_ids.get(1).unwrap()).shares;
+    let returned = defindex_contract.cancel_withdraw_request(&request_ids.get(1).unwrap());
+    assert_eq!(returned, escrowed);
+    assert_eq!(defindex_contract.balance(&users[1]), escrowed);
+    let result = defindex_contract.try_get_withdraw_request(&request_ids.get(1).unwrap());
+    assert_eq!(result, Err(Err(VaultError::WithdrawRequestNotFound.into())));
+
+    // Once liquidity returns, the cancelled request is skipped
+    test.token_0.transfer(&parking, &test.strategy_client_token_0.address, &(amount * 3));
+    let processed = defindex_contract.process_withdraw_queue(&10u32);
+    assert_eq!(processed, sorobanvec![&test.env, request_ids.get(0).unwrap(), request_ids.get(2).unwrap()]);
+    assert_eq!(defindex_contract.get_withdraw_queue(), (3u64, 3u64));
+
+    // The queue is empty, so the user that cancelled can withdraw directly
+    let withdrawn = defindex_contract.withdraw(&escrowed, &sorobanvec![&test.env, 0i128], &users[1]);
+    assert_eq!(withdrawn, sorobanvec![&test.env, escrowed]);
+}
+
+#[test]
+fn a_stuck_head_can_be_parked_and_cancelled_by_its_owner() {
+    let test = DeFindexVaultTest::setup();
+    test.env.mock_all_auths();
+    let strategy_params_token_0 = create_strategy_params_token_0(&test);
+
+    let assets: Vec<AssetStrategySet> = sorobanvec![
+        &test.env,
+        AssetStrategySet {
+            address: test.token_0.address.clone(),
+            strategies: strategy_params_token_0.clone()
+        }
+    ];
+
+    let mut roles: Map<u32, Address> = Map::new(&test.env);
+    roles.set(RolesDataKey::Manager as u32, test.manager.clone());
+    roles.set(RolesDataKey::EmergencyManager as u32, test.emergency_manager.clone());
+    roles.set(RolesDataKey::VaultFeeReceiver as u32, test.vault_fee_receiver.clone());
+    roles.set(RolesDataKey::RebalanceManager as u32, test.rebalance_manager.clone());
+
+    let mut name_symbol: Map<String, String> = Map::new(&test.env);
+    name_symbol.set(String::from_str(&test.env, "name"), String::from_str(&test.env, "dfToken"));
+    name_symbol.set(String::from_str(&test.env, "symbol"), String::from_str(&test.env, "DFT"));
+
+    let defindex_contract = create_defindex_vault(
+        &test.env,
+        assets,
+        roles,
+        2000u32,
+        test.defindex_protocol_receiver.clone(),
+        2500u32,
+        test.soroswap_router.address.clone(),
+        name_symbol,
+        true
+    );
+
+    let amount = 1_000_000_000i128;
+    let users = DeFindexVaultTest::generate_random_users(&test.env, 2);
+    test.token_0_admin_client.mint(&users[0], &amount);
+    test.token_0_admin_client.mint(&users[1], &amount);
+
+    defindex_contract.deposit(&sorobanvec![&test.env, amount], &sorobanvec![&test.env, amount], &users[0], &false);
+    defindex_contract.deposit(&sorobanvec![&test.env, amount], &sorobanvec![&test.env, amount], &users[1], &false);
+    defindex_contract.rebalance(
+        &test.rebalance_manager,
+        &sorobanvec![&test.env, Instruction::Invest(test.strategy_client_token_0.address.clone(), amount * 2)],
+        &None,
+    );
+
+    // The strategy becomes illiquid and the first user queues a withdrawal that cannot complete
+    let parking = Address::generate(&test.env);
+    test.token_0.transfer(&test.strategy_client_token_0.address, &parking, &(amount * 2));
+    let shares_0 = defindex_contract.balance(&users[0]);
+    let request_0 = defindex_contract.request_withdraw(&shares_0, &users[0]);
+    let escrowed = defindex_contract.get_withdraw_request(&request_0).shares;
+    assert!(defindex_contract.try_claim_withdraw(&request_0).is_err());
+
+    // Only the request at the head of the queue can be parked
+    let result = defindex_contract.try_park_withdraw_request(&(request_0 + 1));
+    assert_eq!(result, Err(Err(VaultError::WithdrawRequestNotFound.into())));
+    defindex_contract.park_withdraw_request(&request_0);
+    assert_eq!(defindex_contract.get_withdraw_queue(), (1u64, 1u64));
+
+    // Direct withdrawals are no longer blocked, and the parked request cannot be claimed
+    test.token_0.transfer(&parking, &test.strategy_client_token_0.address, &(amount * 2));
+    let shares_1 = defindex_contract.balance(&users[1]);
+    let withdrawn = defindex_contract.withdraw(&shares_1, &sorobanvec![&test.env, 0i128], &users[1]);
+    assert_eq!(withdrawn, sorobanvec![&test.env, amount]);
+    let result = defindex_contract.try_claim_withdraw(&request_0);
+    assert_eq!(result, Err(Err(VaultError::WithdrawRequestNotFirst.into())));
+
+    // The owner recovers the escrowed shares
+    defindex_contract.cancel_withdraw_request(&request_0);
+    assert_eq!(defindex_contract.balance(&users[0]), escrowed);
+    assert_eq!(defindex_contract.get_withdraw_queue(), (1u64, 1u64));
+}
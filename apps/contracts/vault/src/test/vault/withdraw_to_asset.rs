@@ -0,0 +1,378 @@
+use soroban_sdk::{vec as sorobanvec, Address, Map, String, Vec};
+
+use crate::test::defindex_vault::{AssetStrategySet, ContractError, Instruction, RolesDataKey};
+use crate::test::{
+    create_defindex_vault, create_strategy_params_token_0, create_strategy_params_token_1,
+    DeFindexVaultTest,
+};
+
+#[test]
+fn idle_funds_success() {
+    let test = DeFindexVaultTest::setup();
+    test.env.mock_all_auths();
+    let strategy_params_token_0 = create_strategy_params_token_0(&test);
+    let strategy_params_token_1 = create_strategy_params_token_1(&test);
+
+    let assets: Vec<AssetStrategySet> = sorobanvec![
+        &test.env,
+        AssetStrategySet {
+            address: test.token_0.address.clone(),
+            strategies: strategy_params_token_0.clone()
+        },
+        AssetStrategySet {
+            address: test.token_1.address.clone(),
+            strategies: strategy_params_token_1.clone()
+        }
+    ];
+
+    let mut roles: Map<u32, Address> = Map::new(&test.env);
+    roles.set(RolesDataKey::Manager as u32, test.manager.clone());
+    roles.set(RolesDataKey::EmergencyManager as u32, test.emergency_manager.clone());
+    roles.set(RolesDataKey::VaultFeeReceiver as u32, test.vault_fee_receiver.clone());
+    roles.set(RolesDataKey::RebalanceManager as u32, test.rebalance_manager.clone());
+
+    let mut name_symbol: Map<String, String> = Map::new(&test.env);
+    name_symbol.set(String::from_str(&test.env, "name"), String::from_str(&test.env, "dfToken"));
+    name_symbol.set(String::from_str(&test.env, "symbol"), String::from_str(&test.env, "DFT"));
+
+    let defindex_contract = create_defindex_vault(
+        &test.env,
+        assets,
+        roles,
+        2000u32,
+        test.defindex_protocol_receiver.clone(),
+        2500u32,
+        test.soroswap_router.address.clone(),
+        name_symbol,
+        true
+    );
+
+    // Soroswap pool token_0/token_1 has a 1:4 ratio
+    let amount0 = 1_000_000_000i128;
+    let amount1 = 4_000_000_000i128;
+
+    let users = DeFindexVaultTest::generate_random_users(&test.env, 1);
+    test.token_0_admin_client.mint(&users[0], &amount0);
+    test.token_1_admin_client.mint(&users[0], &amount1);
+
+    defindex_contract.deposit(
+        &sorobanvec![&test.env, amount0, amount1],
+        &sorobanvec![&test.env, amount0, amount1],
+        &users[0],
+        &false,
+    );
+
+    let user_shares = defindex_contract.balance(&users[0]);
+    let withdraw_shares = user_shares / 2;
+    let total_supply = defindex_contract.total_supply();
+    let expected_amount0 = amount0 * withdraw_shares / total_supply;
+    let expected_amount1 = amount1 * withdraw_shares / total_supply;
+
+    let amount_out = defindex_contract.withdraw_to_asset(
+        &withdraw_shares,
+        &test.token_0.address,
+        &0i128,
+        &users[0],
+    );
+
+    // token_1 is swapped at the pool price, minus the 0.3% fee
+    let expected_swapped = expected_amount1 * 997 / 4000;
+    assert!(amount_out > expected_amount0 + expected_swapped * 999 / 1000);
+    assert!(amount_out <= expected_amount0 + expected_amount1 / 4);
+
+    assert_eq!(test.token_0.balance(&users[0]), amount_out);
+    assert_eq!(test.token_1.balance(&users[0]), 0);
+    assert_eq!(defindex_contract.balance(&users[0]), user_shares - withdraw_shares);
+
+    assert_eq!(test.token_0.balance(&defindex_contract.address), amount0 - expected_amount0);
+    assert_eq!(test.token_1.balance(&defindex_contract.address), amount1 - expected_amount1);
+}
+
+#[test]
+fn invested_funds_success() {
+    let test = DeFindexVaultTest::setup();
+    test.env.mock_all_auths();
+    let strategy_params_token_0 = create_strategy_params_token_0(&test);
+    let strategy_params_token_1 = create_strategy_params_token_1(&test);
+
+    let assets: Vec<AssetStrategySet> = sorobanvec![
+        &test.env,
+        AssetStrategySet {
+            address: test.token_0.address.clone(),
+            strategies: strategy_params_token_0.clone()
+        },
+        AssetStrategySet {
+            address: test.token_1.address.clone(),
+            strategies: strategy_params_token_1.clone()
+        }
+    ];
+
+    let mut roles: Map<u32, Address> = Map::new(&test.env);
+    roles.set(RolesDataKey::Manager as u32, test.manager.clone());
+    roles.set(RolesDataKey::EmergencyManager as u32, test.emergency_manager.clone());
+    roles.set(RolesDataKey::VaultFeeReceiver as u32, test.vault_fee_receiver.clone());
+    roles.set(RolesDataKey::RebalanceManager as u32, test.rebalance_manager.clone());
+
+    let mut name_symbol: Map<String, String> = Map::new(&test.env);
+    name_symbol.set(String::from_str(&test.env, "name"), String::from_str(&test.env, "dfToken"));
+    name_symbol.set(String::from_str(&test.env, "symbol"), String::from_str(&test.env, "DFT"));
+
+    let defindex_contract = create_defindex_vault(
+        &test.env,
+        assets,
+        roles,
+        2000u32,
+        test.defindex_protocol_receiver.clone(),
+        2500u32,
+        test.soroswap_router.address.clone(),
+        name_symbol,
+        true
+    );
+
+    let amount0 = 1_000_000_000i128;
+    let amount1 = 4_000_000_000i128;
+
+    let users = DeFindexVaultTest::generate_random_users(&test.env, 1);
+    test.token_0_admin_client.mint(&users[0], &amount0);
+    test.token_1_admin_client.mint(&users[0], &amount1);
+
+    defindex_contract.deposit(
+        &sorobanvec![&test.env, amount0, amount1],
+        &sorobanvec![&test.env, amount0, amount1],
+        &users[0],
+        &false,
+    );
+
+    let invest_instructions = sorobanvec![
+        &test.env,
+        Instruction::Invest(test.strategy_client_token_0.address.clone(), amount0),
+        Instruction::Invest(test.strategy_client_token_1.address.clone(), amount1),
+    ];
+    defindex_contract.rebalance(&test.rebalance_manager, &invest_instructions, &None);
+
+    let user_shares = defindex_contract.balance(&users[0]);
+    let total_supply = defindex_contract.total_supply();
+    let expected_amount0 = amount0 * user_shares / total_supply;
+    let expected_amount1 = amount1 * user_shares / total_supply;
+
+    let amount_out = defindex_contract.withdraw_to_asset(
+        &user_shares,
+        &test.token_1.address,
+        &0i128,
+        &users[0],
+    );
+
+    // token_0 is swapped at the pool price, minus the 0.3% fee
+    let expected_swapped = expected_amount0 * 4 * 997 / 1000;
+    assert!(amount_out > expected_amount1 + expected_swapped * 999 / 1000);
+    assert!(amount_out <= expected_amount1 + expected_amount0 * 4);
+
+    assert_eq!(test.token_1.balance(&users[0]), amount_out);
+    assert_eq!(test.token_0.balance(&users[0]), 0);
+    assert_eq!(defindex_contract.balance(&users[0]), 0);
+
+    // Nothing is left idle in the vault, everything was unwound from the strategies
+    assert_eq!(test.token_0.balance(&defindex_contract.address), 0);
+    assert_eq!(test.token_1.balance(&defindex_contract.address), 0);
+    assert_eq!(test.token_0.balance(&test.strategy_client_token_0.address), amount0 - expected_amount0);
+    assert_eq!(test.token_1.balance(&test.strategy_client_token_1.address), amount1 - expected_amount1);
+}
+
+#[test]
+fn min_out_not_reached() {
+    let test = DeFindexVaultTest::setup();
+    test.env.mock_all_auths();
+    let strategy_params_token_0 = create_strategy_params_token_0(&test);
+    let strategy_params_token_1 = create_strategy_params_token_1(&test);
+
+    let assets: Vec<AssetStrategySet> = sorobanvec![
+        &test.env,
+        AssetStrategySet {
+            address: test.token_0.address.clone(),
+            strategies: strategy_params_token_0.clone()
+        },
+        AssetStrategySet {
+            address: test.token_1.address.clone(),
+            strategies: strategy_params_token_1.clone()
+        }
+    ];
+
+    let mut roles: Map<u32, Address> = Map::new(&test.env);
+    roles.set(RolesDataKey::Manager as u32, test.manager.clone());
+    roles.set(RolesDataKey::EmergencyManager as u32, test.emergency_manager.clone());
+    roles.set(RolesDataKey::VaultFeeReceiver as u32, test.vault_fee_receiver.clone());
+    roles.set(RolesDataKey::RebalanceManager as u32, test.rebalance_manager.clone());
+
+    let mut name_symbol: Map<String, String> = Map::new(&test.env);
+    name_symbol.set(String::from_str(&test.env, "name"), String::from_str(&test.env, "dfToken"));
+    name_symbol.set(String::from_str(&test.env, "symbol"), String::from_str(&test.env, "DFT"));
+
+    let defindex_contract = create_defindex_vault(
+        &test.env,
+        assets,
+        roles,
+        2000u32,
+        test.defindex_protocol_receiver.clone(),
+        2500u32,
+        test.soroswap_router.address.clone(),
+        name_symbol,
+        true
+    );
+
+    let amount0 = 1_000_000_000i128;
+    let amount1 = 4_000_000_000i128;
+
+    let users = DeFindexVaultTest::generate_random_users(&test.env, 1);
+    test.token_0_admin_client.mint(&users[0], &amount0);
+    test.token_1_admin_client.mint(&users[0], &amount1);
+
+    defindex_contract.deposit(
+        &sorobanvec![&test.env, amount0, amount1],
+        &sorobanvec![&test.env, amount0, amount1],
+        &users[0],
+        &false,
+    );
+
+    let user_shares = defindex_contract.balance(&users[0]);
+
+    // The swap fee makes it impossible to get back the full value in token_0
+    let result = defindex_contract.try_withdraw_to_asset(
+        &user_shares,
+        &test.token_0.address,
+        &(amount0 * 2),
+        &users[0],
+    );
+    assert_eq!(result, Err(Ok(ContractError::InsufficientOutputAmount)));
+}
+
+#[test]
+fn unsupported_asset() {
+    let test = DeFindexVaultTest::setup();
+    test.env.mock_all_auths();
+    let strategy_params_token_0 = create_strategy_params_token_0(&test);
+    let strategy_params_token_1 = create_strategy_params_token_1(&test);
+
+    let assets: Vec<AssetStrategySet> = sorobanvec![
+        &test.env,
+        AssetStrategySet {
+            address: test.token_0.address.clone(),
+            strategies: strategy_params_token_0.clone()
+        },
+        AssetStrategySet {
+            address: test.token_1.address.clone(),
+            strategies: strategy_params_token_1.clone()
+        }
+    ];
+
+    let mut roles: Map<u32, Address> = Map::new(&test.env);
+    roles.set(RolesDataKey::Manager as u32, test.manager.clone());
+    roles.set(RolesDataKey::EmergencyManager as u32, test.emergency_manager.clone());
+    roles.set(RolesDataKey::VaultFeeReceiver as u32, test.vault_fee_receiver.clone());
+    roles.set(RolesDataKey::RebalanceManager as u32, test.rebalance_manager.clone());
+
+    let mut name_symbol: Map<String, String> = Map::new(&test.env);
+    name_symbol.set(String::from_str(&test.env, "name"), String::from_str(&test.env, "dfToken"));
+    name_symbol.set(String::from_str(&test.env, "symbol"), String::from_str(&test.env, "DFT"));
+
+    let defindex_contract = create_defindex_vault(
+        &test.env,
+        assets,
+        roles,
+        2000u32,
+        test.defindex_protocol_receiver.clone(),
+        2500u32,
+        test.soroswap_router.address.clone(),
+        name_symbol,
+        true
+    );
+
+    let amount0 = 1_000_000_000i128;
+    let amount1 = 4_000_000_000i128;
+
+    let users = DeFindexVaultTest::generate_random_users(&test.env, 1);
+    test.token_0_admin_client.mint(&users[0], &amount0);
+    test.token_1_admin_client.mint(&users[0], &amount1);
+
+    defindex_contract.deposit(
+        &sorobanvec![&test.env, amount0, amount1],
+        &sorobanvec![&test.env, amount0, amount1],
+        &users[0],
+        &false,
+    );
+
+    let user_shares = defindex_contract.balance(&users[0]);
+
+    let result = defindex_contract.try_withdraw_to_asset(
+        &user_shares,
+        &test.token_2.address,
+        &0i128,
+        &users[0],
+    );
+    assert_eq!(result, Err(Ok(ContractError::UnsupportedAsset)));
+}
+
+#[test]
+fn amount_over_total_supply() {
+    let test = DeFindexVaultTest::setup();
+    test.env.mock_all_auths();
+    let strategy_params_token_0 = create_strategy_params_token_0(&test);
+    let strategy_params_token_1 = create_strategy_params_token_1(&test);
+
+    let assets: Vec<AssetStrategySet> = sorobanvec![
+        &test.env,
+        AssetStrategySet {
+            address: test.token_0.address.clone(),
+            strategies: strategy_params_token_0.clone()
+        },
+        AssetStrategySet {
+            address: test.token_1.address.clone(),
+            strategies: strategy_params_token_1.clone()
+        }
+    ];
+
+    let mut roles: Map<u32, Address> = Map::new(&test.env);
+    roles.set(RolesDataKey::Manager as u32, test.manager.clone());
+    roles.set(RolesDataKey::EmergencyManager as u32, test.emergency_manager.clone());
+    roles.set(RolesDataKey::VaultFeeReceiver as u32, test.vault_fee_receiver.clone());
+    roles.set(RolesDataKey::RebalanceManager as u32, test.rebalance_manager.clone());
+
+    let mut name_symbol: Map<String, String> = Map::new(&test.env);
+    name_symbol.set(String::from_str(&test.env, "name"), String::from_str(&test.env, "dfToken"));
+    name_symbol.set(String::from_str(&test.env, "symbol"), String::from_str(&test.env, "DFT"));
+
+    let defindex_contract = create_defindex_vault(
+        &test.env,
+        assets,
+        roles,
+        2000u32,
+        test.defindex_protocol_receiver.clone(),
+        2500u32,
+        test.soroswap_router.address.clone(),
+        name_symbol,
+        true
+    );
+
+    let amount0 = 1_000_000_000i128;
+    let amount1 = 4_000_000_000i128;
+
+    let users = DeFindexVaultTest::generate_random_users(&test.env, 1);
+    test.token_0_admin_client.mint(&users[0], &amount0);
+    test.token_1_admin_client.mint(&users[0], &amount1);
+
+    defindex_contract.deposit(
+        &sorobanvec![&test.env, amount0, amount1],
+        &sorobanvec![&test.env, amount0, amount1],
+        &users[0],
+        &false,
+    );
+
+    let total_supply = defindex_contract.total_supply();
+
+    let result = defindex_contract.try_withdraw_to_asset(
+        &(total_supply + 1),
+        &test.token_0.address,
+        &0i128,
+        &users[0],
+    );
+    assert_eq!(result, Err(Ok(ContractError::AmountOverTotalSupply)));
+}
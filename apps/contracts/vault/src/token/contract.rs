@@ -6,8 +6,9 @@ use crate::token::metadata::{read_decimal, read_name, read_symbol};
 use crate::token::total_supply::{decrease_total_supply, increase_total_supply, read_total_supply};
 
 use crate::token::storage_types::{INSTANCE_BUMP_AMOUNT, INSTANCE_LIFETIME_THRESHOLD};
+use crate::utils::require_allowlisted;
 use soroban_sdk::token::{self, Interface as _};
-use soroban_sdk::{contract, contractimpl, Address, Env, String};
+use soroban_sdk::{contract, contractimpl, panic_with_error, Address, Env, String};
 use soroban_token_sdk::TokenUtils;
 
 fn check_nonnegative_amount(amount: i128) {
@@ -16,6 +17,12 @@ fn check_nonnegative_amount(amount: i128) {
     }
 }
 
+fn check_allowlisted(e: &Env, to: &Address) {
+    if let Err(err) = require_allowlisted(e, to) {
+        panic_with_error!(e, err);
+    }
+}
+
 pub fn internal_burn(e: Env, from: Address, amount: i128) {
     check_nonnegative_amount(amount);
 
@@ -44,6 +51,18 @@ pub fn internal_mint(e: Env, to: Address, amount: i128) {
         .mint(e.current_contract_address(), to, amount);
 }
 
+pub fn internal_transfer(e: Env, from: Address, to: Address, amount: i128) {
+    check_nonnegative_amount(amount);
+
+    e.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+
+    spend_balance(&e, from.clone(), amount);
+    receive_balance(&e, to.clone(), amount);
+    TokenUtils::new(&e).events().transfer(from, to, amount);
+}
+
 #[contract]
 pub struct VaultToken;
 
@@ -89,6 +108,7 @@ impl token::Interface for VaultToken {
         from.require_auth();
 
         check_nonnegative_amount(amount);
+        check_allowlisted(&e, &to);
 
         e.storage()
             .instance()
@@ -103,6 +123,7 @@ impl token::Interface for VaultToken {
         spender.require_auth();
 
         check_nonnegative_amount(amount);
+        check_allowlisted(&e, &to);
 
         e.storage()
             .instance()
@@ -9,5 +9,5 @@ mod total_supply;
 
 pub use contract::VaultToken;
 // pub use contract::VaultTokenClient;
-pub use contract::{internal_burn, internal_mint};
+pub use contract::{internal_burn, internal_mint, internal_transfer};
 pub use metadata::write_metadata;
@@ -1,11 +1,17 @@
 use common::models::{AssetStrategySet, Strategy};
-use soroban_sdk::{panic_with_error, Env, Map, Vec};
+use soroban_sdk::{panic_with_error, Address, Env, Map, Vec};
 
 use crate::{
     //access::{AccessControl, AccessControlTrait, RolesDataKey},
-    models::CurrentAssetInvestmentAllocation,
+    constants::SCALAR_BPS,
+    events,
+    models::{AssetTargetAllocation, CurrentAssetInvestmentAllocation, Proposal, ProposalAction},
+    storage::{
+        get_manager_timelock, get_next_proposal_id, get_pause_flags, is_allowlist_enabled, is_allowlisted,
+        set_next_proposal_id, set_proposal,
+    },
     token::VaultToken,
-    ContractError,
+    ContractError, VaultError,
 };
 
 
@@ -51,6 +57,130 @@ pub fn validate_strategies(e: &Env, strategies: &Vec<Strategy>){
     }
 }
 
+/// Stores a proposal executable once the manager timelock has elapsed and returns its id.
+pub fn queue_proposal(e: &Env, action: ProposalAction) -> Result<u64, ContractError> {
+    let executable_at = e
+        .ledger()
+        .timestamp()
+        .checked_add(get_manager_timelock(e))
+        .ok_or(ContractError::Overflow)?;
+    let proposal_id = get_next_proposal_id(e);
+    let proposal = Proposal {
+        action,
+        executable_at,
+    };
+    set_proposal(e, proposal_id, &proposal);
+    set_next_proposal_id(e, &(proposal_id + 1));
+
+    events::emit_proposal_created_event(e, proposal_id, proposal.action, executable_at);
+    Ok(proposal_id)
+}
+
+/// Fails with `VaultError::DepositsPaused` if the Emergency Manager paused deposits.
+pub fn require_deposits_enabled(e: &Env) {
+    if get_pause_flags(e).deposits {
+        panic_with_error!(e, VaultError::DepositsPaused);
+    }
+}
+
+/// Returns `ContractError::NotAllowlisted` if the allowlist is enabled and `address` is not on it.
+///
+/// The vault itself is always allowed to hold its own shares.
+pub fn require_allowlisted(e: &Env, address: &Address) -> Result<(), ContractError> {
+    if is_allowlist_enabled(e)
+        && *address != e.current_contract_address()
+        && !is_allowlisted(e, address)
+    {
+        return Err(ContractError::NotAllowlisted);
+    }
+    Ok(())
+}
+
+/// Fails with `VaultError::WithdrawalsPaused` if the Emergency Manager paused withdrawals.
+pub fn require_withdrawals_enabled(e: &Env) {
+    if get_pause_flags(e).withdrawals {
+        panic_with_error!(e, VaultError::WithdrawalsPaused);
+    }
+}
+
+/// Fails with `VaultError::RebalancesPaused` if the Emergency Manager paused rebalances.
+pub fn require_rebalances_enabled(e: &Env) {
+    if get_pause_flags(e).rebalances {
+        panic_with_error!(e, VaultError::RebalancesPaused);
+    }
+}
+
+/// Validates a strategy withdraw order against the vault assets.
+///
+/// The asset must be managed by the vault and every strategy must be one of the asset strategies,
+/// listed at most once.
+pub fn validate_withdraw_order(
+    e: &Env,
+    assets: &Vec<AssetStrategySet>,
+    asset_address: &Address,
+    strategies: &Vec<Address>,
+) -> Result<(), ContractError> {
+    let asset = assets
+        .iter()
+        .find(|asset| &asset.address == asset_address)
+        .ok_or(ContractError::AssetNotFound)?;
+
+    let mut strategy_addresses = Map::new(e);
+    for strategy_address in strategies.iter() {
+        if !asset.strategies.iter().any(|strategy| strategy.address == strategy_address) {
+            return Err(ContractError::StrategyNotFound);
+        }
+        if strategy_addresses.contains_key(strategy_address.clone()) {
+            return Err(ContractError::DuplicatedStrategy);
+        }
+        strategy_addresses.set(strategy_address, true);
+    }
+    Ok(())
+}
+
+/// Calculates how much idle liquidity is missing for an asset to hold its idle reserve.
+///
+/// The reserve is `reserve_bps` of `total_amount`, the asset's total managed funds once the operation
+/// being processed is applied. Returns zero if `idle_amount` already covers the reserve.
+pub fn calculate_idle_reserve_deficit(
+    total_amount: i128,
+    idle_amount: i128,
+    reserve_bps: u32,
+) -> Result<i128, ContractError> {
+    let reserve = total_amount
+        .checked_mul(reserve_bps as i128)
+        .ok_or(ContractError::ArithmeticError)?
+        .checked_div(SCALAR_BPS)
+        .ok_or(ContractError::ArithmeticError)?;
+    Ok(reserve.checked_sub(idle_amount).ok_or(ContractError::Underflow)?.max(0))
+}
+
+/// Validates a target allocation against the vault assets.
+///
+/// The asset must be managed by the vault, every target must reference one of the asset strategies
+/// at most once, and `idle_bps` plus every strategy weight must add up to `SCALAR_BPS`.
+pub fn validate_target_allocation(
+    e: &Env,
+    assets: &Vec<AssetStrategySet>,
+    target: &AssetTargetAllocation,
+) -> Result<(), ContractError> {
+    // The targeted strategies follow the same rules as a withdraw order
+    let mut strategies: Vec<Address> = Vec::new(e);
+    let mut total_bps: i128 = target.idle_bps as i128;
+    for strategy_target in target.strategy_targets.iter() {
+        strategies.push_back(strategy_target.strategy_address);
+        total_bps = total_bps
+            .checked_add(strategy_target.weight_bps as i128)
+            .ok_or(ContractError::Overflow)?;
+    }
+    validate_withdraw_order(e, assets, &target.asset, &strategies)?;
+
+    if total_bps != SCALAR_BPS {
+        return Err(ContractError::InvalidRatio);
+    }
+    Ok(())
+}
+
 // /// From an amount, calculates how much to withdraw from each strategy;
 // /// returns a map of strategy address to token amount
 // pub fn calculate_withdrawal_amounts(
@@ -131,11 +261,54 @@ pub fn calculate_asset_amounts_per_vault_shares(
     Ok(asset_amounts)
 }
 
+/// Calculates the number of vault shares that must be burned to receive at least `amounts` of each asset.
+///
+/// For every asset the required shares are rounded up, and the largest requirement among all assets is returned,
+/// so that burning the resulting shares always yields at least the requested amount of each asset.
+///
+/// # Errors
+/// * Returns `ContractError::WrongAmountsLength` if `amounts` does not match the number of assets.
+/// * Returns `ContractError::InsufficientManagedFunds` if an amount is requested for an asset the vault does not hold.
+/// * Returns `ContractError::ArithmeticError` if there are any issues with multiplication or division.
+pub fn calculate_vault_shares_for_asset_amounts(
+    total_managed_funds: &Vec<CurrentAssetInvestmentAllocation>,
+    amounts: &Vec<i128>,
+    total_shares_supply: i128,
+) -> Result<i128, ContractError> {
+    if amounts.len() != total_managed_funds.len() {
+        return Err(ContractError::WrongAmountsLength);
+    }
+
+    let mut shares: i128 = 0;
+    for (i, asset_allocation) in total_managed_funds.iter().enumerate() {
+        let amount = amounts.get(i as u32).unwrap();
+        validate_amount(amount)?;
+        if amount == 0 {
+            continue;
+        }
+        if asset_allocation.total_amount < amount || total_shares_supply == 0 {
+            return Err(ContractError::InsufficientManagedFunds);
+        }
+        let asset_shares = divide_rounding_up(
+            amount
+                .checked_mul(total_shares_supply)
+                .ok_or(ContractError::ArithmeticError)?,
+            asset_allocation.total_amount,
+        )?;
+        if asset_shares > shares {
+            shares = asset_shares;
+        }
+    }
+
+    Ok(shares)
+}
+
 pub fn calculate_optimal_amounts_and_shares_with_enforced_asset(
     e: &Env,
     total_managed_funds: &Vec<CurrentAssetInvestmentAllocation>,
     amounts_desired: &Vec<i128>,
     enforced_asset_index: u32,
+    total_supply: i128,
 ) -> (Vec<i128>, i128) {
     // Reserve (total managed funds) of the enforced asset
     let reserve_target = total_managed_funds
@@ -174,7 +347,7 @@ pub fn calculate_optimal_amounts_and_shares_with_enforced_asset(
     }
 
     // Calculate shares to mint = (total_supply * amount_desired_target) / reserve_target
-    let shares_to_mint = VaultToken::total_supply(e.clone())
+    let shares_to_mint = total_supply
         .checked_mul(amount_desired_target)
         .unwrap_or_else(|| panic_with_error!(e, ContractError::ArithmeticError))
         .checked_div(reserve_target)
@@ -188,6 +361,7 @@ pub fn calculate_deposit_amounts_and_shares_to_mint(
     total_managed_funds: &Vec<CurrentAssetInvestmentAllocation>,
     amounts_desired: &Vec<i128>,
     amounts_min: &Vec<i128>,
+    total_supply: i128,
 ) -> Result<(Vec<i128>, i128), ContractError> {
     for i in 0..total_managed_funds.len() {
         // Skip zero balance assets
@@ -201,6 +375,7 @@ pub fn calculate_deposit_amounts_and_shares_to_mint(
                 total_managed_funds,
                 amounts_desired,
                 i,
+                total_supply,
             );
 
         let mut should_skip = false;
@@ -0,0 +1,353 @@
+use soroban_sdk::{panic_with_error, token::TokenClient, Address, Env, Vec};
+
+use crate::{
+    events,
+    funds::fetch_total_managed_funds,
+    models::{CurrentAssetInvestmentAllocation, WithdrawRequest},
+    router::swap_in_current_ledger,
+    storage::{
+        get_assets, get_idle_reserve, get_next_withdraw_request_id, get_withdraw_order, get_withdraw_queue_head,
+        get_withdraw_request, has_withdraw_request, remove_withdraw_request, set_withdraw_queue_head,
+        update_report_prev_balance,
+    },
+    strategies::unwind_from_strategy,
+    token::{internal_burn, internal_transfer, VaultToken},
+    utils::calculate_idle_reserve_deficit,
+    ContractError, VaultError,
+};
+
+/// Calculates the amount of each asset corresponding to `withdraw_shares`, proportionally to the
+/// total managed funds of the vault.
+pub fn calculate_withdraw_amounts(
+    e: &Env,
+    total_managed_funds: &Vec<CurrentAssetInvestmentAllocation>,
+    withdraw_shares: i128,
+    total_shares_supply: i128,
+) -> Result<Vec<i128>, ContractError> {
+    let mut amounts: Vec<i128> = Vec::new(e);
+    for asset in total_managed_funds.iter() {
+        let amount = asset
+            .total_amount
+            .checked_mul(withdraw_shares)
+            .ok_or(ContractError::ArithmeticError)?
+            .checked_div(total_shares_supply)
+            .ok_or(ContractError::ArithmeticError)?;
+        amounts.push_back(amount);
+    }
+    Ok(amounts)
+}
+
+/// Common logic for processing withdrawals.
+///
+/// For every asset, the requested amount is taken from idle funds first and the remainder is
+/// unwound from the asset strategies, following the asset withdraw order if the Manager has set one,
+/// or proportionally to their invested amounts otherwise. Funds are sent to `to`.
+/// Withdrawals served from idle funds never touch the strategies; when strategies have to be unwound,
+/// enough is unwound to also refill the idle reserve of the asset.
+/// When `to` is the vault itself, idle funds stay where they are and unwound funds become idle.
+pub fn process_withdraw(
+    e: &Env,
+    total_managed_funds: &Vec<CurrentAssetInvestmentAllocation>,
+    amounts: &Vec<i128>,
+    to: &Address,
+) -> Result<Vec<i128>, ContractError> {
+    if amounts.len() != total_managed_funds.len() {
+        return Err(ContractError::WrongAmountsLength);
+    }
+    let to_vault = to == &e.current_contract_address();
+
+    let mut withdrawn_amounts: Vec<i128> = Vec::new(e);
+
+    // Loop through each asset to handle the withdrawal
+    for (i, asset) in total_managed_funds.iter().enumerate() {
+        let asset_address = &asset.asset;
+        let requested_withdrawal_amount = amounts.get(i as u32).unwrap();
+
+        if requested_withdrawal_amount <= 0 {
+            // Push zero to 'withdrawn_amounts' to indicate no withdrawal for this asset
+            withdrawn_amounts.push_back(0);
+            continue;
+        }
+
+        let idle_funds = asset.idle_amount;
+        if idle_funds >= requested_withdrawal_amount {
+            if !to_vault {
+                TokenClient::new(e, asset_address).transfer(
+                    &e.current_contract_address(),
+                    to,
+                    &requested_withdrawal_amount,
+                );
+            }
+            withdrawn_amounts.push_back(requested_withdrawal_amount);
+            continue;
+        }
+
+        let shortfall = requested_withdrawal_amount
+            .checked_sub(idle_funds)
+            .ok_or(ContractError::Underflow)?;
+
+        // Strategies are being unwound anyway, so refill the idle reserve of the asset in the same call.
+        // The reserve is measured against the asset's total managed funds after the withdrawal.
+        let reserve_top_up = calculate_idle_reserve_deficit(
+            asset
+                .total_amount
+                .checked_sub(requested_withdrawal_amount)
+                .ok_or(ContractError::Underflow)?,
+            0,
+            get_idle_reserve(e, asset_address),
+        )?
+        .min(
+            asset
+                .invested_amount
+                .checked_sub(shortfall)
+                .ok_or(ContractError::Underflow)?
+                .max(0),
+        );
+        let remaining_amount_to_unwind = shortfall
+            .checked_add(reserve_top_up)
+            .ok_or(ContractError::Overflow)?;
+
+        // When topping up the reserve, unwound funds go through the vault before reaching `to`
+        let unwind_to = if reserve_top_up > 0 { e.current_contract_address() } else { to.clone() };
+
+        if idle_funds != 0 && !to_vault && reserve_top_up == 0 {
+            TokenClient::new(e, asset_address).transfer(
+                &e.current_contract_address(),
+                to,
+                &idle_funds,
+            );
+        }
+        let mut cumulative_unwound_amount: i128 = 0;
+        let unwind_amounts = calculate_strategy_unwind_amounts(e, &asset, remaining_amount_to_unwind)?;
+        for (strategy_address, strategy_amount_to_unwind) in unwind_amounts.iter() {
+            if strategy_amount_to_unwind > 0 {
+                let remaining_balance = unwind_from_strategy(
+                    e,
+                    &strategy_address,
+                    &strategy_amount_to_unwind,
+                    &unwind_to,
+                )?;
+                update_report_prev_balance(e, &strategy_address, remaining_balance);
+                cumulative_unwound_amount = cumulative_unwound_amount
+                    .checked_add(strategy_amount_to_unwind)
+                    .ok_or(ContractError::Overflow)?;
+            }
+        }
+
+        if reserve_top_up > 0 && !to_vault {
+            TokenClient::new(e, asset_address).transfer(
+                &e.current_contract_address(),
+                to,
+                &requested_withdrawal_amount,
+            );
+        }
+        let cumulative_amount_for_asset = idle_funds
+            .checked_add(cumulative_unwound_amount)
+            .ok_or(ContractError::Overflow)?
+            .checked_sub(reserve_top_up)
+            .ok_or(ContractError::Underflow)?;
+        withdrawn_amounts.push_back(cumulative_amount_for_asset);
+    }
+
+    Ok(withdrawn_amounts)
+}
+
+/// Splits `amount_to_unwind` across the strategies of an asset.
+///
+/// If the Manager has set a withdraw order for the asset, strategies are drained one after the other in that
+/// order, followed by the strategies not listed in it. Otherwise, the amount is split proportionally to the
+/// invested amounts. In both cases, whatever is left is taken from the last strategy.
+fn calculate_strategy_unwind_amounts(
+    e: &Env,
+    asset: &CurrentAssetInvestmentAllocation,
+    amount_to_unwind: i128,
+) -> Result<Vec<(Address, i128)>, ContractError> {
+    let mut unwind_amounts: Vec<(Address, i128)> = Vec::new(e);
+    let strategies_count = asset.strategy_allocations.len();
+    let withdraw_order = get_withdraw_order(e, &asset.asset);
+
+    if withdraw_order.is_empty() {
+        let mut cumulative_amount: i128 = 0;
+        for (i, strategy_allocation) in asset.strategy_allocations.iter().enumerate() {
+            // If the current strategy is the last one, unwind the remaining amount
+            let strategy_amount_to_unwind: i128 =
+                if i == strategies_count.saturating_sub(1) as usize {
+                    amount_to_unwind
+                        .checked_sub(cumulative_amount)
+                        .ok_or(ContractError::Underflow)?
+                } else {
+                    // Calculate the proportional amount to unwind from this strategy
+                    amount_to_unwind
+                        .checked_mul(strategy_allocation.amount)
+                        .and_then(|result| result.checked_div(asset.invested_amount))
+                        .unwrap_or(0)
+                };
+            cumulative_amount = cumulative_amount
+                .checked_add(strategy_amount_to_unwind)
+                .ok_or(ContractError::Overflow)?;
+            unwind_amounts.push_back((strategy_allocation.strategy_address, strategy_amount_to_unwind));
+        }
+        return Ok(unwind_amounts);
+    }
+
+    // Ordered strategies first, then the ones not listed in the withdraw order
+    let mut ordered_allocations = Vec::new(e);
+    for strategy_address in withdraw_order.iter() {
+        if let Some(strategy_allocation) = asset
+            .strategy_allocations
+            .iter()
+            .find(|allocation| allocation.strategy_address == strategy_address)
+        {
+            ordered_allocations.push_back(strategy_allocation);
+        }
+    }
+    for strategy_allocation in asset.strategy_allocations.iter() {
+        if !withdraw_order.contains(&strategy_allocation.strategy_address) {
+            ordered_allocations.push_back(strategy_allocation);
+        }
+    }
+
+    let mut remaining_amount = amount_to_unwind;
+    for (i, strategy_allocation) in ordered_allocations.iter().enumerate() {
+        if remaining_amount <= 0 {
+            break;
+        }
+        let strategy_amount_to_unwind = if i == strategies_count.saturating_sub(1) as usize {
+            remaining_amount
+        } else {
+            remaining_amount.min(strategy_allocation.amount)
+        };
+        remaining_amount = remaining_amount
+            .checked_sub(strategy_amount_to_unwind)
+            .ok_or(ContractError::Underflow)?;
+        unwind_amounts.push_back((strategy_allocation.strategy_address, strategy_amount_to_unwind));
+    }
+    Ok(unwind_amounts)
+}
+
+/// Swaps the withdrawn amounts held by the vault into `out_asset` through Soroswap and transfers the
+/// resulting amount of `out_asset` to `to`.
+///
+/// The withdrawn amounts must already be idle in the vault (see `process_withdraw`). Returns
+/// `ContractError::InsufficientOutputAmount` if the total amount of `out_asset` is lower than `min_amount_out`,
+/// otherwise the total amount of `out_asset` sent to `to`.
+pub fn process_withdraw_to_asset(
+    e: &Env,
+    withdrawn_amounts: &Vec<i128>,
+    out_asset: &Address,
+    min_amount_out: i128,
+    to: &Address,
+) -> Result<i128, ContractError> {
+    let assets = get_assets(e)?;
+    let out_index = assets
+        .iter()
+        .position(|asset| &asset.address == out_asset)
+        .ok_or(ContractError::UnsupportedAsset)?;
+
+    let mut amount_out = withdrawn_amounts.get(out_index as u32).unwrap_or(0);
+    for (i, asset) in assets.iter().enumerate() {
+        let amount_in = withdrawn_amounts.get(i as u32).unwrap_or(0);
+        if i == out_index || amount_in == 0 {
+            continue;
+        }
+        // The total output is checked against `min_amount_out` below
+        amount_out = amount_out
+            .checked_add(swap_in_current_ledger(e, &asset.address, out_asset, &amount_in)?)
+            .ok_or(ContractError::Overflow)?;
+    }
+    if amount_out < min_amount_out {
+        return Err(ContractError::InsufficientOutputAmount);
+    }
+
+    if amount_out > 0 {
+        TokenClient::new(e, out_asset).transfer(&e.current_contract_address(), to, &amount_out);
+    }
+    Ok(amount_out)
+}
+
+/// Fails with `VaultError::WithdrawRequestNotFirst` while the withdrawal queue has requests that can be
+/// claimed, so that direct withdrawals cannot take the liquidity the queued requests are waiting for.
+///
+/// Cancelled requests are skipped when the head moves, and a head that cannot complete can be parked
+/// with `park_withdraw_request`, so a single failing request does not block every withdrawal.
+pub fn require_withdraw_queue_empty(e: &Env) {
+    if get_withdraw_queue_head(e) < get_next_withdraw_request_id(e) {
+        panic_with_error!(e, VaultError::WithdrawRequestNotFirst);
+    }
+}
+
+/// Completes the withdrawal request at the head of the withdrawal queue.
+///
+/// The escrowed shares are burned and the corresponding amounts, valued at the time of the claim,
+/// are sent to the owner of the request. The queue head then moves to the next request.
+/// Returns the owner of the request and the withdrawn amounts.
+pub fn process_withdraw_request(
+    e: &Env,
+    request_id: u64,
+) -> Result<(Address, Vec<i128>), ContractError> {
+    let request = get_withdraw_request(e, request_id);
+    if request_id != get_withdraw_queue_head(e) {
+        panic_with_error!(e, VaultError::WithdrawRequestNotFirst);
+    }
+
+    // Locks new fees, as a regular withdrawal does
+    let total_managed_funds = fetch_total_managed_funds(e, true)?;
+    let total_shares_supply = VaultToken::total_supply(e.clone());
+
+    internal_burn(e.clone(), e.current_contract_address(), request.shares);
+
+    let amounts = calculate_withdraw_amounts(e, &total_managed_funds, request.shares, total_shares_supply)?;
+    let withdrawn_amounts = process_withdraw(e, &total_managed_funds, &amounts, &request.owner)?;
+
+    remove_withdraw_request(e, request_id);
+    advance_withdraw_queue_head(e, request_id);
+
+    events::emit_withdraw_event(
+        e,
+        request.owner.clone(),
+        request.shares,
+        withdrawn_amounts.clone(),
+        total_shares_supply,
+        total_managed_funds,
+    );
+
+    Ok((request.owner, withdrawn_amounts))
+}
+
+/// Cancels a withdrawal request, either queued or parked, and returns the escrowed shares to its owner.
+pub fn cancel_withdraw_request(e: &Env, request_id: u64) -> Result<WithdrawRequest, ContractError> {
+    let request = get_withdraw_request(e, request_id);
+    request.owner.require_auth();
+
+    remove_withdraw_request(e, request_id);
+    internal_transfer(e.clone(), e.current_contract_address(), request.owner.clone(), request.shares);
+
+    if request_id == get_withdraw_queue_head(e) {
+        advance_withdraw_queue_head(e, request_id);
+    }
+    Ok(request)
+}
+
+/// Moves the request at the head of the withdrawal queue out of the queue without completing it.
+///
+/// The request stays stored with its escrowed shares, but can no longer be claimed. Its owner can
+/// still cancel it to recover the shares.
+pub fn park_withdraw_request(e: &Env, request_id: u64) -> Result<WithdrawRequest, ContractError> {
+    let request = get_withdraw_request(e, request_id);
+    if request_id != get_withdraw_queue_head(e) {
+        panic_with_error!(e, VaultError::WithdrawRequestNotFirst);
+    }
+
+    advance_withdraw_queue_head(e, request_id);
+    Ok(request)
+}
+
+/// Moves the queue head past `request_id`, skipping the requests that have been cancelled meanwhile.
+fn advance_withdraw_queue_head(e: &Env, request_id: u64) {
+    let next_request_id = get_next_withdraw_request_id(e);
+    let mut head = request_id + 1;
+    while head < next_request_id && !has_withdraw_request(e, head) {
+        head += 1;
+    }
+    set_withdraw_queue_head(e, &head);
+}